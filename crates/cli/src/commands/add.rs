@@ -0,0 +1,346 @@
+use chrono::Local;
+use core::conversion::Prices;
+use core::formatter;
+use core::io::file;
+use core::journal::{self, CheckError};
+use core::parser::{
+    self,
+    ast::{Amount, ParsedDate, Posting, Transaction},
+};
+use core::template;
+use dialoguer::{Completion, Confirm, Input};
+use seahorse::{Command, Context, Flag, FlagType};
+
+use crate::emoji;
+use crate::io::{self, show_error, show_info, show_success};
+
+use super::{read_journal, show_io_error};
+
+/// Creates a command that asks for the details of a transaction and appends it to a journal.
+/// Payees and accounts are completed with the ones already in the journal by pressing tab, and the
+/// last transaction of the payee is proposed as a template. With `--from-last` the last transaction
+/// of the payee is copied without asking anything.
+pub fn create() -> Command {
+    Command::new("add")
+        .alias("a")
+        .usage("[file path] Asks for a transaction and appends it to the given journal")
+        .flag(
+            Flag::new("from-last", FlagType::String)
+                .description("Copies the last transaction of the given payee without asking"),
+        )
+        .flag(
+            Flag::new("date", FlagType::String)
+                .alias("d")
+                .description("Date of the copied transaction, today by default"),
+        )
+        .flag(Flag::new("amount", FlagType::String).description(
+            "Amount of the copied transaction, the one of the last transaction by default",
+        ))
+        .action(handler)
+}
+
+/// Completes the input with the first candidate that starts with it, ignoring case.
+struct Candidates(Vec<String>);
+
+impl Completion for Candidates {
+    fn get(&self, input: &str) -> Option<String> {
+        let input = input.to_lowercase();
+
+        self.0
+            .iter()
+            .find(|candidate| {
+                candidate.len() > input.len() && candidate.to_lowercase().starts_with(&input)
+            })
+            .cloned()
+    }
+}
+
+fn handler(context: &Context) {
+    let path = match context.args.first() {
+        Some(path) => path,
+        None => return io::show_error(emoji::for_error(), "No file given"),
+    };
+    let journal = match read_journal(path) {
+        Ok(journal) => journal,
+        Err(_) => std::process::exit(1),
+    };
+
+    let payees = Candidates(journal::payees(&journal));
+    let accounts = Candidates(journal::accounts(&journal));
+    let prices = Prices::from_journal(&journal);
+    let transactions = journal::resolve(journal);
+    let interactive = context.string_flag("from-last").is_err();
+
+    let transaction = match context.string_flag("from-last") {
+        Ok(payee) => copy_last_transaction(context, &transactions, &payee),
+        Err(_) => ask_transaction(&payees, &accounts, &transactions).unwrap_or_else(|err| {
+            show_error(
+                emoji::for_error(),
+                format!("Could not read the input: {}", err),
+            );
+            std::process::exit(1);
+        }),
+    };
+
+    if let Err(error) = journal::check_transaction(&transaction, &prices) {
+        show_error(
+            emoji::for_error(),
+            match error {
+                CheckError::TransactionsWithoutValue(_) => {
+                    "The transaction has no values associated with it".to_owned()
+                }
+                CheckError::UnbalancedTransactions(transactions) => format!(
+                    "The transaction is unbalanced. Difference between postings: {}",
+                    transactions
+                        .first()
+                        .map(|transaction| transaction.difference)
+                        .unwrap_or_default()
+                ),
+                CheckError::OverflowingTransactions(_) => {
+                    "The amounts of the transaction are too large to be computed".to_owned()
+                }
+                CheckError::NonParsable(err) => err.to_string(),
+            },
+        );
+        std::process::exit(1);
+    }
+
+    let formatted_transaction = formatter::format_transaction(&transaction);
+    println!("\n{}", formatted_transaction);
+
+    if !interactive {
+        return append_transaction(path, &formatted_transaction);
+    }
+
+    match Confirm::new()
+        .with_prompt("Add the transaction to the journal?")
+        .default(true)
+        .interact()
+    {
+        Ok(true) => append_transaction(path, &formatted_transaction),
+        Ok(false) => {}
+        Err(err) => show_error(
+            emoji::for_error(),
+            format!("Could not read the input: {}", err),
+        ),
+    }
+}
+
+/// Copies the last transaction of the payee with the date and amount given in the flags, exiting
+/// if the payee has no transactions or the flags are not valid.
+fn copy_last_transaction(
+    context: &Context,
+    transactions: &[Transaction],
+    payee: &str,
+) -> Transaction {
+    let last_transaction = match template::find(transactions, payee) {
+        Some(transaction) => transaction,
+        None => {
+            show_error(
+                emoji::for_search(),
+                format!("There are no transactions of \"{}\"", payee),
+            );
+            std::process::exit(1);
+        }
+    };
+    let date = match context.string_flag("date") {
+        Ok(date) => parser::parse_date(&date).unwrap_or_else(|_| {
+            show_error(
+                emoji::for_error(),
+                format!("\"{}\" is not a valid date", date),
+            );
+            std::process::exit(1);
+        }),
+        Err(_) => today(),
+    };
+    let amount = context.string_flag("amount").ok().map(|amount| {
+        parse_amount_in_currency(&amount, last_transaction).unwrap_or_else(|| {
+            show_error(
+                emoji::for_error(),
+                format!("\"{}\" is not a valid amount", amount),
+            );
+            std::process::exit(1);
+        })
+    });
+
+    template::apply(last_transaction, date, amount)
+}
+
+/// Parses an amount, taking the currency of the first posting of the template if the amount
+/// only has a quantity.
+fn parse_amount_in_currency(amount: &str, template: &Transaction) -> Option<Amount> {
+    parser::parse_amount(amount).ok().or_else(|| {
+        let currency = template::apply(template, ParsedDate::default(), None)
+            .postings
+            .0
+            .amount?
+            .currency;
+        parser::parse_amount(&format!("{} {}", amount.trim(), currency)).ok()
+    })
+}
+
+fn today() -> ParsedDate {
+    ParsedDate::Full(Local::now().date_naive())
+}
+
+fn ask_transaction(
+    payees: &Candidates,
+    accounts: &Candidates,
+    transactions: &[Transaction],
+) -> std::io::Result<Transaction> {
+    let date = Input::<String>::new()
+        .with_prompt("Date")
+        .default(formatter::format_date(&today()))
+        .validate_with(|input: &String| parser::parse_date(input).map(|_| ()))
+        .interact_text()?;
+    let payee = Input::<String>::new()
+        .with_prompt("Payee")
+        .completion_with(payees)
+        .validate_with(|input: &String| only_allowed(input, |c| c.is_alphanumeric() || c == ' '))
+        .interact_text()?;
+
+    let template = template::find(transactions, &payee)
+        .map(|transaction| template::apply(transaction, transaction.date.clone(), None));
+    if let Some(template) = &template {
+        show_info(
+            emoji::for_search(),
+            format!(
+                "Proposing the transaction of {} as template",
+                formatter::format_date(&template.date)
+            ),
+        );
+    }
+
+    let description = Input::<String>::new()
+        .with_prompt("Description")
+        .with_initial_text(
+            template
+                .as_ref()
+                .map(|template| template.description.clone())
+                .unwrap_or_default(),
+        )
+        .allow_empty(true)
+        .validate_with(|input: &String| only_allowed(input, |c| c.is_alphanumeric() || c == ' '))
+        .interact_text()?;
+    let tags = Input::<String>::new()
+        .with_prompt("Tags (separated by commas)")
+        .with_initial_text(
+            template
+                .as_ref()
+                .map(|template| template.tags.join(", "))
+                .unwrap_or_default(),
+        )
+        .allow_empty(true)
+        .validate_with(|input: &String| {
+            only_allowed(input, |c| {
+                c.is_alphanumeric() || c == '-' || c == ',' || c == ' '
+            })
+        })
+        .interact_text()?;
+
+    let first_posting = ask_posting(
+        accounts,
+        template.as_ref().map(|template| &template.postings.0),
+        None,
+    )?;
+    let balancing_amount = first_posting.amount.as_ref().map(|amount| Amount {
+        quantity: -amount.quantity,
+        currency: amount.currency.clone(),
+    });
+    let second_posting = ask_posting(
+        accounts,
+        template.as_ref().map(|template| &template.postings.1),
+        balancing_amount,
+    )?;
+
+    Ok(Transaction {
+        date: parser::parse_date(&date).unwrap_or_default(),
+        payee: payee.trim().to_owned(),
+        description: description.trim().to_owned(),
+        tags: tags
+            .split(',')
+            .map(|tag| tag.trim().to_owned())
+            .filter(|tag| !tag.is_empty())
+            .collect(),
+        postings: (first_posting, second_posting),
+        ..Transaction::default()
+    })
+}
+
+/// Asks for the account and the amount of a posting, proposing the ones of the posting of the
+/// template if any. If a balancing amount is given, the amount can be left empty so that the
+/// posting takes it, as in any transaction of the journal.
+fn ask_posting(
+    accounts: &Candidates,
+    template: Option<&Posting>,
+    balancing_amount: Option<Amount>,
+) -> std::io::Result<Posting> {
+    let mut account_input = Input::<String>::new();
+    account_input
+        .with_prompt("Account")
+        .completion_with(accounts)
+        .validate_with(|input: &String| parser::parse_account(input).map(|_| ()));
+    if let Some(template) = template {
+        account_input.default(template.account.to_string());
+    }
+    let account = account_input.interact_text()?;
+
+    let mut amount_input = Input::<String>::new();
+    amount_input
+        .with_prompt(match &balancing_amount {
+            Some(amount) => format!("Amount (empty for {})", formatter::format_amount(amount)),
+            None => "Amount".to_owned(),
+        })
+        .allow_empty(balancing_amount.is_some())
+        .validate_with(|input: &String| match input.trim() {
+            "" if balancing_amount.is_some() => Ok(()),
+            input => parser::parse_amount(input).map(|_| ()),
+        });
+    if let Some(amount) = template.and_then(|template| template.amount.as_ref()) {
+        amount_input.default(formatter::format_amount(amount));
+    }
+    let amount = amount_input.interact_text()?;
+
+    Ok(Posting {
+        account: parser::parse_account(&account).unwrap_or_default(),
+        amount: parser::parse_amount(&amount).ok(),
+        lot: None,
+        price: None,
+    })
+}
+
+fn only_allowed(input: &str, is_allowed: impl Fn(char) -> bool) -> Result<(), String> {
+    match input.chars().find(|&c| !is_allowed(c)) {
+        Some(c) => Err(format!("\"{}\" is not allowed", c)),
+        None => Ok(()),
+    }
+}
+
+/// Appends the transaction to the journal, writing the whole journal again so that it is never
+/// left with half a transaction.
+fn append_transaction(path: &str, formatted_transaction: &str) {
+    let content = match file::read_content(path) {
+        Ok(content) => content,
+        Err(err) => {
+            show_io_error(err);
+            std::process::exit(1);
+        }
+    };
+    let separator = match content.as_str() {
+        "" => "",
+        content if content.ends_with("\n\n") => "",
+        content if content.ends_with('\n') => "\n",
+        _ => "\n\n",
+    };
+
+    match file::replace_content(
+        path,
+        &format!("{}{}{}", content, separator, formatted_transaction),
+    ) {
+        Ok(_) => show_success(emoji::for_success(), "The transaction was added"),
+        Err(err) => {
+            show_io_error(err);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use chrono::NaiveDate;
+use core::conversion::{self, Prices, Valuation};
+use core::journal;
+use core::report::balance::{self, BalanceReport};
+use seahorse::{Command, Context, Flag, FlagType};
+
+use crate::emoji;
+use crate::io;
+use crate::output::{self, Output};
+use crate::watch;
+
+use super::{currency_flag, read_date, read_journal, read_query, read_valuation, value_flag};
+
+/// Creates a command that shows the balance of the accounts of a journal at a date, optionally
+/// valuing it at cost or at market prices, and showing it again every time the journal changes.
+pub fn create() -> Command {
+    Command::new("balance")
+        .alias("b")
+        .usage("[file path] [query] Shows the balance of the accounts that match the query")
+        .flag(output::flag())
+        .flag(watch::flag())
+        .flag(value_flag())
+        .flag(
+            Flag::new("at", FlagType::String)
+                .description("Date of the balance as year-month-day, which also dates the prices"),
+        )
+        .flag(currency_flag("Currency of the market values"))
+        .action(handler)
+}
+
+fn handler(context: &Context) {
+    let path = match context.args.first() {
+        Some(path) => path,
+        None => return io::show_error(emoji::for_error(), "No file given"),
+    };
+    let output = match output::from_context(context) {
+        Some(output) => output,
+        None => std::process::exit(1),
+    };
+
+    let at = match read_date(context, "at") {
+        Ok(at) => at,
+        Err(_) => std::process::exit(1),
+    };
+    let valuation = match read_valuation(context, at) {
+        Ok(valuation) => valuation,
+        Err(_) => std::process::exit(1),
+    };
+
+    if !context.bool_flag("watch") {
+        if show_balance(path, &context.args[1..], at, &valuation, &output).is_err() {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Err(err) = watch::watch(path, || {
+        let _ = show_balance(path, &context.args[1..], at, &valuation, &output);
+    }) {
+        io::show_error(emoji::for_error(), err);
+        std::process::exit(1);
+    }
+}
+
+fn show_balance(
+    path: &str,
+    query: &[String],
+    at: Option<NaiveDate>,
+    valuation: &Option<Valuation>,
+    output: &Output,
+) -> Result<(), ()> {
+    let (journal, query) =
+        read_journal(path).and_then(|journal| read_query(query).map(|query| (journal, query)))?;
+    let prices = Prices::from_journal(&journal);
+    let mut transactions = journal::resolve(journal);
+
+    if let Some(at) = at {
+        transactions.retain(|transaction| transaction.date.date() <= at);
+    }
+    if let Some(valuation) = valuation {
+        transactions = conversion::value(&transactions, valuation, &prices)
+            .map_err(|err| io::show_error(emoji::for_error(), err.to_string()))?;
+    }
+
+    let report = balance::report(&transactions, &query)
+        .map_err(|err| io::show_error(emoji::for_error(), err.to_string()))?;
+
+    match output {
+        Output::Text => show_report(&report),
+        Output::Json => output::print_json(&report),
+        Output::Csv => output::print_csv(&report.rows),
+    }
+    Ok(())
+}
+
+fn show_report(report: &BalanceReport) {
+    let width = report
+        .rows
+        .iter()
+        .map(|row| row.quantity.len() + row.currency.len() + 1)
+        .chain(
+            report
+                .totals
+                .iter()
+                .map(|total| total.quantity.len() + total.currency.len() + 1),
+        )
+        .max()
+        .unwrap_or_default();
+
+    for row in &report.rows {
+        let depth = row.account.matches(':').count();
+        let name = row.account.rsplit(':').next().unwrap_or_default();
+
+        println!(
+            "{:>width$}  {}{}",
+            format!("{} {}", row.quantity, row.currency),
+            "  ".repeat(depth),
+            name,
+            width = width
+        );
+    }
+
+    println!("{}", "-".repeat(width));
+    for total in &report.totals {
+        println!(
+            "{:>width$}",
+            format!("{} {}", total.quantity, total.currency),
+            width = width
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use console::Term;
+use core::chart::{self, svg, terminal, Interval};
+use core::conversion::Prices;
+use core::io::file;
+use core::journal;
+use seahorse::{Command, Context, Flag, FlagType};
+
+use crate::emoji;
+use crate::io::{self, show_error, show_success};
+
+use super::{currency_flag, read_currency, read_journal, read_query};
+
+/// Creates a command that charts the postings that match a query in every month or year, as
+/// stacked bars of what was spent or earned, or as lines of the balance over time. Charts are drawn
+/// in the terminal or written as an SVG file.
+pub fn create() -> Command {
+    Command::new("chart")
+        .usage("[file path] [query] Charts the postings that match the query over time")
+        .flag(
+            Flag::new("monthly", FlagType::Bool)
+                .alias("m")
+                .description("Groups the postings by month instead of by year"),
+        )
+        .flag(
+            Flag::new("by-account", FlagType::Bool)
+                .alias("a")
+                .description("Charts every account separately"),
+        )
+        .flag(
+            Flag::new("line", FlagType::Bool)
+                .alias("l")
+                .description("Charts the balance over time as lines instead of bars"),
+        )
+        .flag(
+            Flag::new("svg", FlagType::String)
+                .description("Writes the chart as an SVG file to the given path"),
+        )
+        .flag(currency_flag("Currency of the values"))
+        .action(handler)
+}
+
+fn handler(context: &Context) {
+    let path = match context.args.first() {
+        Some(path) => path,
+        None => return io::show_error(emoji::for_error(), "No file given"),
+    };
+    let (journal, query) = match read_journal(path)
+        .and_then(|journal| read_query(&context.args[1..]).map(|query| (journal, query)))
+    {
+        Ok(result) => result,
+        Err(_) => std::process::exit(1),
+    };
+    let currency = read_currency(context);
+    let interval = if context.bool_flag("monthly") {
+        Interval::Month
+    } else {
+        Interval::Year
+    };
+    let line = context.bool_flag("line");
+
+    let prices = Prices::from_journal(&journal);
+    let transactions = journal::resolve(journal);
+    let values = if line { chart::balances } else { chart::flows };
+    let chart = match values(
+        &transactions,
+        &query,
+        &prices,
+        &currency,
+        interval,
+        context.bool_flag("by-account"),
+    ) {
+        Ok(chart) => chart,
+        Err(err) => {
+            show_error(emoji::for_error(), err.to_string());
+            std::process::exit(1);
+        }
+    };
+
+    if !chart.unpriced.is_empty() {
+        eprintln!(
+            "{}",
+            io::text_for_info(
+                emoji::for_warning(),
+                format!(
+                    "Amounts in {} were left out because they have no price in {}",
+                    chart.unpriced.join(", "),
+                    currency
+                ),
+            )
+        );
+    }
+
+    if chart.periods.is_empty() {
+        return show_error(emoji::for_search(), "No postings match the query");
+    }
+
+    match context.string_flag("svg") {
+        Ok(svg_path) => {
+            let content = if line {
+                svg::line_chart(&chart)
+            } else {
+                svg::bar_chart(&chart)
+            };
+
+            match file::replace_content(&svg_path, &content) {
+                Ok(_) => show_success(
+                    emoji::for_success(),
+                    format!("Chart written to \"{}\"", svg_path),
+                ),
+                Err(_) => {
+                    show_error(
+                        emoji::for_error(),
+                        format!("The chart could not be written to \"{}\"", svg_path),
+                    );
+                    std::process::exit(1);
+                }
+            }
+        }
+        Err(_) => {
+            let (rows, columns) = Term::stdout().size();
+
+            if line {
+                println!(
+                    "{}",
+                    terminal::line_chart(&chart, columns as usize, (rows as usize / 2).max(8))
+                );
+            } else {
+                println!("{}", terminal::bar_chart(&chart, columns as usize));
+            }
+        }
+    }
+}
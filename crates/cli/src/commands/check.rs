@@ -1,98 +1,222 @@
-use core::io::{file, file::FileError};
-use core::journal::{self, CheckError};
-use seahorse::{Command, Context};
+use core::io::file;
+use core::journal::{self, Diagnostic, DiagnosticCode};
+use core::report::check::{self, CheckMessage, CheckReport, Severity};
+use seahorse::{Command, Context, Flag, FlagType};
 
 use crate::emoji;
-use crate::io::{self, show_error, show_success};
+use crate::io::{self, show_error, show_info, show_success};
+use crate::output::{self, Output};
+use crate::watch;
+
+/// Exit code used when the journal was parsed but is not valid.
+const VALIDATION_FAILURE: i32 = 1;
+/// Exit code used when the journal could not be parsed.
+const PARSE_FAILURE: i32 = 2;
+/// Exit code used when the journal could not be read.
+const IO_FAILURE: i32 = 3;
+/// Exit code used when the command was not used correctly, like without a file or with an unknown
+/// output.
+const USAGE_FAILURE: i32 = 4;
+
+/// Defines the formats in which the check can show its result. Besides the outputs shared with
+/// other commands, the check can be shown as `file:line:column: severity: message` lines, which
+/// GitHub and most editors turn into annotations.
+enum Format {
+    Output(Output),
+    Github,
+}
 
 /// Creates a command that attempts to parse a given journal file and shows the result of the parsing.
+/// The command exits with 1 if the journal is not valid, 2 if it could not be parsed, 3 if it could
+/// not be read and 4 if no file or an unknown output was given. When watching, the check runs again every time the
+/// journal changes and shows which problems appeared or disappeared.
 pub fn create() -> Command {
     Command::new("check")
         .alias("c")
         .usage("[file path] Checks that the given journal file is valid")
+        .flag(
+            Flag::new("output", FlagType::String)
+                .alias("o")
+                .description("Format of the output (text, json, csv or github), text by default"),
+        )
+        .flag(Flag::new("format", FlagType::String).description("Same as --output"))
+        .flag(watch::flag())
         .action(handler)
 }
 
 fn handler(context: &Context) {
-    if let Some(file_path) = context.args.first() {
-        check_file_path(file_path);
-    } else {
-        io::show_error(emoji::for_error(), "No file given");
+    let format = match context
+        .string_flag("output")
+        .or_else(|_| context.string_flag("format"))
+        .as_deref()
+    {
+        Ok("text") | Err(_) => Format::Output(Output::Text),
+        Ok("json") => Format::Output(Output::Json),
+        Ok("csv") => Format::Output(Output::Csv),
+        Ok("github") => Format::Github,
+        Ok(output) => {
+            show_error(
+                emoji::for_error(),
+                format!(
+                    "Unknown output \"{}\", use text, json, csv or github",
+                    output
+                ),
+            );
+            std::process::exit(USAGE_FAILURE)
+        }
+    };
+
+    let path = match context.args.first() {
+        Some(path) => path,
+        None => {
+            show_error(emoji::for_error(), "No file given");
+            std::process::exit(USAGE_FAILURE)
+        }
+    };
+
+    if !context.bool_flag("watch") {
+        match check_file_path(path, &format) {
+            (0, _) => return,
+            (exit_code, _) => std::process::exit(exit_code),
+        }
+    }
+
+    let mut previous_diagnostics = None;
+    let result = watch::watch(path, || {
+        if let (_, Some(diagnostics)) = check_file_path(path, &format) {
+            show_changes(&format, &mut previous_diagnostics, diagnostics);
+        }
+    });
+
+    if let Err(err) = result {
+        show_error(emoji::for_error(), err);
+        std::process::exit(IO_FAILURE);
     }
 }
 
-fn check_file_path(path: &str) {
+/// Checks the journal in the given path and returns the code with which the command should exit,
+/// together with the problems found if the journal could be read.
+fn check_file_path(path: &str, format: &Format) -> (i32, Option<Vec<Diagnostic>>) {
     let content = file::read_content(path);
-    match content {
-        Ok(content) => check_content(content),
-        Err(error) => match error {
-            FileError::NotFound => {
-                show_error(emoji::for_search(), format!("File \"{}\" not found", path))
-            }
-            FileError::Unknown => {
-                show_error(emoji::for_error(), "Unknown error while reading the file");
-            }
-        },
+    let text = match content {
+        Ok(content) => {
+            let diagnostics = journal::diagnose_with(&content, path, &mut file::read_content);
+            return (check_content(path, &diagnostics, format), Some(diagnostics));
+        }
+        Err(err) if err.is_not_found() => io::text_for_error(emoji::for_search(), err.to_string()),
+        Err(err) => io::text_for_error(emoji::for_error(), err.to_string()),
+    };
+
+    match format {
+        Format::Output(Output::Text) => println!("{}", text),
+        _ => eprintln!("{}", text),
     }
+    (IO_FAILURE, None)
 }
 
-fn check_content(content: String) {
-    match journal::validate(&content) {
-        Ok(_) => {
-            show_success(emoji::for_success(), "The given journal is a valid file");
-        }
-        Err(error) => {
-            show_validate_error(error);
-            std::process::exit(1);
+fn check_content(path: &str, diagnostics: &[Diagnostic], format: &Format) -> i32 {
+    let report = check::report(diagnostics);
+
+    match format {
+        Format::Output(Output::Text) => show_report(&report),
+        Format::Output(Output::Json) => output::print_json(&report),
+        Format::Output(Output::Csv) => output::print_csv(&check::rows(&report)),
+        Format::Github => report
+            .messages
+            .iter()
+            .for_each(|message| show_annotation(path, message)),
+    }
+
+    if diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.code == DiagnosticCode::NonParsable)
+    {
+        PARSE_FAILURE
+    } else if !report.valid {
+        VALIDATION_FAILURE
+    } else {
+        0
+    }
+}
+
+/// Shows whether the journal is valid followed by every problem found with its line.
+fn show_report(report: &CheckReport) {
+    if report.valid {
+        show_success(emoji::for_success(), "The given journal is a valid file");
+    }
+
+    for message in &report.messages {
+        let text = format!(
+            "{} {} ({})",
+            message.code,
+            message.message,
+            location(message.path.as_deref(), message.line)
+        );
+
+        match message.severity {
+            Severity::Error => show_error(emoji::for_error(), text),
+            Severity::Warning => show_info(emoji::for_warning(), text),
         }
     }
 }
 
-fn show_validate_error(error: CheckError) {
-    match error {
-        CheckError::NonParsable(err) => show_error(
-            emoji::for_error(),
-            format!("There was an error parsing the journal:\n{}", err),
-        ),
-        CheckError::TransactionsWithoutValue(transactions) => {
-            show_error(
-                emoji::for_error(),
-                "The following transactions have no values associated with them".to_owned(),
-            );
-            show_error(
-                emoji::for_error(),
-                transactions
-                    .into_iter()
-                    .map(|transaction| {
-                        format!(
-                            "- Payee: {}, date: {:?}",
-                            transaction.payee, transaction.date
-                        )
-                    })
-                    .collect::<Vec<String>>()
-                    .join("\n"),
-            );
+/// Shows the problems that appeared or disappeared since the previous run of the check. They are
+/// shown in the standard error unless the output is text, so that the output can still be parsed.
+fn show_changes(
+    format: &Format,
+    previous_diagnostics: &mut Option<Vec<Diagnostic>>,
+    diagnostics: Vec<Diagnostic>,
+) {
+    let show = |text: String| match format {
+        Format::Output(Output::Text) => println!("{}", text),
+        _ => eprintln!("{}", text),
+    };
+
+    if let Some(previous_diagnostics) = previous_diagnostics {
+        let changes = journal::compare_diagnostics(previous_diagnostics, &diagnostics);
+
+        for diagnostic in changes.appeared {
+            show(io::text_for_error(
+                emoji::for_warning(),
+                format!(
+                    "+ {} {} ({})",
+                    diagnostic.code.code(),
+                    diagnostic.message,
+                    location(diagnostic.path.as_deref(), diagnostic.span.start.line)
+                ),
+            ));
         }
-        CheckError::UnbalancedTransactions(transactions) => {
-            show_error(
-                emoji::for_error(),
-                "The following transactions are unbalanced".to_owned(),
-            );
-            show_error(
-                emoji::for_error(),
-                transactions
-                    .into_iter()
-                    .map(|unbalanced_transaction| {
-                        format!(
-                            "- Payee: {}, date: {}. Difference between postings: {}",
-                            unbalanced_transaction.transaction.payee,
-                            unbalanced_transaction.transaction.date,
-                            unbalanced_transaction.difference
-                        )
-                    })
-                    .collect::<Vec<String>>()
-                    .join("\n"),
-            );
+        for diagnostic in changes.disappeared {
+            show(io::text_for_success(
+                emoji::for_success(),
+                format!("- {} {}", diagnostic.code.code(), diagnostic.message),
+            ));
         }
     }
+
+    *previous_diagnostics = Some(diagnostics);
+}
+
+/// Describes the line where a problem was found, with the path of the included journal if it was
+/// found in one.
+fn location(path: Option<&str>, line: usize) -> String {
+    match path {
+        Some(path) => format!("line {} of {}", line, path),
+        None => format!("line {}", line),
+    }
+}
+
+fn show_annotation(path: &str, message: &CheckMessage) {
+    println!(
+        "{}:{}:{}: {}: {} {}",
+        message.path.as_deref().unwrap_or(path),
+        message.line,
+        message.column,
+        match message.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        },
+        message.code,
+        message.message
+    )
 }
@@ -0,0 +1,95 @@
+use std::convert::TryFrom;
+
+use chrono::NaiveDate;
+use core::close;
+use core::formatter;
+use core::journal;
+use core::parser::ast::{JournalElement, Transaction};
+use seahorse::{Command, Context, Flag, FlagType};
+
+use crate::emoji;
+use crate::io::show_error;
+
+use super::gains::show_lot_error;
+use super::{booking_flag, read_booking, read_journal};
+
+/// Creates a command that prints the transactions that close a year of a journal and open the next
+/// one. The closing transactions are meant to be appended to the journal of the closed year and
+/// the opening transactions to start the journal of the next year, keeping the lots that are held.
+pub fn create() -> Command {
+    Command::new("close")
+        .usage("[file path] --year [year] Prints the transactions that close the given year")
+        .flag(
+            Flag::new("year", FlagType::Int)
+                .alias("y")
+                .description("Year to close"),
+        )
+        .flag(
+            Flag::new("closing", FlagType::Bool)
+                .description("Prints only the transactions that close the year"),
+        )
+        .flag(
+            Flag::new("opening", FlagType::Bool)
+                .description("Prints only the transactions that open the next year"),
+        )
+        .flag(booking_flag())
+        .action(handler)
+}
+
+fn handler(context: &Context) {
+    let path = match context.args.first() {
+        Some(path) => path,
+        None => {
+            show_error(emoji::for_error(), "No file given");
+            std::process::exit(1);
+        }
+    };
+    let year = match context.int_flag("year").map(i32::try_from) {
+        Ok(Ok(year)) if NaiveDate::from_ymd_opt(year, 1, 1).is_some() => year,
+        Ok(_) => {
+            show_error(emoji::for_error(), "The year is not valid");
+            std::process::exit(1);
+        }
+        Err(_) => {
+            show_error(emoji::for_error(), "No year given");
+            std::process::exit(1);
+        }
+    };
+
+    let booking = match read_booking(context) {
+        Ok(booking) => booking,
+        Err(_) => std::process::exit(1),
+    };
+
+    let transactions = match read_journal(path) {
+        Ok(journal) => journal::resolve(journal),
+        Err(_) => std::process::exit(1),
+    };
+    let closing = match close::close(&transactions, year, booking) {
+        Ok(closing) => closing,
+        Err(error) => {
+            show_lot_error(error);
+            std::process::exit(1);
+        }
+    };
+
+    match (context.bool_flag("closing"), context.bool_flag("opening")) {
+        (true, false) => print!("{}", format_transactions(closing.closing)),
+        (false, true) => print!("{}", format_transactions(closing.opening)),
+        _ => {
+            println!("// Closing transactions of {}\n", year);
+            println!("{}", format_transactions(closing.closing));
+            println!("// Opening transactions of {}\n", year + 1);
+            print!("{}", format_transactions(closing.opening));
+        }
+    }
+}
+
+fn format_transactions(transactions: Vec<Transaction>) -> String {
+    formatter::format_journal(
+        &transactions
+            .into_iter()
+            .map(JournalElement::Transaction)
+            .collect::<Vec<JournalElement>>(),
+    )
+}
@@ -0,0 +1,124 @@
+use core::convert::{ledger, ConversionIssue, ReportedIssue};
+use core::formatter;
+use core::io::file;
+use seahorse::{Command, Context, Flag, FlagType};
+
+use crate::emoji;
+use crate::io;
+
+use super::show_io_error;
+
+/// Creates a command that converts a journal written in the ledger or hledger syntax, which are
+/// read alike, to the pledger syntax. The converted journal is printed to the standard output
+/// and the dropped constructs to the standard error, so that the output can be redirected to a
+/// file. It fails if a transaction had to be dropped.
+pub fn create() -> Command {
+    Command::new("convert")
+        .usage("--from [ledger|hledger] [file path] Converts a journal from the ledger or hledger syntax")
+        .flag(
+            Flag::new("from", FlagType::String)
+                .alias("f")
+                .description("Syntax of the journal (ledger or hledger), ledger by default"),
+        )
+        .action(handler)
+}
+
+fn handler(context: &Context) {
+    let path = match context.args.first() {
+        Some(path) => path,
+        None => {
+            io::show_error(emoji::for_error(), "No file given");
+            std::process::exit(1);
+        }
+    };
+    match context.string_flag("from").as_deref() {
+        Ok("ledger") | Ok("hledger") | Err(_) => {}
+        Ok(syntax) => {
+            io::show_error(
+                emoji::for_error(),
+                format!("Unknown syntax \"{}\", use ledger or hledger", syntax),
+            );
+            std::process::exit(1);
+        }
+    }
+
+    let content = match file::read_content(path) {
+        Ok(content) => content,
+        Err(err) => {
+            show_io_error(err);
+            std::process::exit(1);
+        }
+    };
+
+    let conversion = ledger::convert(&content);
+    let dropped_transactions = conversion
+        .issues
+        .iter()
+        .any(|reported_issue| drops_transaction(&reported_issue.issue));
+    print!("{}", formatter::format_journal(&conversion.journal));
+    conversion.issues.into_iter().for_each(show_issue);
+
+    if dropped_transactions {
+        std::process::exit(1);
+    }
+}
+
+fn drops_transaction(issue: &ConversionIssue) -> bool {
+    matches!(
+        issue,
+        ConversionIssue::InvalidDate(_)
+            | ConversionIssue::InvalidAmount(_)
+            | ConversionIssue::TooFewPostings
+            | ConversionIssue::UnsplittableTransaction
+    )
+}
+
+fn show_issue(reported_issue: ReportedIssue) {
+    let text = match reported_issue.issue {
+        ConversionIssue::DroppedComment => "Dropped a comment".to_owned(),
+        ConversionIssue::DroppedDirective(directive) => {
+            format!("Dropped the unsupported directive \"{}\"", directive)
+        }
+        ConversionIssue::DroppedAutomatedTransaction => {
+            "Dropped an automated transaction".to_owned()
+        }
+        ConversionIssue::DroppedPeriodicTransaction => "Dropped a periodic transaction".to_owned(),
+        ConversionIssue::DroppedVirtualPosting(account) => {
+            format!("Dropped the virtual posting to \"{}\"", account)
+        }
+        ConversionIssue::DroppedPostingStatus => "Dropped the status of a posting".to_owned(),
+        ConversionIssue::DroppedBalanceAssertion => "Dropped a balance assertion".to_owned(),
+        ConversionIssue::InvalidDate(date) => {
+            format!("Dropped a transaction with the invalid date \"{}\"", date)
+        }
+        ConversionIssue::InvalidAmount(amount) => {
+            format!(
+                "Dropped a transaction with the invalid amount \"{}\"",
+                amount
+            )
+        }
+        ConversionIssue::TooFewPostings => {
+            "Dropped a transaction with less than two postings".to_owned()
+        }
+        ConversionIssue::UnsplittableTransaction => {
+            "Dropped a transaction with more than two postings that could not be split".to_owned()
+        }
+        ConversionIssue::SplitTransaction(transactions) => {
+            format!("Split a transaction in {} transactions", transactions)
+        }
+        ConversionIssue::RenamedAccount { from, to } => {
+            format!("Renamed the account \"{}\" to \"{}\"", from, to)
+        }
+        ConversionIssue::RenamedCurrency { from, to } => {
+            format!("Renamed the currency \"{}\" to \"{}\"", from, to)
+        }
+    };
+
+    eprintln!(
+        "{}",
+        io::text_for_info(
+            emoji::for_warning(),
+            format!("Line {}: {}", reported_issue.line, text)
+        )
+    );
+}
@@ -1,9 +1,11 @@
-use core::io::{file, file::FileError};
+use core::io::file;
 use core::parser;
 use seahorse::{Command, Context};
 
 use crate::emoji;
-use crate::io::{self, show_error, show_info, show_success};
+use crate::io::{self, show_error};
+
+use super::show_io_error;
 
 /// Creates a command that attempts to parse a given journal file and shows the result of the parsing.
 pub fn create() -> Command {
@@ -25,14 +27,7 @@ fn check_file_path(path: &str) {
     let content = file::read_content(path);
     match content {
         Ok(content) => check_content(content),
-        Err(error) => match error {
-            FileError::NotFound => {
-                show_error(emoji::for_search(), format!("File \"{}\" not found", path))
-            }
-            FileError::Unknown => {
-                show_error(emoji::for_error(), "Unknown error while reading the file");
-            }
-        },
+        Err(err) => show_io_error(err),
     }
 }
 
@@ -47,7 +42,7 @@ fn check_content(content: String) {
                 .join("\n> "),
         ),
         Err(error) => {
-            show_error(emoji::for_error(), format!("{}", error));
+            show_error(emoji::for_error(), error.to_string());
             std::process::exit(1);
         }
     }
@@ -0,0 +1,96 @@
+use core::export::{self, ExportFormat, ExportWarning};
+use core::formatter;
+use seahorse::{Command, Context, Flag, FlagType};
+
+use crate::emoji;
+use crate::io::{self, show_error};
+
+use super::read_journal;
+
+/// Creates a command that exports a journal, together with the journals it includes, to the syntax
+/// of another plain text accounting tool. The exported journal is printed to the standard output and the warnings to the standard error,
+/// so that the output can be redirected to a file.
+pub fn create() -> Command {
+    Command::new("export")
+        .alias("e")
+        .usage("[file path] --format [ledger|hledger|beancount] Exports the given journal to another syntax")
+        .flag(
+            Flag::new("format", FlagType::String)
+                .alias("f")
+                .description("Syntax to export the journal to (ledger, hledger or beancount)"),
+        )
+        .action(handler)
+}
+
+fn handler(context: &Context) {
+    let path = match context.args.first() {
+        Some(path) => path,
+        None => {
+            show_error(emoji::for_error(), "No file given");
+            std::process::exit(1);
+        }
+    };
+    let format = match context.string_flag("format").as_deref() {
+        Ok("ledger") => ExportFormat::Ledger,
+        Ok("hledger") => ExportFormat::Hledger,
+        Ok("beancount") => ExportFormat::Beancount,
+        Ok(format) => {
+            show_error(
+                emoji::for_error(),
+                format!(
+                    "Unknown format \"{}\", use ledger, hledger or beancount",
+                    format
+                ),
+            );
+            std::process::exit(1);
+        }
+        Err(_) => {
+            show_error(emoji::for_error(), "No format given");
+            std::process::exit(1);
+        }
+    };
+
+    let journal = match read_journal(path) {
+        Ok(journal) => journal,
+        Err(_) => std::process::exit(1),
+    };
+
+    let export = export::export(journal, &format);
+    print!("{}", export.content);
+    export.warnings.into_iter().for_each(show_export_warning);
+}
+
+fn show_export_warning(warning: ExportWarning) {
+    let text = match warning {
+        ExportWarning::DroppedComments => "Comments are not exported".to_owned(),
+        ExportWarning::DroppedInclude(path) => {
+            format!("The included journal \"{}\" was not exported", path)
+        }
+        ExportWarning::PartialDateWithoutYear(transaction) => format!(
+            "The transaction of {} \"{}\" has no year, so the default year was used",
+            formatter::format_date(&transaction.date),
+            transaction.payee
+        ),
+        ExportWarning::AutomatedTransactionNeedsFlag(automated_transaction) => format!(
+            "The automated transaction \"= {}\" is only applied by hledger with --auto",
+            automated_transaction.query
+        ),
+        ExportWarning::ExpandedAutomatedTransaction(automated_transaction) => format!(
+            "The automated transaction \"= {}\" was replaced by the postings it generates",
+            automated_transaction.query
+        ),
+        ExportWarning::AddedGainsPosting(transaction) => format!(
+            "The sale of {} \"{}\" was given a posting to Income:Gains",
+            formatter::format_date(&transaction.date),
+            transaction.payee
+        ),
+        ExportWarning::RenamedAccount { from, to } => {
+            format!("The account \"{}\" was exported as \"{}\"", from, to)
+        }
+        ExportWarning::RenamedCurrency { from, to } => {
+            format!("The currency \"{}\" was exported as \"{}\"", from, to)
+        }
+    };
+
+    eprintln!("{}", io::text_for_info(emoji::for_warning(), text));
+}
@@ -0,0 +1,126 @@
+use core::journal;
+use core::lots::{self, LotError};
+use core::report::gains::{self, GainsReport};
+use seahorse::{Command, Context};
+
+use crate::emoji;
+use crate::io::{self, show_error};
+use crate::output::{self, Output};
+
+use super::{booking_flag, read_booking, read_journal};
+
+/// Creates a command that shows the gains realized by the sales of the lots of a journal, for
+/// every sale and for every tax year.
+pub fn create() -> Command {
+    Command::new("gains")
+        .usage("[file path] Shows the gains realized by every sale and by every year")
+        .flag(output::flag())
+        .flag(booking_flag())
+        .action(handler)
+}
+
+fn handler(context: &Context) {
+    let path = match context.args.first() {
+        Some(path) => path,
+        None => return io::show_error(emoji::for_error(), "No file given"),
+    };
+    let output = match output::from_context(context) {
+        Some(output) => output,
+        None => std::process::exit(1),
+    };
+    let booking = match read_booking(context) {
+        Ok(booking) => booking,
+        Err(_) => std::process::exit(1),
+    };
+
+    let journal = match read_journal(path) {
+        Ok(journal) => journal,
+        Err(_) => std::process::exit(1),
+    };
+
+    match lots::book(&journal::resolve(journal), booking)
+        .and_then(|lots| gains::report(&lots.sales).map_err(LotError::from))
+    {
+        Ok(report) => match output {
+            Output::Text => show_report(&report),
+            Output::Json => output::print_json(&report),
+            Output::Csv => output::print_csv(&report.sales),
+        },
+        Err(error) => {
+            show_lot_error(error);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn show_report(report: &GainsReport) {
+    let column_width = |value: fn(&gains::SaleRow) -> usize| {
+        report.sales.iter().map(value).max().unwrap_or_default()
+    };
+    let account_width = column_width(|sale| sale.account.len());
+    let units_width = column_width(|sale| sale.quantity.len() + sale.commodity.len() + 1);
+    let proceeds_width = column_width(|sale| sale.proceeds.len());
+    let cost_width = column_width(|sale| sale.cost.len());
+    let gain_width = column_width(|sale| sale.gain.len() + sale.currency.len() + 1);
+
+    for sale in &report.sales {
+        println!(
+            "{}  {:account_width$}  {:>units_width$}  {:>proceeds_width$}  {:>cost_width$}  {:>gain_width$}",
+            sale.date,
+            sale.account,
+            format!("{} {}", sale.quantity, sale.commodity),
+            sale.proceeds,
+            sale.cost,
+            format!("{} {}", sale.gain, sale.currency),
+            account_width = account_width,
+            units_width = units_width,
+            proceeds_width = proceeds_width,
+            cost_width = cost_width,
+            gain_width = gain_width
+        );
+    }
+
+    if !report.sales.is_empty() {
+        println!();
+    }
+    for year in &report.years {
+        println!("{}  {} {}", year.year, year.gain, year.currency);
+    }
+}
+
+pub(crate) fn show_lot_error(error: LotError) {
+    let message = match error {
+        LotError::InsufficientUnits {
+            date,
+            account,
+            commodity,
+            missing,
+        } => format!(
+            "The sale of {} in \"{}\" on {} needs {} more units than were held",
+            commodity, account, date, missing
+        ),
+        LotError::LotNotFound { date, account, lot } => format!(
+            "The sale in \"{}\" on {} names a lot with cost {} {} that is not held",
+            account, date, lot.cost.quantity, lot.cost.currency
+        ),
+        LotError::MissingPrice {
+            date,
+            account,
+            commodity,
+        } => format!(
+            "The sale of {} in \"{}\" on {} has no price",
+            commodity, account, date
+        ),
+        LotError::CurrencyMismatch {
+            date,
+            account,
+            commodity,
+        } => format!(
+            "The sale of {} in \"{}\" on {} is in a different currency than the cost of its lots",
+            commodity, account, date
+        ),
+        LotError::Overflow(error) => error.to_string(),
+    };
+
+    show_error(emoji::for_error(), message);
+}
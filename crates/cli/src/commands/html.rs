@@ -0,0 +1,69 @@
+use std::path::Path;
+
+use core::io::file;
+use core::site;
+use seahorse::{Command, Context, Flag, FlagType};
+
+use crate::emoji;
+use crate::io::{show_error, show_success};
+
+use super::{currency_flag, read_currency, read_journal};
+
+/// Creates a command that writes a static site with the reports of a journal, which can be opened
+/// in a browser without a server.
+pub fn create() -> Command {
+    Command::new("html")
+        .usage("[file path] Writes a static site with the reports of the journal")
+        .flag(
+            Flag::new("out", FlagType::String)
+                .description("Directory the site is written to, site by default"),
+        )
+        .flag(currency_flag("Currency of the charts"))
+        .action(handler)
+}
+
+fn handler(context: &Context) {
+    let path = match context.args.first() {
+        Some(path) => path,
+        None => return show_error(emoji::for_error(), "No file given"),
+    };
+    let out = context
+        .string_flag("out")
+        .unwrap_or_else(|_| "site".to_owned());
+    let currency = read_currency(context);
+
+    let journal = match read_journal(path) {
+        Ok(journal) => journal,
+        Err(_) => std::process::exit(1),
+    };
+    let pages = match site::build(journal, &currency) {
+        Ok(pages) => pages,
+        Err(err) => {
+            show_error(emoji::for_error(), err.to_string());
+            std::process::exit(1);
+        }
+    };
+
+    let written = file::create_directory(&out).and_then(|_| {
+        pages.iter().try_for_each(|page| {
+            file::replace_content(
+                &Path::new(&out).join(&page.path).to_string_lossy(),
+                &page.content,
+            )
+        })
+    });
+
+    match written {
+        Ok(_) => show_success(
+            emoji::for_success(),
+            format!("{} pages written to \"{}\"", pages.len(), out),
+        ),
+        Err(_) => {
+            show_error(
+                emoji::for_error(),
+                format!("The site could not be written to \"{}\"", out),
+            );
+            std::process::exit(1);
+        }
+    }
+}
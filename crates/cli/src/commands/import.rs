@@ -0,0 +1,292 @@
+use std::convert::TryFrom;
+use std::path::Path;
+
+use core::formatter;
+use core::import::duplicates::{self, Deduplication, DuplicateOptions, DuplicateReason};
+use core::import::{self, csv, ofx, qif, rules, ImportError};
+use core::io::file;
+use core::journal;
+use core::parser::ast::Transaction;
+use seahorse::{Command, Context, Flag, FlagType};
+
+use crate::emoji;
+use crate::io::{self, show_error, show_info, show_success};
+
+use super::{read_journal, show_io_error};
+
+/// Creates a command that imports a bank statement using a rules file and either prints the
+/// resulting transactions or appends them to a journal.
+pub fn create() -> Command {
+    Command::new("import")
+        .alias("i")
+        .usage(
+            "[file path] --rules [rules path] Imports the transactions of a CSV, OFX or QIF bank statement",
+        )
+        .flag(
+            Flag::new("rules", FlagType::String)
+                .alias("r")
+                .description("Rules file that defines how to read and categorise the statement"),
+        )
+        .flag(
+            Flag::new("format", FlagType::String)
+                .alias("f")
+                .description("Format of the statement (csv, ofx or qif), detected from the extension by default"),
+        )
+        .flag(
+            Flag::new("append", FlagType::String)
+                .alias("a")
+                .description("Journal file to append the transactions to instead of printing them"),
+        )
+        .flag(
+            Flag::new("dry-run", FlagType::Bool)
+                .alias("n")
+                .description("Shows which transactions would be appended and which ones would be skipped without changing the journal"),
+        )
+        .flag(
+            Flag::new("flag-duplicates", FlagType::Bool)
+                .description("Appends duplicated transactions as pending and tagged with \"duplicate\" instead of skipping them"),
+        )
+        .flag(
+            Flag::new("date-window", FlagType::Int)
+                .alias("w")
+                .description("Maximum number of days between two transactions to consider them duplicates (3 by default)"),
+        )
+        .action(handler)
+}
+
+fn handler(context: &Context) {
+    let statement_path = match context.args.first() {
+        Some(path) => path,
+        None => return io::show_error(emoji::for_error(), "No file given"),
+    };
+    let rules_path = match context.string_flag("rules") {
+        Ok(path) => path,
+        Err(_) => return io::show_error(emoji::for_error(), "No rules file given"),
+    };
+    let format = context.string_flag("format").unwrap_or_else(|_| {
+        Path::new(statement_path)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default()
+    });
+
+    let transactions = read_file(&rules_path)
+        .and_then(|rules_content| {
+            read_file(statement_path).map(|statement| (rules_content, statement))
+        })
+        .and_then(|(rules_content, statement)| {
+            import_statement(&format, &rules_content, &statement).map_err(show_import_error)
+        });
+
+    let options = DuplicateOptions {
+        date_window: context
+            .int_flag("date-window")
+            .map(|days| i64::try_from(days.unsigned_abs()).unwrap_or(i64::MAX))
+            .unwrap_or_else(|_| DuplicateOptions::default().date_window),
+        ..DuplicateOptions::default()
+    };
+
+    if let Ok(transactions) = &transactions {
+        transactions.iter().for_each(warn_about_unwritable_text);
+    }
+
+    match transactions {
+        Ok(transactions) => match context.string_flag("append") {
+            Ok(journal_path) => append_transactions(
+                &journal_path,
+                transactions,
+                &options,
+                context.bool_flag("flag-duplicates"),
+                context.bool_flag("dry-run"),
+            ),
+            Err(_) if context.bool_flag("dry-run") => show_error(
+                emoji::for_error(),
+                "A dry run needs the journal to compare with, given with --append",
+            ),
+            Err(_) => print!("{}", format_transactions(&transactions)),
+        },
+        Err(_) => std::process::exit(1),
+    }
+}
+
+fn read_file(path: &str) -> Result<String, ()> {
+    file::read_content(path).map_err(show_io_error)
+}
+
+fn import_statement(
+    format: &str,
+    rules_content: &str,
+    statement: &str,
+) -> Result<Vec<Transaction>, ImportError> {
+    let rules = rules::parse(rules_content)?;
+    let entries = match format {
+        "ofx" | "qfx" => ofx::read(statement, &rules)?,
+        "qif" => qif::read(statement, &rules)?,
+        _ => csv::read(statement, &rules)?,
+    };
+    import::to_transactions(entries, &rules)
+}
+
+/// Warns that the payee or the description of the transaction have characters that are removed
+/// when writing it, which can be replaced with a rule instead.
+fn warn_about_unwritable_text(transaction: &Transaction) {
+    for (field, text) in [
+        ("payee", &transaction.payee),
+        ("description", &transaction.description),
+    ]
+    .iter()
+    {
+        if !formatter::is_writable_text(text) {
+            eprintln!(
+                "{}",
+                io::text_for_info(
+                    emoji::for_warning(),
+                    format!(
+                        "The {} \"{}\" has characters that are removed when writing it, add a replace rule to the rules file to choose how to write them",
+                        field, text
+                    ),
+                )
+            );
+        }
+    }
+}
+
+fn format_transactions(transactions: &[Transaction]) -> String {
+    transactions
+        .iter()
+        .map(formatter::format_transaction)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn append_transactions(
+    journal_path: &str,
+    transactions: Vec<Transaction>,
+    options: &DuplicateOptions,
+    flag_duplicates: bool,
+    dry_run: bool,
+) {
+    let journal = match read_journal(journal_path) {
+        Ok(journal) => journal,
+        Err(_) => std::process::exit(1),
+    };
+
+    let deduplication = duplicates::find(transactions, &journal::resolve(journal), options);
+
+    if dry_run {
+        return show_dry_run(&deduplication, flag_duplicates);
+    }
+
+    let skipped = deduplication.duplicates.len();
+    let Deduplication {
+        mut unique,
+        duplicates,
+    } = deduplication;
+
+    if flag_duplicates {
+        unique.extend(
+            duplicates
+                .into_iter()
+                .map(|duplicate| duplicates::flag(duplicate.transaction)),
+        );
+    } else if skipped > 0 {
+        show_info(
+            emoji::for_warning(),
+            format!(
+                "Skipped {} transactions that were already in the journal",
+                skipped
+            ),
+        );
+    }
+
+    if unique.is_empty() {
+        return;
+    }
+
+    match file::append_content(journal_path, &format!("\n{}", format_transactions(&unique))) {
+        Ok(_) => show_success(
+            emoji::for_success(),
+            format!(
+                "Appended {} transactions to \"{}\"",
+                unique.len(),
+                journal_path
+            ),
+        ),
+        Err(err) => show_io_error(err),
+    }
+}
+
+fn show_dry_run(deduplication: &Deduplication, flag_duplicates: bool) {
+    show_info(
+        emoji::for_success(),
+        format!(
+            "{} transactions would be appended",
+            deduplication.unique.len()
+        ),
+    );
+    println!("{}", format_transactions(&deduplication.unique));
+
+    if deduplication.duplicates.is_empty() {
+        return;
+    }
+
+    show_info(
+        emoji::for_warning(),
+        format!(
+            "{} transactions would be {}",
+            deduplication.duplicates.len(),
+            if flag_duplicates {
+                "appended flagged as duplicates"
+            } else {
+                "skipped"
+            }
+        ),
+    );
+    deduplication.duplicates.iter().for_each(|duplicate| {
+        let reason = match duplicate.reason {
+            DuplicateReason::SameImportId => "has the same import ID as",
+            DuplicateReason::SimilarTransaction => "looks like",
+        };
+
+        println!(
+            "{}-> {} the transaction of {} \"{}\"\n",
+            formatter::format_transaction(&duplicate.transaction),
+            reason,
+            formatter::format_date(&duplicate.existing.date),
+            duplicate.existing.payee
+        );
+    });
+}
+
+fn show_import_error(error: ImportError) {
+    match error {
+        ImportError::NonParsableRules(err) => show_error(
+            emoji::for_error(),
+            format!("There was an error parsing the rules:\n{}", err),
+        ),
+        ImportError::MissingRule(rule) => show_error(
+            emoji::for_error(),
+            format!("The rules file does not define \"{}\"", rule),
+        ),
+        ImportError::NonParsableStatement(err) => show_error(
+            emoji::for_error(),
+            format!("There was an error reading the statement: {}", err),
+        ),
+        ImportError::MissingColumn { row, column } => show_error(
+            emoji::for_error(),
+            format!("Row {} does not contain column {}", row, column),
+        ),
+        ImportError::MissingField { row, field } => show_error(
+            emoji::for_error(),
+            format!("Transaction {} does not contain the field {}", row, field),
+        ),
+        ImportError::InvalidDate { row, value } => show_error(
+            emoji::for_error(),
+            format!("Row {} contains an invalid date: \"{}\"", row, value),
+        ),
+        ImportError::InvalidAmount { row, value } => show_error(
+            emoji::for_error(),
+            format!("Row {} contains an invalid amount: \"{}\"", row, value),
+        ),
+    }
+}
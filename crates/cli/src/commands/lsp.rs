@@ -0,0 +1,21 @@
+use seahorse::{Command, Context};
+
+use crate::emoji;
+use crate::io;
+use crate::lsp;
+
+/// Creates a command that runs a language server for journals, which editors can start to get
+/// diagnostics, completion, hovers, navigation, formatting and quick fixes.
+pub fn create() -> Command {
+    Command::new("lsp")
+        .usage("Runs a language server for journals through the standard input and output")
+        .action(handler)
+}
+
+fn handler(_: &Context) {
+    // The standard output belongs to the protocol, so errors are shown in the standard error
+    if let Err(err) = lsp::run() {
+        eprintln!("{}", io::text_for_error(emoji::for_error(), err));
+        std::process::exit(1);
+    }
+}
@@ -1,2 +1,158 @@
+pub mod add;
+pub mod balance;
+pub mod chart;
 pub mod check;
+pub mod close;
+pub mod convert;
 pub mod debug;
+pub mod export;
+pub mod gains;
+pub mod html;
+pub mod import;
+pub mod lsp;
+pub mod networth;
+pub mod print;
+pub mod reconcile;
+pub mod register;
+pub mod serve;
+pub mod tui;
+pub mod unrealized;
+
+use chrono::{Local, NaiveDate};
+use core::conversion::{Valuation, DEFAULT_CURRENCY};
+use core::error::IoError;
+use core::io::file;
+use core::journal::include;
+use core::lots::Booking;
+use core::parser::{
+    self,
+    ast::{CurrencyCode, JournalElement},
+};
+use seahorse::{Context, Flag, FlagType};
+
+use crate::emoji;
+use crate::io::show_error;
+
+/// Reads and parses the journal in the given path together with the journals it includes, showing
+/// an error if any of them can't be read or parsed.
+pub(crate) fn read_journal(path: &str) -> Result<Vec<JournalElement>, ()> {
+    let content = file::read_content(path).map_err(show_io_error)?;
+    let journal = parser::parse_journal(&content).map_err(|err| {
+        show_error(
+            emoji::for_error(),
+            format!("There was an error parsing the journal:\n{}", err),
+        )
+    })?;
+
+    include::expand(journal, path, &mut file::read_content)
+        .map_err(|err| show_error(emoji::for_error(), err.to_string()))
+}
+
+/// Shows why a file could not be read or written, with the cause given by the system.
+pub(crate) fn show_io_error(error: IoError) {
+    let emoji = if error.is_not_found() {
+        emoji::for_search()
+    } else {
+        emoji::for_error()
+    };
+
+    show_error(emoji, error.to_string())
+}
+
+/// Parses the query given as the arguments that follow the journal path, which is empty if there
+/// are no more arguments.
+pub(crate) fn read_query(args: &[String]) -> Result<parser::ast::Query, ()> {
+    if args.is_empty() {
+        return Ok(parser::ast::Query(vec![]));
+    }
+
+    parser::parse_query(&args.join(" ")).map_err(|err| {
+        show_error(
+            emoji::for_error(),
+            format!("There was an error parsing the query:\n{}", err),
+        )
+    })
+}
+
+/// Creates the flag that chooses the lots reduced by sales.
+pub(crate) fn booking_flag() -> Flag {
+    Flag::new("booking", FlagType::String)
+        .alias("b")
+        .description("Lots reduced by sales (fifo, lifo or average), fifo by default")
+}
+
+/// Returns the booking method given in the context, fifo if none was given.
+pub(crate) fn read_booking(context: &Context) -> Result<Booking, ()> {
+    match context.string_flag("booking").as_deref() {
+        Ok("fifo") | Err(_) => Ok(Booking::Fifo),
+        Ok("lifo") => Ok(Booking::Lifo),
+        Ok("average") => Ok(Booking::Average),
+        Ok(booking) => {
+            show_error(
+                emoji::for_error(),
+                format!("Unknown booking \"{}\", use fifo, lifo or average", booking),
+            );
+            Err(())
+        }
+    }
+}
+
+/// Creates the flag that chooses the currency of the values of a command, whose description is
+/// completed with the default currency.
+pub(crate) fn currency_flag(description: &str) -> Flag {
+    Flag::new("currency", FlagType::String)
+        .alias("c")
+        .description(format!("{}, {} by default", description, DEFAULT_CURRENCY))
+}
+
+/// Returns the currency given in the context, or the default currency if none was given.
+pub(crate) fn read_currency(context: &Context) -> CurrencyCode {
+    context
+        .string_flag("currency")
+        .unwrap_or_else(|_| DEFAULT_CURRENCY.to_owned())
+}
+
+/// Creates the flag that chooses how the amounts of a report are valued.
+pub(crate) fn value_flag() -> Flag {
+    Flag::new("value", FlagType::String)
+        .alias("V")
+        .description("Values the amounts at cost or at the market prices (cost or market)")
+}
+
+/// Returns the valuation given in the context, if any. Market values are in the currency of the
+/// context with the prices known at the given date, or today if there is none.
+pub(crate) fn read_valuation(
+    context: &Context,
+    at: Option<NaiveDate>,
+) -> Result<Option<Valuation>, ()> {
+    match context.string_flag("value").as_deref() {
+        Ok("cost") => Ok(Some(Valuation::Cost)),
+        Ok("market") => Ok(Some(Valuation::Market {
+            currency: read_currency(context),
+            date: at.unwrap_or_else(|| Local::now().date_naive()),
+        })),
+        Ok(value) => {
+            show_error(
+                emoji::for_error(),
+                format!("Unknown value \"{}\", use cost or market", value),
+            );
+            Err(())
+        }
+        Err(_) => Ok(None),
+    }
+}
+
+/// Returns the date given as year-month-day in the flag of the context, if any.
+pub(crate) fn read_date(context: &Context, flag: &str) -> Result<Option<NaiveDate>, ()> {
+    match context.string_flag(flag) {
+        Ok(date) => NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| {
+                show_error(
+                    emoji::for_error(),
+                    format!("The date \"{}\" is not valid", date),
+                )
+            }),
+        Err(_) => Ok(None),
+    }
+}
@@ -0,0 +1,139 @@
+use chrono::Local;
+use core::conversion::Prices;
+use core::journal;
+use core::report::networth::{self, NetWorthReport};
+use seahorse::{Command, Context, Flag, FlagType};
+
+use crate::emoji;
+use crate::io;
+use crate::output::{self, Output};
+
+use super::{currency_flag, read_currency, read_date, read_journal};
+
+/// Creates a command that shows the assets, liabilities and net worth of a journal at a date, or
+/// at the end of every month, valued in a single currency.
+pub fn create() -> Command {
+    Command::new("networth")
+        .usage("[file path] Shows the assets, liabilities and net worth valued in a currency")
+        .flag(output::flag())
+        .flag(
+            Flag::new("monthly", FlagType::Bool)
+                .alias("m")
+                .description("Shows the net worth at the end of every month"),
+        )
+        .flag(Flag::new("at", FlagType::String).description(
+            "Date of the net worth, or end of the months, as year-month-day, today by default \
+             or the last transaction with --monthly",
+        ))
+        .flag(Flag::new("end", FlagType::String).description("Same as --at"))
+        .flag(currency_flag("Currency of the values"))
+        .action(handler)
+}
+
+fn handler(context: &Context) {
+    let path = match context.args.first() {
+        Some(path) => path,
+        None => return io::show_error(emoji::for_error(), "No file given"),
+    };
+    let output = match output::from_context(context) {
+        Some(output) => output,
+        None => std::process::exit(1),
+    };
+    let at = match read_date(context, "at").and_then(|at| match at {
+        Some(at) => Ok(Some(at)),
+        None => read_date(context, "end"),
+    }) {
+        Ok(at) => at,
+        Err(_) => std::process::exit(1),
+    };
+    let today = Local::now().date_naive();
+    let currency = read_currency(context);
+
+    let journal = match read_journal(path) {
+        Ok(journal) => journal,
+        Err(_) => std::process::exit(1),
+    };
+    let prices = Prices::from_journal(&journal);
+    let transactions = journal::resolve(journal);
+
+    let dates = if context.bool_flag("monthly") {
+        let dates = transactions
+            .iter()
+            .map(|transaction| transaction.date.date());
+        match (dates.clone().min(), dates.max()) {
+            (Some(first), Some(last)) => {
+                networth::month_ends(first, at.unwrap_or_else(|| last.min(today)))
+            }
+            _ => vec![],
+        }
+    } else {
+        vec![at.unwrap_or(today)]
+    };
+    let report = match networth::report(&transactions, &prices, &currency, &dates) {
+        Ok(report) => report,
+        Err(err) => {
+            io::show_error(emoji::for_error(), err.to_string());
+            std::process::exit(1);
+        }
+    };
+
+    if !report.unpriced.is_empty() {
+        eprintln!(
+            "{}",
+            io::text_for_info(
+                emoji::for_warning(),
+                format!(
+                    "Amounts in {} were left out because they have no price in {}",
+                    report.unpriced.join(", "),
+                    currency
+                ),
+            )
+        );
+    }
+
+    match output {
+        Output::Text => show_report(&report),
+        Output::Json => output::print_json(&report),
+        Output::Csv => output::print_csv(&report.rows),
+    }
+}
+
+fn show_report(report: &NetWorthReport) {
+    let column_width = |title: &str, value: fn(&networth::NetWorthRow) -> usize| {
+        report
+            .rows
+            .iter()
+            .map(value)
+            .chain(std::iter::once(title.len()))
+            .max()
+            .unwrap_or_default()
+    };
+    let assets_width = column_width("Assets", |row| row.assets.len());
+    let liabilities_width = column_width("Liabilities", |row| row.liabilities.len());
+    let net_worth_width = column_width("Net worth", |row| {
+        row.net_worth.len() + row.currency.len() + 1
+    });
+
+    println!(
+        "{:10}  {:>assets_width$}  {:>liabilities_width$}  {:>net_worth_width$}",
+        "Date",
+        "Assets",
+        "Liabilities",
+        "Net worth",
+        assets_width = assets_width,
+        liabilities_width = liabilities_width,
+        net_worth_width = net_worth_width
+    );
+    for row in &report.rows {
+        println!(
+            "{}  {:>assets_width$}  {:>liabilities_width$}  {:>net_worth_width$}",
+            row.date,
+            row.assets,
+            row.liabilities,
+            format!("{} {}", row.net_worth, row.currency),
+            assets_width = assets_width,
+            liabilities_width = liabilities_width,
+            net_worth_width = net_worth_width
+        );
+    }
+}
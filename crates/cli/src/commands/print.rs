@@ -0,0 +1,34 @@
+use core::formatter;
+use core::report::journal;
+use seahorse::{Command, Context, Flag, FlagType};
+
+use crate::emoji;
+use crate::io;
+use crate::output;
+
+use super::read_journal;
+
+/// Creates a command that prints a journal, either in the journal syntax or as JSON.
+pub fn create() -> Command {
+    Command::new("print")
+        .alias("p")
+        .usage("[file path] Prints the given journal")
+        .flag(
+            Flag::new("json", FlagType::Bool)
+                .description("Prints the resolved journal as JSON, with amounts as strings"),
+        )
+        .action(handler)
+}
+
+fn handler(context: &Context) {
+    let path = match context.args.first() {
+        Some(path) => path,
+        None => return io::show_error(emoji::for_error(), "No file given"),
+    };
+
+    match read_journal(path) {
+        Ok(journal) if context.bool_flag("json") => output::print_json(&journal::report(journal)),
+        Ok(journal) => print!("{}", formatter::format_journal(&journal)),
+        Err(_) => std::process::exit(1),
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use chrono::{Local, NaiveDate};
+use core::formatter;
+use core::io::file;
+use core::journal::{self, include};
+use core::parser::{self, ast::Amount};
+use core::reconcile::{self, Reconciliation};
+use rust_decimal::Decimal;
+use seahorse::{Command, Context, Flag, FlagType};
+
+use crate::emoji::{self, Emoji};
+use crate::io::{show_error, show_info, show_success};
+
+use super::{read_journal, show_io_error};
+
+/// Creates a command that compares the cleared balance of an account with the balance of its
+/// statement, listing the pending postings of the account and suggesting which of them explain the
+/// difference. The selected pending transactions can be marked as cleared in the journal.
+pub fn create() -> Command {
+    Command::new("reconcile")
+        .usage("[file path] [account] --statement-balance [amount] Reconciles an account with its statement")
+        .flag(
+            Flag::new("statement-balance", FlagType::String)
+                .alias("s")
+                .description("Balance of the account in the statement"),
+        )
+        .flag(
+            Flag::new("date", FlagType::String)
+                .alias("d")
+                .description("Date of the statement as year-month-day, today by default"),
+        )
+        .flag(
+            Flag::new("currency", FlagType::String)
+                .alias("c")
+                .description("Currency of the statement, only needed if the account has several"),
+        )
+        .flag(
+            Flag::new("clear", FlagType::String).description(
+                "Marks as cleared the listed pending postings (e.g. 1,3) or the suggested ones",
+            ),
+        )
+        .action(handler)
+}
+
+fn handler(context: &Context) {
+    let (path, account) = match context.args.as_slice() {
+        [path, account, ..] => (path, account),
+        _ => fail(emoji::for_error(), "No file or account given"),
+    };
+    let statement_balance = match context
+        .string_flag("statement-balance")
+        .map(|balance| Decimal::from_str(&balance))
+    {
+        Ok(Ok(balance)) => balance,
+        Ok(Err(_)) => fail(emoji::for_error(), "The statement balance is not valid"),
+        Err(_) => fail(emoji::for_error(), "No statement balance given"),
+    };
+    let date = match context.string_flag("date") {
+        Ok(date) => match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+            Ok(date) => date,
+            Err(_) => fail(emoji::for_error(), "The date is not valid"),
+        },
+        Err(_) => Local::now().date_naive(),
+    };
+
+    let transactions = match read_journal(path) {
+        Ok(journal) => journal::resolve(journal),
+        Err(_) => std::process::exit(1),
+    };
+
+    let currency = match context.string_flag("currency") {
+        Ok(currency) => currency,
+        Err(_) => match reconcile::currencies(&transactions, account).as_slice() {
+            [currency] => currency.clone(),
+            [] => fail(
+                emoji::for_search(),
+                format!("The account \"{}\" has no postings", account),
+            ),
+            currencies => fail(
+                emoji::for_error(),
+                format!(
+                    "The account has postings in {}, choose one with --currency",
+                    currencies.join(", ")
+                ),
+            ),
+        },
+    };
+
+    let reconciliation = match reconcile::reconcile(
+        &transactions,
+        account,
+        &Amount {
+            quantity: statement_balance,
+            currency,
+        },
+        date,
+    ) {
+        Ok(reconciliation) => reconciliation,
+        Err(err) => fail(emoji::for_error(), err.to_string()),
+    };
+
+    match context.string_flag("clear") {
+        Ok(selection) => clear(path, &reconciliation, &selection),
+        Err(_) => show_reconciliation(&reconciliation),
+    }
+}
+
+fn show_reconciliation(reconciliation: &Reconciliation) {
+    let suggestion = reconciliation.suggestion.clone().unwrap_or_default();
+
+    if reconciliation.pending.is_empty() {
+        show_info(emoji::for_search(), "There are no pending postings");
+    }
+
+    for (position, pending_posting) in reconciliation.pending.iter().enumerate() {
+        println!(
+            "{} {:>3}. {} {:<30} {:>12}",
+            if suggestion.contains(&position) {
+                "*"
+            } else {
+                " "
+            },
+            position + 1,
+            formatter::format_date(&pending_posting.transaction.date),
+            pending_posting.transaction.payee,
+            formatter::format_amount(&pending_posting.amount)
+        );
+    }
+
+    println!(
+        "\nCleared balance:   {}\nStatement balance: {}\nDifference:        {}\n",
+        formatter::format_amount(&reconciliation.cleared_balance),
+        formatter::format_amount(&reconciliation.statement_balance),
+        formatter::format_amount(&reconciliation.difference)
+    );
+
+    match &reconciliation.suggestion {
+        Some(suggestion) if suggestion.is_empty() => show_success(
+            emoji::for_success(),
+            "The cleared balance matches the statement",
+        ),
+        Some(suggestion) => show_info(
+            emoji::for_search(),
+            format!(
+                "Clearing the postings marked with * ({}) explains the difference, use --clear suggested to do it",
+                positions(suggestion)
+            ),
+        ),
+        None => show_error(
+            emoji::for_warning(),
+            "No combination of pending postings explains the difference",
+        ),
+    }
+}
+
+fn clear(path: &str, reconciliation: &Reconciliation, selection: &str) {
+    let selected: Vec<usize> = if selection == "suggested" {
+        match &reconciliation.suggestion {
+            Some(suggestion) => suggestion.clone(),
+            None => fail(
+                emoji::for_error(),
+                "No combination of pending postings explains the difference",
+            ),
+        }
+    } else {
+        match selection
+            .split(',')
+            .map(|position| position.trim().parse::<usize>())
+            .collect::<Result<Vec<usize>, _>>()
+        {
+            Ok(positions)
+                if positions
+                    .iter()
+                    .all(|&position| position >= 1 && position <= reconciliation.pending.len()) =>
+            {
+                positions.into_iter().map(|position| position - 1).collect()
+            }
+            _ => fail(
+                emoji::for_error(),
+                format!("\"{}\" is not a valid list of pending postings", selection),
+            ),
+        }
+    };
+
+    // The journal was already read, so it only fails here if it changed in the meantime.
+    let origins = match file::read_content(path).map(|content| parser::parse_journal(&content)) {
+        Ok(Ok(journal)) => include::origins(&journal, path, &mut file::read_content),
+        Ok(Err(_)) => fail(emoji::for_error(), "The journal changed while reconciling"),
+        Err(err) => {
+            show_io_error(err);
+            std::process::exit(1);
+        }
+    };
+    let mut indexes: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for &position in &selected {
+        match origins.get(reconciliation.pending[position].index) {
+            Some((origin, index)) => indexes.entry(origin).or_default().push(*index),
+            None => fail(emoji::for_error(), "The journal changed while reconciling"),
+        }
+    }
+
+    for (origin, indexes) in indexes {
+        let cleared_content =
+            match file::read_content(origin)
+                .map_err(show_io_error)
+                .and_then(|content| {
+                    reconcile::clear(&content, &indexes)
+                        .map_err(|err| show_error(emoji::for_error(), err))
+                }) {
+                Ok(cleared_content) => cleared_content,
+                Err(_) => std::process::exit(1),
+            };
+
+        if let Err(err) = file::replace_content(origin, &cleared_content) {
+            show_io_error(err);
+            std::process::exit(1);
+        }
+    }
+
+    show_success(
+        emoji::for_success(),
+        format!("Marked the postings {} as cleared", positions(&selected)),
+    );
+}
+
+/// Shows the error and ends the command with a failure.
+fn fail<S: Into<String>>(emoji: Emoji, text: S) -> ! {
+    show_error(emoji, text);
+    std::process::exit(1)
+}
+
+fn positions(positions: &[usize]) -> String {
+    positions
+        .iter()
+        .map(|position| (position + 1).to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
@@ -0,0 +1,116 @@
+use core::conversion::{self, Prices};
+use core::journal;
+use core::report::register::{self, RegisterReport};
+use seahorse::{Command, Context, Flag, FlagType};
+
+use crate::emoji;
+use crate::io;
+use crate::output::{self, Output};
+
+use super::{currency_flag, read_date, read_journal, read_query, read_valuation, value_flag};
+
+/// Creates a command that lists the postings of a journal with a running total, optionally
+/// valuing them at cost or at market prices.
+pub fn create() -> Command {
+    Command::new("register")
+        .alias("r")
+        .usage("[file path] [query] Lists the postings that match the query with a running total")
+        .flag(output::flag())
+        .flag(value_flag())
+        .flag(Flag::new("at", FlagType::String).description(
+            "Last date of the postings as year-month-day, which also dates the prices",
+        ))
+        .flag(currency_flag("Currency of the market values"))
+        .action(handler)
+}
+
+fn handler(context: &Context) {
+    let path = match context.args.first() {
+        Some(path) => path,
+        None => return io::show_error(emoji::for_error(), "No file given"),
+    };
+    let output = match output::from_context(context) {
+        Some(output) => output,
+        None => std::process::exit(1),
+    };
+
+    let at = match read_date(context, "at") {
+        Ok(at) => at,
+        Err(_) => std::process::exit(1),
+    };
+    let valuation = match read_valuation(context, at) {
+        Ok(valuation) => valuation,
+        Err(_) => std::process::exit(1),
+    };
+
+    match read_journal(path)
+        .and_then(|journal| read_query(&context.args[1..]).map(|query| (journal, query)))
+    {
+        Ok((journal, query)) => {
+            let prices = Prices::from_journal(&journal);
+            let mut transactions = journal::resolve(journal);
+
+            if let Some(at) = at {
+                transactions.retain(|transaction| transaction.date.date() <= at);
+            }
+            if let Some(valuation) = &valuation {
+                transactions = match conversion::value(&transactions, valuation, &prices) {
+                    Ok(transactions) => transactions,
+                    Err(err) => {
+                        io::show_error(emoji::for_error(), err.to_string());
+                        std::process::exit(1);
+                    }
+                };
+            }
+
+            let report = match register::report(&transactions, &query) {
+                Ok(report) => report,
+                Err(err) => {
+                    io::show_error(emoji::for_error(), err.to_string());
+                    std::process::exit(1);
+                }
+            };
+
+            match output {
+                Output::Text => show_report(&report),
+                Output::Json => output::print_json(&report),
+                Output::Csv => output::print_csv(&report.entries),
+            }
+        }
+        Err(_) => std::process::exit(1),
+    }
+}
+
+fn show_report(report: &RegisterReport) {
+    let column_width = |value: fn(&register::RegisterEntry) -> usize| {
+        report.entries.iter().map(value).max().unwrap_or_default()
+    };
+    let payee_width = column_width(|entry| entry.payee.chars().count());
+    let account_width = column_width(|entry| account(entry).len());
+    let amount_width = column_width(|entry| entry.quantity.len() + entry.currency.len() + 1);
+    let total_width = column_width(|entry| entry.total.len() + entry.currency.len() + 1);
+
+    for entry in &report.entries {
+        println!(
+            "{}  {:payee_width$}  {:account_width$}  {:>amount_width$}  {:>total_width$}",
+            entry.date,
+            entry.payee,
+            account(entry),
+            format!("{} {}", entry.quantity, entry.currency),
+            format!("{} {}", entry.total, entry.currency),
+            payee_width = payee_width,
+            account_width = account_width,
+            amount_width = amount_width,
+            total_width = total_width
+        );
+    }
+}
+
+/// Returns the account of the entry, marking the postings added by automated transactions.
+fn account(entry: &register::RegisterEntry) -> String {
+    if entry.automated {
+        format!("{} (automated)", entry.account)
+    } else {
+        entry.account.clone()
+    }
+}
@@ -0,0 +1,37 @@
+use std::convert::TryFrom;
+
+use seahorse::{Command, Context, Flag, FlagType};
+
+use crate::emoji;
+use crate::io::show_error;
+use crate::server;
+
+/// Creates a command that serves a journal on localhost through a JSON API and a small web
+/// interface, reading the journal again every time it changes.
+pub fn create() -> Command {
+    Command::new("serve")
+        .usage("[file path] Serves the reports of the journal on localhost")
+        .flag(
+            Flag::new("port", FlagType::Int)
+                .alias("p")
+                .description("Port to listen on, 5000 by default"),
+        )
+        .action(handler)
+}
+
+fn handler(context: &Context) {
+    let path = match context.args.first() {
+        Some(path) => path,
+        None => return show_error(emoji::for_error(), "No file given"),
+    };
+    let port = match context.int_flag("port").map(u16::try_from) {
+        Ok(Ok(port)) => port,
+        Ok(Err(_)) => return show_error(emoji::for_error(), "The port is not valid"),
+        Err(_) => 5000,
+    };
+
+    if let Err(err) = server::run(path, port) {
+        show_error(emoji::for_error(), err);
+        std::process::exit(1);
+    }
+}
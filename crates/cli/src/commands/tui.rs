@@ -0,0 +1,25 @@
+use seahorse::{Command, Context};
+
+use crate::emoji;
+use crate::io::{self, show_error};
+use crate::tui;
+
+/// Creates a command that opens a full screen interface to browse the given journal, with the
+/// account tree, the register of the selected account and the selected transaction.
+pub fn create() -> Command {
+    Command::new("tui")
+        .usage("[file path] Browses the given journal in a full screen interface")
+        .action(handler)
+}
+
+fn handler(context: &Context) {
+    let path = match context.args.first() {
+        Some(path) => path,
+        None => return io::show_error(emoji::for_error(), "No file given"),
+    };
+
+    if let Err(err) = tui::run(path) {
+        show_error(emoji::for_error(), err);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::Local;
+use core::conversion::Prices;
+use core::journal;
+use core::lots::{self, LotError};
+use core::report::unrealized::{self, UnrealizedReport};
+use seahorse::{Command, Context, Flag, FlagType};
+
+use crate::emoji;
+use crate::io;
+use crate::output::{self, Output};
+
+use super::gains::show_lot_error;
+use super::{booking_flag, read_booking, read_date, read_journal};
+
+/// Creates a command that compares the cost of the lots held with their market value, for every
+/// lot and for every account.
+pub fn create() -> Command {
+    Command::new("unrealized")
+        .usage("[file path] Shows the cost and the market value of every lot held")
+        .flag(output::flag())
+        .flag(booking_flag())
+        .flag(
+            Flag::new("at", FlagType::String)
+                .description("Date of the holdings and prices as year-month-day, today by default"),
+        )
+        .action(handler)
+}
+
+fn handler(context: &Context) {
+    let path = match context.args.first() {
+        Some(path) => path,
+        None => return io::show_error(emoji::for_error(), "No file given"),
+    };
+    let output = match output::from_context(context) {
+        Some(output) => output,
+        None => std::process::exit(1),
+    };
+    let (booking, at) = match (read_booking(context), read_date(context, "at")) {
+        (Ok(booking), Ok(at)) => (booking, at.unwrap_or_else(|| Local::now().date_naive())),
+        _ => std::process::exit(1),
+    };
+
+    let journal = match read_journal(path) {
+        Ok(journal) => journal,
+        Err(_) => std::process::exit(1),
+    };
+    let prices = Prices::from_journal(&journal);
+    let mut transactions = journal::resolve(journal);
+    transactions.retain(|transaction| transaction.date.date() <= at);
+
+    match lots::book(&transactions, booking)
+        .and_then(|lots| unrealized::report(&lots.holdings, &prices, at).map_err(LotError::from))
+    {
+        Ok(report) => match output {
+            Output::Text => show_report(&report),
+            Output::Json => output::print_json(&report),
+            Output::Csv => output::print_csv(&report.holdings),
+        },
+        Err(error) => {
+            show_lot_error(error);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn show_report(report: &UnrealizedReport) {
+    let column_width = |value: fn(&unrealized::HoldingRow) -> usize| {
+        report.holdings.iter().map(value).max().unwrap_or_default()
+    };
+    let account_width = column_width(|holding| holding.account.len());
+    let units_width = column_width(|holding| holding.quantity.len() + holding.commodity.len() + 1);
+    let cost_width = column_width(|holding| holding.cost.len());
+    let market_width = column_width(|holding| holding.market.len());
+    let gain_width = column_width(|holding| holding.gain.len() + holding.currency.len() + 1);
+
+    for holding in &report.holdings {
+        println!(
+            "{}  {:account_width$}  {:>units_width$}  {:>cost_width$}  {:>market_width$}  {:>gain_width$}",
+            holding.date,
+            holding.account,
+            format!("{} {}", holding.quantity, holding.commodity),
+            holding.cost,
+            holding.market,
+            format!("{} {}", holding.gain, holding.currency),
+            account_width = account_width,
+            units_width = units_width,
+            cost_width = cost_width,
+            market_width = market_width,
+            gain_width = gain_width
+        );
+    }
+
+    if !report.holdings.is_empty() {
+        println!();
+    }
+    for account in &report.accounts {
+        println!(
+            "{}  {}  {}  {} {}",
+            account.account, account.cost, account.market, account.gain, account.currency
+        );
+    }
+}
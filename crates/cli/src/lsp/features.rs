@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+
+use core::editor::{self, CompletionKind, Edit};
+use core::io::file;
+use core::journal;
+use core::parser::ast::{self, Query, Span};
+use core::report::balance;
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CompletionItem, CompletionItemKind,
+    CompletionResponse, CompletionTextEdit, Diagnostic, DiagnosticSeverity, GotoDefinitionResponse,
+    Hover, HoverContents, Location, MarkupContent, MarkupKind, NumberOrString, Position, Range,
+    TextDocumentIdentifier, TextDocumentPositionParams, TextEdit, Url, WorkspaceEdit,
+};
+
+use super::Document;
+
+/// Returns the problems found while checking the journal together with the journals it includes,
+/// if it's a file, leaving out the problems found inside the included journals.
+pub fn diagnostics(content: &str, path: Option<&str>) -> Vec<Diagnostic> {
+    let diagnostics = match path {
+        Some(path) => journal::diagnose_with(content, path, &mut file::read_content),
+        None => journal::diagnose(content),
+    };
+
+    diagnostics
+        .into_iter()
+        .filter(|diagnostic| diagnostic.path.is_none())
+        .map(|diagnostic| Diagnostic {
+            range: span_range(diagnostic.span),
+            severity: Some(if diagnostic.code.is_error() {
+                DiagnosticSeverity::ERROR
+            } else {
+                DiagnosticSeverity::WARNING
+            }),
+            code: Some(NumberOrString::String(diagnostic.code.code().to_owned())),
+            source: Some("pledger".to_owned()),
+            message: diagnostic.message,
+            ..Diagnostic::default()
+        })
+        .collect()
+}
+
+/// Suggests accounts, payees, tags or currencies depending on what is being written.
+pub fn completion(
+    documents: &HashMap<Url, Document>,
+    params: TextDocumentPositionParams,
+) -> Option<CompletionResponse> {
+    let document = documents.get(&params.text_document.uri)?;
+    let line = line_before(&document.content, params.position);
+    let (candidates, kind) = match editor::completion_kind(&line) {
+        CompletionKind::Account => (
+            journal::accounts(&document.journal),
+            CompletionItemKind::MODULE,
+        ),
+        CompletionKind::Payee => (
+            journal::payees(&document.journal),
+            CompletionItemKind::VALUE,
+        ),
+        CompletionKind::Tag => (
+            journal::tags(&document.journal),
+            CompletionItemKind::KEYWORD,
+        ),
+        CompletionKind::Currency => (
+            journal::currencies(&document.journal),
+            CompletionItemKind::UNIT,
+        ),
+        CompletionKind::Nothing => return None,
+    };
+
+    // Clients usually don't consider colons part of a word, so the whole word is replaced
+    let word_length = line
+        .chars()
+        .rev()
+        .take_while(|c| !c.is_whitespace() && *c != ';' && *c != ',')
+        .count();
+    let range = Range {
+        start: Position {
+            line: params.position.line,
+            character: params.position.character - word_length as u32,
+        },
+        end: params.position,
+    };
+
+    Some(CompletionResponse::Array(
+        candidates
+            .into_iter()
+            .map(|candidate| CompletionItem {
+                label: candidate.clone(),
+                kind: Some(kind),
+                text_edit: Some(CompletionTextEdit::Edit(TextEdit {
+                    range,
+                    new_text: candidate,
+                })),
+                ..CompletionItem::default()
+            })
+            .collect(),
+    ))
+}
+
+/// Shows the balance of the account under the cursor.
+pub fn hover(
+    documents: &HashMap<Url, Document>,
+    params: TextDocumentPositionParams,
+) -> Option<Hover> {
+    let document = documents.get(&params.text_document.uri)?;
+    let account = word_at(&document.content, params.position)?;
+    let report =
+        balance::report(&journal::resolve(document.journal.clone()), &Query(vec![])).ok()?;
+    let balances = report
+        .rows
+        .iter()
+        .filter(|row| row.account == account)
+        .map(|row| format!("- {} {}", row.quantity, row.currency))
+        .collect::<Vec<String>>();
+
+    if balances.is_empty() {
+        return None;
+    }
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: format!("**{}**\n\n{}", account, balances.join("\n")),
+        }),
+        range: None,
+    })
+}
+
+/// Returns the `account` declaration of the account under the cursor.
+pub fn definition(
+    documents: &HashMap<Url, Document>,
+    params: TextDocumentPositionParams,
+) -> Option<GotoDefinitionResponse> {
+    let document = documents.get(&params.text_document.uri)?;
+    let account = word_at(&document.content, params.position)?;
+    let span = editor::find_declaration(&document.content, &account)?;
+
+    Some(GotoDefinitionResponse::Scalar(Location {
+        uri: params.text_document.uri,
+        range: span_range(span),
+    }))
+}
+
+/// Formats every element of the journal, or nothing if the journal can't be parsed.
+pub fn formatting(
+    documents: &HashMap<Url, Document>,
+    text_document: TextDocumentIdentifier,
+) -> Option<Vec<TextEdit>> {
+    let document = documents.get(&text_document.uri)?;
+
+    editor::format_edits(&document.content)
+        .ok()
+        .map(|edits| edits.into_iter().map(text_edit).collect())
+}
+
+/// Offers to write the balancing amount of the transactions in the given range that omitted it.
+pub fn code_actions(
+    documents: &HashMap<Url, Document>,
+    text_document: TextDocumentIdentifier,
+    range: Range,
+) -> Option<Vec<CodeActionOrCommand>> {
+    let document = documents.get(&text_document.uri)?;
+    // Lines of the journal start at 1, while lines of the editor start at 0
+    let (first_line, last_line) = (range.start.line as usize + 1, range.end.line as usize + 1);
+
+    Some(
+        editor::balancing_amounts(&document.content)
+            .into_iter()
+            .filter(|balancing_amount| {
+                balancing_amount.transaction.start.line <= last_line
+                    && balancing_amount.transaction.end.line >= first_line
+            })
+            .map(|balancing_amount| {
+                CodeActionOrCommand::CodeAction(CodeAction {
+                    title: "Insert balancing amount".to_owned(),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    edit: Some(WorkspaceEdit {
+                        changes: Some(HashMap::from([(
+                            text_document.uri.clone(),
+                            vec![text_edit(balancing_amount.edit)],
+                        )])),
+                        ..WorkspaceEdit::default()
+                    }),
+                    ..CodeAction::default()
+                })
+            })
+            .collect(),
+    )
+}
+
+fn line_before(content: &str, position: Position) -> String {
+    content
+        .lines()
+        .nth(position.line as usize)
+        .unwrap_or_default()
+        .chars()
+        .take(position.character as usize)
+        .collect()
+}
+
+fn word_at(content: &str, position: Position) -> Option<String> {
+    let line = content.lines().nth(position.line as usize)?;
+    editor::word_at(line, position.character as usize + 1)
+}
+
+fn text_edit(edit: Edit) -> TextEdit {
+    TextEdit {
+        range: Range {
+            start: lsp_position(edit.start),
+            end: lsp_position(edit.end),
+        },
+        new_text: edit.text,
+    }
+}
+
+/// Converts a span, whose end is the last character, into a range that ends after it.
+fn span_range(span: Span) -> Range {
+    Range {
+        start: lsp_position(span.start),
+        end: Position {
+            line: span.end.line as u32 - 1,
+            character: span.end.column as u32,
+        },
+    }
+}
+
+/// Converts a position of the journal into an editor position, which starts at 0. Columns are
+/// counted in characters, which matches the editor count for every character outside of the
+/// supplementary planes.
+fn lsp_position(position: ast::Position) -> Position {
+    Position {
+        line: position.line as u32 - 1,
+        character: position.column as u32 - 1,
+    }
+}
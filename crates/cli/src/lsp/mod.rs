@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+    PublishDiagnostics,
+};
+use lsp_types::request::{CodeActionRequest, Completion, Formatting, GotoDefinition, HoverRequest};
+use lsp_types::{
+    CodeActionProviderCapability, CompletionOptions, DidChangeTextDocumentParams,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, HoverProviderCapability, OneOf,
+    PublishDiagnosticsParams, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind,
+    Url,
+};
+
+use core::io::file;
+use core::journal::include;
+use core::parser::{self, ast::JournalElement};
+
+mod features;
+
+/// Represents a journal opened in the editor.
+pub struct Document {
+    pub content: String,
+    /// Elements of the last version of the content that could be parsed, together with the
+    /// elements of the journals it includes, which are used for suggestions while the content is
+    /// being edited and can't be parsed.
+    pub journal: Vec<JournalElement>,
+}
+
+/// Runs a language server that communicates through the standard input and output until the
+/// editor shuts it down.
+pub fn run() -> Result<(), String> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![":".to_owned(), ";".to_owned(), ",".to_owned()]),
+            ..CompletionOptions::default()
+        }),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        ..ServerCapabilities::default()
+    };
+
+    serde_json::to_value(capabilities)
+        .map_err(|err| err.to_string())
+        .and_then(|capabilities| {
+            connection
+                .initialize(capabilities)
+                .map_err(|err| err.to_string())
+        })?;
+    serve(connection)?;
+    io_threads.join().map_err(|err| err.to_string())
+}
+
+fn serve(connection: Connection) -> Result<(), String> {
+    let mut documents: HashMap<Url, Document> = HashMap::new();
+
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection
+                    .handle_shutdown(&request)
+                    .map_err(|err| err.to_string())?
+                {
+                    return Ok(());
+                }
+
+                send(
+                    &connection,
+                    Message::Response(respond(&documents, &request)),
+                )?;
+            }
+            Message::Notification(notification) => {
+                if let Some(uri) = update_documents(&mut documents, notification) {
+                    let diagnostics = documents
+                        .get(&uri)
+                        .map(|document| {
+                            features::diagnostics(&document.content, file_path(&uri).as_deref())
+                        })
+                        .unwrap_or_default();
+                    let params = PublishDiagnosticsParams {
+                        uri,
+                        diagnostics,
+                        version: None,
+                    };
+
+                    send(
+                        &connection,
+                        Message::Notification(Notification::new(
+                            PublishDiagnostics::METHOD.to_owned(),
+                            params,
+                        )),
+                    )?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn send(connection: &Connection, message: Message) -> Result<(), String> {
+    connection
+        .sender
+        .send(message)
+        .map_err(|err| err.to_string())
+}
+
+/// Keeps the documents in sync with the editor, returning the document that changed if any.
+fn update_documents(
+    documents: &mut HashMap<Url, Document>,
+    notification: Notification,
+) -> Option<Url> {
+    let (uri, content) = match notification.method.as_str() {
+        DidOpenTextDocument::METHOD => {
+            let params: DidOpenTextDocumentParams =
+                serde_json::from_value(notification.params).ok()?;
+            (params.text_document.uri, params.text_document.text)
+        }
+        DidChangeTextDocument::METHOD => {
+            let params: DidChangeTextDocumentParams =
+                serde_json::from_value(notification.params).ok()?;
+            let content = params.content_changes.into_iter().last()?.text;
+            (params.text_document.uri, content)
+        }
+        DidCloseTextDocument::METHOD => {
+            let params: DidCloseTextDocumentParams =
+                serde_json::from_value(notification.params).ok()?;
+            documents.remove(&params.text_document.uri);
+            return Some(params.text_document.uri);
+        }
+        _ => return None,
+    };
+
+    let journal = match (parser::parse_journal(&content), documents.remove(&uri)) {
+        (Ok(journal), _) => match file_path(&uri) {
+            // Journals whose includes can't be expanded are still useful for suggestions.
+            Some(path) => {
+                include::expand(journal.clone(), &path, &mut file::read_content).unwrap_or(journal)
+            }
+            None => journal,
+        },
+        (Err(_), Some(document)) => document.journal,
+        (Err(_), None) => vec![],
+    };
+    documents.insert(uri.clone(), Document { content, journal });
+    Some(uri)
+}
+
+/// Returns the path of the document if it's a file, which is needed to find the journals it
+/// includes.
+fn file_path(uri: &Url) -> Option<String> {
+    uri.to_file_path()
+        .ok()
+        .and_then(|path| path.to_str().map(str::to_owned))
+}
+
+fn respond(documents: &HashMap<Url, Document>, request: &Request) -> Response {
+    handle::<Completion>(request, |params| {
+        features::completion(documents, params.text_document_position)
+    })
+    .or_else(|| {
+        handle::<HoverRequest>(request, |params| {
+            features::hover(documents, params.text_document_position_params)
+        })
+    })
+    .or_else(|| {
+        handle::<GotoDefinition>(request, |params| {
+            features::definition(documents, params.text_document_position_params)
+        })
+    })
+    .or_else(|| {
+        handle::<Formatting>(request, |params| {
+            features::formatting(documents, params.text_document)
+        })
+    })
+    .or_else(|| {
+        handle::<CodeActionRequest>(request, |params| {
+            features::code_actions(documents, params.text_document, params.range)
+        })
+    })
+    .unwrap_or_else(|| {
+        Response::new_err(
+            request.id.clone(),
+            ErrorCode::MethodNotFound as i32,
+            format!("Unknown method \"{}\"", request.method),
+        )
+    })
+}
+
+/// Answers the request with the given handler if the request is of type `R`.
+fn handle<R: lsp_types::request::Request>(
+    request: &Request,
+    handler: impl FnOnce(R::Params) -> R::Result,
+) -> Option<Response> {
+    if request.method != R::METHOD {
+        return None;
+    }
+
+    Some(
+        match serde_json::from_value::<R::Params>(request.params.clone()) {
+            Ok(params) => Response::new_ok(request.id.clone(), handler(params)),
+            Err(err) => Response::new_err(
+                request.id.clone(),
+                ErrorCode::InvalidParams as i32,
+                err.to_string(),
+            ),
+        },
+    )
+}
@@ -1,6 +1,11 @@
 mod commands;
 mod emoji;
 mod io;
+mod lsp;
+mod output;
+mod server;
+mod tui;
+mod watch;
 
 use seahorse::App;
 use std::env;
@@ -12,7 +17,24 @@ fn main() {
         .author(env!("CARGO_PKG_AUTHORS"))
         .version(env!("CARGO_PKG_VERSION"))
         .usage("pledger [args]")
-        .command(commands::check::create());
+        .command(commands::check::create())
+        .command(commands::balance::create())
+        .command(commands::register::create())
+        .command(commands::print::create())
+        .command(commands::gains::create())
+        .command(commands::unrealized::create())
+        .command(commands::networth::create())
+        .command(commands::chart::create())
+        .command(commands::html::create())
+        .command(commands::serve::create())
+        .command(commands::close::create())
+        .command(commands::reconcile::create())
+        .command(commands::add::create())
+        .command(commands::tui::create())
+        .command(commands::lsp::create())
+        .command(commands::import::create())
+        .command(commands::export::create())
+        .command(commands::convert::create());
 
     #[cfg(debug_assertions)]
     let app = { app.command(commands::debug::create()) };
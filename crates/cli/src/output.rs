@@ -0,0 +1,60 @@
+use seahorse::{Context, Flag, FlagType};
+use serde::Serialize;
+
+use crate::emoji;
+use crate::io::show_error;
+
+/// Defines the formats in which commands can show their results.
+#[derive(PartialEq)]
+pub enum Output {
+    Text,
+    Json,
+    Csv,
+}
+
+/// Creates the flag that selects the output of a command.
+pub fn flag() -> Flag {
+    Flag::new("output", FlagType::String)
+        .alias("o")
+        .description("Format of the output (text, json or csv), text by default")
+}
+
+/// Returns the output selected in the context, showing an error if the output is unknown.
+pub fn from_context(context: &Context) -> Option<Output> {
+    match context.string_flag("output").as_deref() {
+        Ok("text") | Err(_) => Some(Output::Text),
+        Ok("json") => Some(Output::Json),
+        Ok("csv") => Some(Output::Csv),
+        Ok(output) => {
+            show_error(
+                emoji::for_error(),
+                format!("Unknown output \"{}\", use text, json or csv", output),
+            );
+            None
+        }
+    }
+}
+
+/// Prints the given value as pretty printed JSON.
+pub fn print_json<T: Serialize>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{}", json),
+        Err(err) => show_error(
+            emoji::for_error(),
+            format!("Could not create JSON: {}", err),
+        ),
+    }
+}
+
+/// Prints the given rows as CSV, with a header that contains the name of each field.
+pub fn print_csv<T: Serialize>(rows: &[T]) {
+    let mut writer = csv::Writer::from_writer(std::io::stdout());
+
+    if let Err(err) = rows
+        .iter()
+        .try_for_each(|row| writer.serialize(row))
+        .and_then(|_| writer.flush().map_err(csv::Error::from))
+    {
+        show_error(emoji::for_error(), format!("Could not create CSV: {}", err));
+    }
+}
@@ -0,0 +1,64 @@
+use core::parser::{self, ast::Query};
+use core::report::journal::{self as journal_report, TransactionReport};
+use serde::Serialize;
+use serde_json::json;
+
+use super::Journal;
+
+/// Answers a request to the endpoint in the given path with its status and JSON body. Endpoints
+/// that list postings or transactions take a query in the `query` parameter.
+pub fn respond(journal: &Journal, path: &str, parameters: Option<&str>) -> (u16, String) {
+    let journal = match journal {
+        Ok(journal) => journal,
+        Err(err) => return error(500, err),
+    };
+    let query = match read_query(parameters) {
+        Ok(query) => query,
+        Err(err) => return error(400, &err),
+    };
+
+    match path {
+        "/api/accounts" => ok(&journal.accounts()),
+        "/api/balance" => match journal.balance(&query) {
+            Ok(report) => ok(&report),
+            Err(err) => error(500, &err.to_string()),
+        },
+        "/api/register" => match journal.register(&query) {
+            Ok(report) => ok(&report),
+            Err(err) => error(500, &err.to_string()),
+        },
+        "/api/transactions" => ok(&journal
+            .filter(&query)
+            .into_iter()
+            .map(TransactionReport::from)
+            .collect::<Vec<TransactionReport>>()),
+        "/api/prices" => ok(&journal_report::report(journal.elements().to_vec()).prices),
+        _ => error(404, &format!("Unknown endpoint \"{}\"", path)),
+    }
+}
+
+/// Returns the status with a JSON body that describes the error.
+pub fn error(status: u16, message: &str) -> (u16, String) {
+    (status, json!({ "error": message }).to_string())
+}
+
+fn ok<T: Serialize>(value: &T) -> (u16, String) {
+    match serde_json::to_string(value) {
+        Ok(body) => (200, body),
+        Err(err) => error(500, &err.to_string()),
+    }
+}
+
+fn read_query(parameters: Option<&str>) -> Result<Query, String> {
+    let query = parameters
+        .map(|parameters| form_urlencoded::parse(parameters.as_bytes()))
+        .and_then(|mut pairs| pairs.find(|(key, _)| key == "query"))
+        .map(|(_, value)| value.into_owned())
+        .unwrap_or_default();
+
+    if query.trim().is_empty() {
+        return Ok(Query(vec![]));
+    }
+
+    parser::parse_query(&query).map_err(|err| err.to_string())
+}
@@ -0,0 +1,76 @@
+use std::sync::{Arc, RwLock};
+use std::thread;
+
+use tiny_http::{Header, Method, Response, Server};
+
+use crate::emoji;
+use crate::io;
+use crate::watch;
+
+mod api;
+
+/// Page of the web interface, which only uses the JSON endpoints.
+const UI: &str = include_str!("ui.html");
+
+/// Journal being served, or the reason why it could not be read or parsed.
+type Journal = Result<core::Journal, String>;
+
+/// Serves the journal in the given path on localhost until the process is stopped, reading it
+/// again every time it changes.
+pub fn run(path: &str, port: u16) -> Result<(), String> {
+    let server = Server::http(("127.0.0.1", port))
+        .map_err(|err| format!("Could not listen on port {}: {}", port, err))?;
+    let journal = Arc::new(RwLock::new(load(path)));
+
+    let watched_path = path.to_owned();
+    let watched_journal = Arc::clone(&journal);
+    thread::spawn(move || {
+        let result = watch::on_change(&watched_path, || {
+            if let Ok(mut journal) = watched_journal.write() {
+                *journal = load(&watched_path);
+            }
+        });
+
+        if let Err(err) = result {
+            eprintln!("{}", io::text_for_error(emoji::for_error(), err));
+        }
+    });
+
+    io::show_info(
+        emoji::for_success(),
+        format!("Serving \"{}\" on http://localhost:{}", path, port),
+    );
+
+    for request in server.incoming_requests() {
+        let (path, query) = match request.url().split_once('?') {
+            Some((path, query)) => (path.to_owned(), Some(query.to_owned())),
+            None => (request.url().to_owned(), None),
+        };
+        let (status, content_type, body) = match (request.method(), path.as_str()) {
+            (Method::Get, "/") => (200, "text/html; charset=utf-8", UI.to_owned()),
+            (Method::Get, _) => {
+                let (status, body) = match journal.read() {
+                    Ok(journal) => api::respond(&journal, &path, query.as_deref()),
+                    Err(_) => api::error(500, "The journal could not be read"),
+                };
+                (status, "application/json", body)
+            }
+            _ => {
+                let (status, body) = api::error(405, "Only GET requests are supported");
+                (status, "application/json", body)
+            }
+        };
+
+        let mut response = Response::from_string(body).with_status_code(status);
+        if let Ok(header) = Header::from_bytes("Content-Type", content_type) {
+            response.add_header(header);
+        }
+        let _ = request.respond(response);
+    }
+
+    Ok(())
+}
+
+fn load(path: &str) -> Journal {
+    core::Journal::from_path(path).map_err(|err| err.to_string())
+}
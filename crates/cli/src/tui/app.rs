@@ -0,0 +1,292 @@
+use core::formatter;
+use core::io::file;
+use core::journal::{self, include};
+use core::parser::{
+    self,
+    ast::{Query, Transaction},
+};
+use core::period::Period;
+use core::reconcile;
+use core::report::register::{self, AccountEntry};
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::widgets::{ListState, TableState};
+
+/// Defines the panes that can receive the keys that move the selection.
+#[derive(PartialEq)]
+pub enum Focus {
+    Accounts,
+    Register,
+}
+
+/// Represents a posting of the register of the selected account.
+pub struct RegisterRow {
+    /// Position of the transaction of the posting among the transactions of the journal.
+    pub index: usize,
+    pub date: String,
+    pub payee: String,
+    pub account: String,
+    pub amount: String,
+    pub total: String,
+}
+
+impl From<&AccountEntry> for RegisterRow {
+    fn from(entry: &AccountEntry) -> Self {
+        RegisterRow {
+            index: entry.index,
+            date: formatter::format_date(&entry.transaction.date),
+            payee: entry.transaction.payee.clone(),
+            account: entry.posting.account.to_string(),
+            amount: entry
+                .posting
+                .amount
+                .as_ref()
+                .map(formatter::format_amount)
+                .unwrap_or_default(),
+            total: entry
+                .totals
+                .iter()
+                .map(|(currency, quantity)| format!("{} {}", quantity, currency))
+                .collect::<Vec<String>>()
+                .join(", "),
+        }
+    }
+}
+
+/// Holds the state of the terminal interface.
+pub struct App {
+    path: String,
+    transactions: Vec<Transaction>,
+    /// Journal and position in it of every transaction, which can be in an included journal.
+    origins: Vec<(String, usize)>,
+    pub accounts: Vec<String>,
+    pub account_state: ListState,
+    pub rows: Vec<RegisterRow>,
+    pub register_state: TableState,
+    pub focus: Focus,
+    pub period: Period,
+    pub filter: String,
+    pub editing_filter: bool,
+    pub message: Option<String>,
+    pub should_quit: bool,
+}
+
+impl App {
+    /// Creates the state for the journal in the given path, returning an error if the journal
+    /// can't be read or parsed.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let mut app = App {
+            path: path.to_owned(),
+            transactions: vec![],
+            origins: vec![],
+            accounts: vec![],
+            account_state: ListState::default(),
+            rows: vec![],
+            register_state: TableState::default(),
+            focus: Focus::Accounts,
+            period: Period::All,
+            filter: String::new(),
+            editing_filter: false,
+            message: None,
+            should_quit: false,
+        };
+
+        app.reload()?;
+        app.account_state.select(Some(0));
+        app.refresh();
+        Ok(app)
+    }
+
+    /// Returns the transaction of the selected row of the register.
+    pub fn selected_transaction(&self) -> Option<&Transaction> {
+        self.register_state
+            .selected()
+            .and_then(|selected| self.rows.get(selected))
+            .and_then(|row| self.transactions.get(row.index))
+    }
+
+    /// Updates the state with the given key.
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if self.editing_filter {
+            return self.handle_filter_key(key);
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::Accounts => Focus::Register,
+                    Focus::Register => Focus::Accounts,
+                }
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Left | KeyCode::Char('h') => self.set_period(self.period.previous()),
+            KeyCode::Right | KeyCode::Char('l') => self.set_period(self.period.next()),
+            KeyCode::Char('m') => self.set_period(Period::month_of(self.latest_date())),
+            KeyCode::Char('y') => self.set_period(Period::year_of(self.latest_date())),
+            KeyCode::Char('a') => self.set_period(Period::All),
+            KeyCode::Char('/') => self.editing_filter = true,
+            KeyCode::Char(' ') => self.toggle_status(),
+            _ => {}
+        }
+    }
+
+    fn handle_filter_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter | KeyCode::Esc => self.editing_filter = false,
+            KeyCode::Backspace => {
+                self.filter.pop();
+            }
+            KeyCode::Char(c) => self.filter.push(c),
+            _ => return,
+        }
+
+        self.refresh();
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let (state, length) = match self.focus {
+            Focus::Accounts => (
+                &mut self.account_state as &mut dyn Selection,
+                self.accounts.len(),
+            ),
+            Focus::Register => (
+                &mut self.register_state as &mut dyn Selection,
+                self.rows.len(),
+            ),
+        };
+
+        if length == 0 {
+            return;
+        }
+
+        let selected = state.selected_position().unwrap_or(0) as isize + offset;
+        state.select_position(Some(selected.clamp(0, length as isize - 1) as usize));
+
+        if self.focus == Focus::Accounts {
+            self.refresh();
+        }
+    }
+
+    fn set_period(&mut self, period: Period) {
+        self.period = period;
+        self.refresh();
+    }
+
+    fn latest_date(&self) -> chrono::NaiveDate {
+        self.transactions
+            .iter()
+            .map(|transaction| transaction.date.date())
+            .max()
+            .unwrap_or_else(|| chrono::Local::now().date_naive())
+    }
+
+    /// Reads and parses the journal and the journals it includes again, keeping the selections.
+    fn reload(&mut self) -> Result<(), String> {
+        let content = file::read_content(&self.path)
+            .map_err(|_| format!("Could not read the file \"{}\"", self.path))?;
+        let journal = parser::parse_journal(&content)
+            .map_err(|err| format!("There was an error parsing the journal:\n{}", err))?;
+        let origins = include::origins(&journal, &self.path, &mut file::read_content);
+        let journal = include::expand(journal, &self.path, &mut file::read_content)
+            .map_err(|err| err.to_string())?;
+
+        self.origins = origins;
+        self.accounts = journal::accounts(&journal);
+        self.transactions = journal::resolve(journal);
+        Ok(())
+    }
+
+    /// Computes the rows of the register for the selected account, period and filter.
+    fn refresh(&mut self) {
+        let query = match parser::parse_query(&self.filter) {
+            Ok(query) => {
+                self.message = None;
+                query
+            }
+            Err(_) if self.filter.trim().is_empty() => {
+                self.message = None;
+                Query(vec![])
+            }
+            Err(_) => {
+                self.message = Some("The filter is not a valid query".to_owned());
+                Query(vec![])
+            }
+        };
+        let account = self
+            .account_state
+            .selected()
+            .and_then(|selected| self.accounts.get(selected))
+            .cloned()
+            .unwrap_or_default();
+
+        self.rows =
+            match register::account_report(&self.transactions, &account, self.period, &query) {
+                Ok(entries) => entries.iter().map(RegisterRow::from).collect(),
+                Err(err) => {
+                    self.message = Some(err.to_string());
+                    vec![]
+                }
+            };
+        let selected = self.register_state.selected().unwrap_or(0);
+        self.register_state.select(if self.rows.is_empty() {
+            None
+        } else {
+            Some(selected.min(self.rows.len() - 1))
+        });
+    }
+
+    /// Switches the status of the selected transaction and writes the change to the journal.
+    fn toggle_status(&mut self) {
+        let (path, index) = match self
+            .register_state
+            .selected()
+            .and_then(|selected| self.rows.get(selected))
+            .and_then(|row| self.origins.get(row.index))
+        {
+            Some((path, index)) => (path.clone(), *index),
+            None => return,
+        };
+
+        let result = file::read_content(&path)
+            .map_err(|_| "Could not read the file".to_owned())
+            .and_then(|content| reconcile::toggle_status(&content, index))
+            .and_then(|content| {
+                file::replace_content(&path, &content)
+                    .map_err(|_| "Could not write the file".to_owned())
+            })
+            .and_then(|_| self.reload());
+
+        self.refresh();
+        self.message = Some(match result {
+            Ok(_) => "Status changed".to_owned(),
+            Err(err) => err,
+        });
+    }
+}
+
+/// Allows moving the selection of lists and tables in the same way.
+trait Selection {
+    fn selected_position(&self) -> Option<usize>;
+    fn select_position(&mut self, position: Option<usize>);
+}
+
+impl Selection for ListState {
+    fn selected_position(&self) -> Option<usize> {
+        self.selected()
+    }
+
+    fn select_position(&mut self, position: Option<usize>) {
+        self.select(position)
+    }
+}
+
+impl Selection for TableState {
+    fn selected_position(&self) -> Option<usize> {
+        self.selected()
+    }
+
+    fn select_position(&mut self, position: Option<usize>) {
+        self.select(position)
+    }
+}
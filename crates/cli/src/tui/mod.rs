@@ -0,0 +1,28 @@
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+
+mod app;
+mod ui;
+
+/// Runs the terminal interface for the journal in the given path until the user quits, returning
+/// an error if the journal can't be read or the terminal fails.
+pub fn run(path: &str) -> Result<(), String> {
+    let mut app = app::App::load(path)?;
+    let mut terminal = ratatui::init();
+
+    let result = (|| -> std::io::Result<()> {
+        while !app.should_quit {
+            terminal.draw(|frame| ui::draw(frame, &mut app))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    app.handle_key(key);
+                }
+            }
+        }
+
+        Ok(())
+    })();
+
+    ratatui::restore();
+    result.map_err(|err| err.to_string())
+}
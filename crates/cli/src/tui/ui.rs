@@ -0,0 +1,105 @@
+use core::formatter;
+use core::parser::ast::TransactionStatus;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Row, Table};
+use ratatui::Frame;
+
+use super::app::{App, Focus};
+
+const HELP: &str =
+    "tab: pane  /: filter  h/l: period  m/y/a: month/year/all  space: status  q: quit";
+
+/// Draws the account tree, the register of the selected account, the selected transaction and a
+/// status line with the period and the filter.
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let [main_area, status_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(3)]).areas(frame.area());
+    let [accounts_area, right_area] =
+        Layout::horizontal([Constraint::Percentage(25), Constraint::Percentage(75)])
+            .areas(main_area);
+    let [register_area, detail_area] =
+        Layout::vertical([Constraint::Percentage(65), Constraint::Percentage(35)])
+            .areas(right_area);
+
+    let highlight_style = Style::default().add_modifier(Modifier::REVERSED);
+
+    let accounts = List::new(app.accounts.iter().map(|account| {
+        let depth = account.matches(':').count();
+        let name = account.rsplit(':').next().unwrap_or_default();
+        ListItem::new(format!("{}{}", "  ".repeat(depth), name))
+    }))
+    .block(pane("Accounts", app.focus == Focus::Accounts))
+    .highlight_style(highlight_style);
+    frame.render_stateful_widget(accounts, accounts_area, &mut app.account_state);
+
+    let register_title = format!("Register ({})", app.period);
+    let register = Table::new(
+        app.rows.iter().map(|row| {
+            Row::new(vec![
+                row.date.clone(),
+                row.payee.clone(),
+                row.account.clone(),
+                row.amount.clone(),
+                row.total.clone(),
+            ])
+        }),
+        [
+            Constraint::Length(10),
+            Constraint::Percentage(25),
+            Constraint::Percentage(25),
+            Constraint::Percentage(15),
+            Constraint::Percentage(25),
+        ],
+    )
+    .header(
+        Row::new(vec!["Date", "Payee", "Account", "Amount", "Total"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(pane(&register_title, app.focus == Focus::Register))
+    .row_highlight_style(highlight_style);
+    frame.render_stateful_widget(register, register_area, &mut app.register_state);
+
+    let detail = match app.selected_transaction() {
+        Some(transaction) => format!(
+            "{}\nStatus: {}",
+            formatter::format_transaction(transaction),
+            match transaction.status {
+                TransactionStatus::Cleared => "cleared",
+                TransactionStatus::Pending => "pending",
+            }
+        ),
+        None => String::new(),
+    };
+    frame.render_widget(
+        Paragraph::new(detail).block(pane("Transaction", false)),
+        detail_area,
+    );
+
+    let status = if app.editing_filter {
+        format!("Filter: {}_", app.filter)
+    } else {
+        format!(
+            "Filter: {}  |  {}",
+            app.filter,
+            app.message.as_deref().unwrap_or(HELP)
+        )
+    };
+    frame.render_widget(
+        Paragraph::new(status).block(pane("", app.editing_filter)),
+        status_area,
+    );
+}
+
+fn pane(title: &str, focused: bool) -> Block<'_> {
+    let border_style = if focused {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default()
+    };
+
+    Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(border_style)
+}
@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use console::Term;
+use core::io::file;
+use core::journal::include;
+use core::parser;
+use notify_debouncer_mini::new_debouncer;
+use notify_debouncer_mini::notify::{RecursiveMode, Watcher};
+use seahorse::{Flag, FlagType};
+
+/// Time that has to pass without changes before running again, so that editors that save a file
+/// in several steps only cause one run.
+const DEBOUNCE_TIME: Duration = Duration::from_millis(300);
+
+/// Returns the flag that makes a command run again every time its journal changes.
+pub fn flag() -> Flag {
+    Flag::new("watch", FlagType::Bool)
+        .alias("w")
+        .description("Runs again every time the journal changes")
+}
+
+/// Runs the given function and runs it again, after clearing the terminal, every time the journal
+/// in the given path or one of the journals it includes changes. It only returns if the journal
+/// can't be watched.
+pub fn watch(path: &str, mut run: impl FnMut()) -> Result<(), String> {
+    run();
+    on_change(path, || {
+        let _ = Term::stdout().clear_screen();
+        run();
+    })
+}
+
+/// Calls the given function every time the journal in the given path or one of the journals it
+/// includes changes. It only returns if the journal can't be watched.
+pub fn on_change(path: &str, mut changed: impl FnMut()) -> Result<(), String> {
+    let (sender, receiver) = mpsc::channel();
+    let mut debouncer = new_debouncer(DEBOUNCE_TIME, sender)
+        .map_err(|err| format!("Could not watch the journal: {}", err))?;
+    let mut directories = HashSet::new();
+    let mut files = watched_files(path)?;
+    watch_directories(debouncer.watcher(), &files, &mut directories)?;
+
+    for events in receiver {
+        let is_change = events.is_ok_and(|events| {
+            events.iter().any(|event| {
+                files.iter().any(|(directory, name)| {
+                    event.path.parent() == Some(directory.as_path())
+                        && event.path.file_name() == Some(name.as_os_str())
+                })
+            })
+        });
+
+        if is_change {
+            changed();
+            // The change could have added includes, which have to be watched too
+            files = watched_files(path)?;
+            watch_directories(debouncer.watcher(), &files, &mut directories)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the directory and name of the journal in the given path and of every journal it
+/// includes. Included journals in directories that don't exist can't be watched, so are skipped.
+fn watched_files(path: &str) -> Result<Vec<(PathBuf, OsString)>, String> {
+    let included = match file::read_content(path).map(|content| parser::parse_journal(&content)) {
+        Ok(Ok(journal)) => include::paths(&journal, path, &mut file::read_content),
+        _ => vec![],
+    };
+    let mut files = vec![watched_file(path)
+        .ok_or_else(|| format!("Could not watch the journal: {} can't be found", path))?];
+    files.extend(included.iter().filter_map(|path| watched_file(path)));
+
+    Ok(files)
+}
+
+fn watched_file(path: &str) -> Option<(PathBuf, OsString)> {
+    let path = Path::new(path);
+    // Many editors save by replacing the file, so its directory is watched instead of the file
+    let directory = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+
+    Some((
+        directory.canonicalize().ok()?,
+        path.file_name()?.to_os_string(),
+    ))
+}
+
+fn watch_directories(
+    watcher: &mut dyn Watcher,
+    files: &[(PathBuf, OsString)],
+    directories: &mut HashSet<PathBuf>,
+) -> Result<(), String> {
+    for (directory, _) in files {
+        if directories.insert(directory.clone()) {
+            watcher
+                .watch(directory, RecursiveMode::NonRecursive)
+                .map_err(|err| format!("Could not watch the journal: {}", err))?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,290 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::conversion::{checked_convert_to, Prices};
+use crate::error::OverflowError;
+use crate::parser::ast::{CurrencyCode, Query, Transaction};
+use crate::period::Period;
+use crate::query::matches_posting;
+use crate::report::{add_to, postings};
+
+pub mod svg;
+pub mod terminal;
+
+/// Defines the length of the periods that the values of a chart are grouped in.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Interval {
+    Month,
+    Year,
+}
+
+/// Represents the values of an account, or of every account together, in every period of a chart.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Series {
+    pub name: String,
+    pub values: Vec<Decimal>,
+}
+
+/// Represents the values of a chart in a single currency, with a value of every series for every
+/// period, together with the currencies that were left out because they had no price in it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Chart {
+    pub periods: Vec<String>,
+    pub series: Vec<Series>,
+    pub currency: CurrencyCode,
+    pub unpriced: Vec<String>,
+}
+
+impl Chart {
+    /// Returns the height of the stacked bar of every period, which adds up the size of the values
+    /// of every series regardless of their sign.
+    pub(crate) fn stacked_totals(&self) -> Vec<f64> {
+        (0..self.periods.len())
+            .map(|index| {
+                self.series
+                    .iter()
+                    .map(|series| number(series.values[index]).abs())
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Returns the lowest and the highest values of every series, always including 0 and never
+    /// being empty.
+    pub(crate) fn range(&self) -> (f64, f64) {
+        let values = self
+            .series
+            .iter()
+            .flat_map(|series| series.values.iter().map(|value| number(*value)));
+        let (low, high) = values.fold((0.0, 0.0), |(low, high), value| {
+            (f64::min(low, value), f64::max(high, value))
+        });
+
+        if low == high {
+            (low, low + 1.0)
+        } else {
+            (low, high)
+        }
+    }
+}
+
+/// Converts a value of a chart into a number that can be scaled to the size of the chart.
+pub(crate) fn number(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or_default()
+}
+
+/// Adds up the postings that match the query in every period, for every account or for all of
+/// them together. Amounts are valued in the currency with the price known at the date of their
+/// transaction and are left out if there is no price, listing their currency as unpriced. Returns
+/// an error if the values are too large to be computed.
+pub fn flows(
+    transactions: &[Transaction],
+    query: &Query,
+    prices: &Prices,
+    currency: &CurrencyCode,
+    interval: Interval,
+    by_account: bool,
+) -> Result<Chart, OverflowError> {
+    let mut periods: Vec<Period> = vec![];
+    let mut values: BTreeMap<String, BTreeMap<usize, Decimal>> = BTreeMap::new();
+    let mut unpriced = BTreeSet::new();
+    let mut transactions: Vec<&Transaction> = transactions.iter().collect();
+    transactions.sort_by_key(|transaction| transaction.date.date());
+
+    for transaction in transactions {
+        let date = transaction.date.date();
+        let period = match interval {
+            Interval::Month => Period::month_of(date),
+            Interval::Year => Period::year_of(date),
+        };
+
+        for posting in postings(transaction) {
+            let amount = match &posting.amount {
+                Some(amount) if matches_posting(query, transaction, &posting) => amount,
+                _ => continue,
+            };
+            let valued = match checked_convert_to(currency, amount, prices, date)? {
+                Some(valued) => valued,
+                None => {
+                    unpriced.insert(amount.currency.clone());
+                    continue;
+                }
+            };
+
+            while periods.last() != Some(&period) {
+                periods.push(periods.last().map_or(period, Period::next));
+            }
+
+            let name = if by_account {
+                posting.account.to_string()
+            } else {
+                "total".to_owned()
+            };
+            add_to(
+                values
+                    .entry(name)
+                    .or_default()
+                    .entry(periods.len() - 1)
+                    .or_default(),
+                valued.quantity,
+            )?;
+        }
+    }
+
+    Ok(Chart {
+        series: values
+            .into_iter()
+            .map(|(name, values)| Series {
+                name,
+                values: (0..periods.len())
+                    .map(|index| values.get(&index).copied().unwrap_or_default())
+                    .collect(),
+            })
+            .collect(),
+        periods: periods.iter().map(Period::to_string).collect(),
+        currency: currency.clone(),
+        unpriced: unpriced.into_iter().collect(),
+    })
+}
+
+/// Returns the balance at the end of every period of the postings that match the query, for every
+/// account or for all of them together, valued like in `flows`.
+pub fn balances(
+    transactions: &[Transaction],
+    query: &Query,
+    prices: &Prices,
+    currency: &CurrencyCode,
+    interval: Interval,
+    by_account: bool,
+) -> Result<Chart, OverflowError> {
+    let mut chart = flows(transactions, query, prices, currency, interval, by_account)?;
+
+    for series in &mut chart.series {
+        let mut balance = Decimal::ZERO;
+
+        for value in &mut series.values {
+            add_to(&mut balance, *value)?;
+            *value = balance;
+        }
+    }
+
+    Ok(chart)
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    use super::{balances, flows, Interval};
+
+    use crate::conversion::Prices;
+    use crate::error::OverflowError;
+    use crate::journal::resolve;
+    use crate::parser::ast::{Query, QueryTerm};
+    use crate::parser::parse_journal;
+
+    const JOURNAL: &str = "P 2021-01-01 USD 0.5 EUR\n\n\
+                           2021-01-05 Tesco\nexpenses:food 30 EUR\nassets:bank\n\n\
+                           2021-01-20 Cinema\nexpenses:fun 10 EUR\nassets:bank\n\n\
+                           2021-03-02 Amazon\nexpenses:fun 20 USD\nassets:card\n\n\
+                           2021-03-05 Shop\nexpenses:fun 5 GBP\nassets:card\n";
+
+    fn values(by_account: bool, cumulative: bool) -> Vec<(String, Vec<Decimal>)> {
+        let journal = parse_journal(JOURNAL).unwrap();
+        let prices = Prices::from_journal(&journal);
+        let query = Query(vec![QueryTerm::Account("expenses".to_owned())]);
+        let chart = if cumulative {
+            balances(
+                &resolve(journal),
+                &query,
+                &prices,
+                &"EUR".to_owned(),
+                Interval::Month,
+                by_account,
+            )
+            .unwrap()
+        } else {
+            flows(
+                &resolve(journal),
+                &query,
+                &prices,
+                &"EUR".to_owned(),
+                Interval::Month,
+                by_account,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(chart.periods, vec!["2021-01", "2021-02", "2021-03"]);
+        assert_eq!(chart.unpriced, vec!["GBP".to_owned()]);
+        chart
+            .series
+            .into_iter()
+            .map(|series| (series.name, series.values))
+            .collect()
+    }
+
+    #[test]
+    fn adds_up_the_postings_of_every_period() {
+        assert_eq!(
+            values(false, false),
+            vec![("total".to_owned(), vec![dec!(40), dec!(0), dec!(10)])]
+        );
+        assert_eq!(
+            values(true, false),
+            vec![
+                ("expenses:food".to_owned(), vec![dec!(30), dec!(0), dec!(0)]),
+                ("expenses:fun".to_owned(), vec![dec!(10), dec!(0), dec!(10)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn accumulates_balances() {
+        assert_eq!(
+            values(true, true),
+            vec![
+                (
+                    "expenses:food".to_owned(),
+                    vec![dec!(30), dec!(30), dec!(30)]
+                ),
+                (
+                    "expenses:fun".to_owned(),
+                    vec![dec!(10), dec!(10), dec!(20)]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn fails_when_a_value_is_too_large_to_be_computed() {
+        let journal = parse_journal(&format!(
+            "2021-01-05 Tesco\nexpenses:food {max} EUR\nassets:bank\n\n\
+             2021-01-06 Tesco\nexpenses:food {max} EUR\nassets:bank\n\n\
+             2021-02-05 Tesco\nexpenses:fun {max} EUR\nassets:bank\n\n\
+             2021-03-05 Tesco\nexpenses:fun {max} EUR\nassets:bank\n",
+            max = Decimal::MAX
+        ))
+        .unwrap();
+        let prices = Prices::from_journal(&journal);
+        let transactions = resolve(journal);
+        let chart = |query: &str, cumulative: bool| {
+            let values = if cumulative { balances } else { flows };
+            values(
+                &transactions,
+                &Query(vec![QueryTerm::Account(query.to_owned())]),
+                &prices,
+                &"EUR".to_owned(),
+                Interval::Month,
+                false,
+            )
+        };
+
+        assert_eq!(chart("food", false), Err(OverflowError));
+        assert!(chart("fun", false).is_ok());
+        assert_eq!(chart("fun", true), Err(OverflowError));
+    }
+}
@@ -0,0 +1,229 @@
+use std::fmt::Write;
+
+use super::{number, Chart};
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 400.0;
+const LEFT: f64 = 90.0;
+const RIGHT: f64 = 600.0;
+const TOP: f64 = 20.0;
+const BOTTOM: f64 = 360.0;
+/// Colors of every series, repeated when there are more series.
+const COLORS: [&str; 8] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+];
+
+/// Draws the chart as a standalone SVG document with a vertical bar for every period that stacks
+/// the size of the values of every series.
+pub fn bar_chart(chart: &Chart) -> String {
+    let highest = chart
+        .stacked_totals()
+        .into_iter()
+        .fold(0.0, f64::max)
+        .max(f64::EPSILON);
+    let slot = slot(chart);
+    let mut content = frame(chart, 0.0, highest);
+
+    for index in 0..chart.periods.len() {
+        let mut y = BOTTOM;
+
+        for (position, series) in chart.series.iter().enumerate() {
+            let height = number(series.values[index]).abs() / highest * (BOTTOM - TOP);
+
+            if height > 0.0 {
+                y -= height;
+                let _ = writeln!(
+                    content,
+                    "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\">\
+                     <title>{} {}: {} {}</title></rect>",
+                    LEFT + slot * (index as f64 + 0.1),
+                    y,
+                    slot * 0.8,
+                    height,
+                    color(position),
+                    escape(&chart.periods[index]),
+                    escape(&series.name),
+                    series.values[index].normalize(),
+                    escape(&chart.currency)
+                );
+            }
+        }
+    }
+
+    document(&content)
+}
+
+/// Draws the chart as a standalone SVG document with a line for every series.
+pub fn line_chart(chart: &Chart) -> String {
+    let (low, high) = chart.range();
+    let slot = slot(chart);
+    let mut content = frame(chart, low, high);
+
+    for (position, series) in chart.series.iter().enumerate() {
+        let points = series
+            .values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                format!(
+                    "{:.1},{:.1}",
+                    LEFT + slot * (index as f64 + 0.5),
+                    scale(number(*value), low, high)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        let _ = writeln!(
+            content,
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\">\
+             <title>{}</title></polyline>",
+            points,
+            color(position),
+            escape(&series.name)
+        );
+    }
+
+    document(&content)
+}
+
+/// Draws the axes of the chart with the values between `low` and `high`, the labels of the periods
+/// and the legend of the series.
+fn frame(chart: &Chart, low: f64, high: f64) -> String {
+    let mut content = String::new();
+    let slot = slot(chart);
+
+    for value in [low, (low + high) / 2.0, high] {
+        let y = scale(value, low, high);
+        let _ = writeln!(
+            content,
+            "<line x1=\"{}\" y1=\"{:.1}\" x2=\"{}\" y2=\"{:.1}\" stroke=\"#ddd\"/>\
+             <text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{:.2} {}</text>",
+            LEFT,
+            y,
+            RIGHT,
+            y,
+            LEFT - 6.0,
+            y + 4.0,
+            value,
+            escape(&chart.currency)
+        );
+    }
+    if low < 0.0 {
+        let y = scale(0.0, low, high);
+        let _ = writeln!(
+            content,
+            "<line x1=\"{}\" y1=\"{:.1}\" x2=\"{}\" y2=\"{:.1}\" stroke=\"#888\"/>",
+            LEFT, y, RIGHT, y
+        );
+    }
+
+    // Labels are skipped when there are too many periods for them to fit
+    let every = chart.periods.len().div_ceil(12).max(1);
+    for (index, period) in chart.periods.iter().enumerate().step_by(every) {
+        let _ = writeln!(
+            content,
+            "<text x=\"{:.1}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+            LEFT + slot * (index as f64 + 0.5),
+            BOTTOM + 18.0,
+            escape(period)
+        );
+    }
+
+    for (position, series) in chart.series.iter().enumerate() {
+        let y = TOP + 20.0 * position as f64;
+        let _ = writeln!(
+            content,
+            "<rect x=\"{}\" y=\"{}\" width=\"12\" height=\"12\" fill=\"{}\"/>\
+             <text x=\"{}\" y=\"{}\">{}</text>",
+            RIGHT + 20.0,
+            y,
+            color(position),
+            RIGHT + 38.0,
+            y + 10.0,
+            escape(&series.name)
+        );
+    }
+
+    content
+}
+
+fn document(content: &str) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {w} {h}\" width=\"{w}\" \
+         height=\"{h}\" font-family=\"sans-serif\" font-size=\"12\">\n\
+         <rect width=\"{w}\" height=\"{h}\" fill=\"white\"/>\n{}</svg>\n",
+        content,
+        w = WIDTH,
+        h = HEIGHT
+    )
+}
+
+/// Returns the width taken by every period.
+fn slot(chart: &Chart) -> f64 {
+    (RIGHT - LEFT) / chart.periods.len().max(1) as f64
+}
+
+/// Returns the vertical position of the value in a chart whose values go from `low` to `high`.
+fn scale(value: f64, low: f64, high: f64) -> f64 {
+    BOTTOM - (value - low) / (high - low) * (BOTTOM - TOP)
+}
+
+fn color(position: usize) -> &'static str {
+    COLORS[position % COLORS.len()]
+}
+
+/// Escapes the characters of the text that have a meaning in XML and HTML.
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal_macros::dec;
+
+    use super::{bar_chart, line_chart};
+
+    use crate::chart::{Chart, Series};
+
+    fn chart() -> Chart {
+        Chart {
+            periods: vec!["2021-01".to_owned(), "2021-02".to_owned()],
+            series: vec![
+                Series {
+                    name: "expenses:food & drinks".to_owned(),
+                    values: vec![dec!(30), dec!(10)],
+                },
+                Series {
+                    name: "expenses:fun".to_owned(),
+                    values: vec![dec!(10), dec!(0)],
+                },
+            ],
+            currency: "EUR".to_owned(),
+            unpriced: vec![],
+        }
+    }
+
+    #[test]
+    fn stacks_the_series_in_bars() {
+        let svg = bar_chart(&chart());
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert_eq!(svg.matches("<rect x=\"").count(), 5);
+        assert!(svg.contains(
+            "<rect x=\"115.5\" y=\"20.0\" width=\"204.0\" height=\"85.0\" fill=\"#f28e2b\">"
+        ));
+        assert!(svg.contains("expenses:food &amp; drinks"));
+    }
+
+    #[test]
+    fn draws_a_line_for_every_series() {
+        let svg = line_chart(&chart());
+
+        assert!(svg.contains("<polyline points=\"217.5,20.0 472.5,246.7\""));
+        assert!(svg.contains("<polyline points=\"217.5,246.7 472.5,360.0\""));
+    }
+}
@@ -0,0 +1,199 @@
+use rust_decimal::Decimal;
+
+use super::{number, Chart};
+
+/// Characters that fill the segment of every series in a bar, repeated when there are more series.
+const BLOCKS: [char; 4] = ['█', '▓', '▒', '░'];
+/// Characters that mark the values of every series in a line chart, repeated when there are more
+/// series.
+const MARKERS: [char; 4] = ['●', '○', '■', '□'];
+
+/// Draws the chart as a horizontal bar for every period that stacks the size of the values of every
+/// series, followed by the total of the period, in the given number of columns.
+pub fn bar_chart(chart: &Chart, width: usize) -> String {
+    let totals: Vec<String> = (0..chart.periods.len())
+        .map(|index| {
+            let total: Decimal = chart.series.iter().map(|series| series.values[index]).sum();
+            format!("{} {}", total.normalize(), chart.currency)
+        })
+        .collect();
+    let label_width = chart
+        .periods
+        .iter()
+        .map(String::len)
+        .max()
+        .unwrap_or_default();
+    let total_width = totals.iter().map(|total| total.chars().count()).max();
+    let bar_width = width
+        .saturating_sub(label_width + total_width.unwrap_or_default() + 2)
+        .max(10);
+    let stacked_totals = chart.stacked_totals();
+    let highest = stacked_totals.iter().cloned().fold(0.0, f64::max);
+
+    let mut lines: Vec<String> = chart
+        .periods
+        .iter()
+        .enumerate()
+        .map(|(index, period)| {
+            let bar: String = chart
+                .series
+                .iter()
+                .enumerate()
+                .map(|(position, series)| {
+                    let size = number(series.values[index]).abs();
+                    let length = if highest > 0.0 {
+                        (size / highest * bar_width as f64).round() as usize
+                    } else {
+                        0
+                    };
+                    BLOCKS[position % BLOCKS.len()].to_string().repeat(length)
+                })
+                .collect();
+
+            format!(
+                "{:label_width$} {} {}",
+                period,
+                bar,
+                totals[index],
+                label_width = label_width
+            )
+        })
+        .collect();
+
+    lines.extend(legend(chart, &BLOCKS));
+    lines.join("\n")
+}
+
+/// Draws the chart as a line chart of the given size, marking the value of every series in every
+/// period. Only the latest periods are drawn when there are more periods than columns.
+pub fn line_chart(chart: &Chart, width: usize, height: usize) -> String {
+    let height = height.max(2);
+    let (low, high) = chart.range();
+    let (low_label, high_label) = (
+        format!("{:.2} {}", low, chart.currency),
+        format!("{:.2} {}", high, chart.currency),
+    );
+    let axis_width = low_label.chars().count().max(high_label.chars().count());
+    let plot_width = width.saturating_sub(axis_width + 2).max(1);
+    let skipped = chart.periods.len().saturating_sub(plot_width);
+    let periods = chart.periods.len() - skipped;
+    let step = match periods {
+        0 => 1,
+        periods => (plot_width / periods).clamp(1, 8),
+    };
+
+    let mut grid = vec![vec![' '; periods * step]; height];
+    for (position, series) in chart.series.iter().enumerate() {
+        for (column, value) in series.values[skipped..].iter().enumerate() {
+            let row =
+                ((number(*value) - low) / (high - low) * (height - 1) as f64).round() as usize;
+            grid[height - 1 - row][column * step] = MARKERS[position % MARKERS.len()];
+        }
+    }
+
+    let mut lines: Vec<String> = grid
+        .into_iter()
+        .enumerate()
+        .map(|(row, cells)| {
+            let label = match row {
+                0 => high_label.as_str(),
+                row if row == height - 1 => low_label.as_str(),
+                _ => "",
+            };
+            let line: String = cells.into_iter().collect();
+
+            format!("{:>axis_width$} ┤{}", label, line, axis_width = axis_width)
+                .trim_end()
+                .to_owned()
+        })
+        .collect();
+
+    lines.push(format!(
+        "{:axis_width$} └{}",
+        "",
+        "─".repeat(periods * step),
+        axis_width = axis_width
+    ));
+    if let (Some(first), Some(last)) = (chart.periods.get(skipped), chart.periods.last()) {
+        let labels = if periods > 1 {
+            // The last label starts below its value, unless it would overlap the first one
+            let gap = ((periods - 1) * step).saturating_sub(first.len()).max(1);
+            format!("{}{}{}", first, " ".repeat(gap), last)
+        } else {
+            first.clone()
+        };
+
+        lines.push(format!(
+            "{:axis_width$}  {}",
+            "",
+            labels,
+            axis_width = axis_width
+        ));
+    }
+
+    lines.extend(legend(chart, &MARKERS));
+    lines.join("\n")
+}
+
+fn legend(chart: &Chart, symbols: &[char]) -> Vec<String> {
+    std::iter::once(String::new())
+        .chain(chart.series.iter().enumerate().map(|(position, series)| {
+            format!("{} {}", symbols[position % symbols.len()], series.name)
+        }))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal_macros::dec;
+
+    use super::{bar_chart, line_chart};
+
+    use crate::chart::{Chart, Series};
+
+    fn chart() -> Chart {
+        Chart {
+            periods: vec!["2021-01".to_owned(), "2021-02".to_owned()],
+            series: vec![
+                Series {
+                    name: "expenses:food".to_owned(),
+                    values: vec![dec!(30), dec!(10)],
+                },
+                Series {
+                    name: "expenses:fun".to_owned(),
+                    values: vec![dec!(10), dec!(0)],
+                },
+            ],
+            currency: "EUR".to_owned(),
+            unpriced: vec![],
+        }
+    }
+
+    #[test]
+    fn stacks_the_series_in_bars() {
+        assert_eq!(
+            bar_chart(&chart(), 39),
+            "2021-01 ██████████████████▓▓▓▓▓▓ 40 EUR\n\
+             2021-02 ██████ 10 EUR\n\
+             \n\
+             █ expenses:food\n\
+             ▓ expenses:fun"
+        );
+    }
+
+    #[test]
+    fn marks_the_values_of_every_series() {
+        assert_eq!(
+            line_chart(&chart(), 22, 4),
+            "30.00 EUR ┤●\n\
+             \u{20}         ┤\n\
+             \u{20}         ┤○    ●\n\
+             \u{20}0.00 EUR ┤     ○\n\
+             \u{20}         └──────────\n\
+             \u{20}          2021-01 2021-02\n\
+             \n\
+             ● expenses:food\n\
+             ○ expenses:fun"
+        );
+    }
+}
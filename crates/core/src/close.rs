@@ -0,0 +1,330 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::error::OverflowError;
+use crate::lots::{book, Booking, LotError};
+use crate::parser::ast::{Account, Amount, Lot, ParsedDate, Posting, Transaction};
+use crate::report::{add_to, has_root, postings, BALANCE_SHEET_ROOTS, PROFIT_AND_LOSS_ROOTS};
+
+/// Account that the balances of income and expense accounts are moved to when closing a year.
+pub const RETAINED_EARNINGS: &str = "equity:retained-earnings";
+/// Account that the balances of asset and liability accounts are taken from when opening a year.
+pub const OPENING_BALANCES: &str = "equity:opening-balances";
+
+/// Represents the transactions that close a year and open the next one.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Closing {
+    /// Transactions dated on the last day of the year that leave every income and expense
+    /// account at zero, moving their balances to `equity:retained-earnings`.
+    pub closing: Vec<Transaction>,
+    /// Transactions dated on the first day of the next year that give every asset and liability
+    /// account the balance it had at the end of the year, taken from `equity:opening-balances`,
+    /// with one transaction for every lot held.
+    pub opening: Vec<Transaction>,
+}
+
+/// Creates the transactions that close the given year and open the next one, one per account and
+/// currency since transactions have two postings. Balances include every transaction up to the end
+/// of the year, so years that were already closed in the same journal are zero. Commodities held in
+/// lots are opened with one transaction per lot, which keeps its cost and date, booking the sales
+/// of the year with the given method. Returns an error if the lots can't be booked or the balances
+/// are too large to be computed.
+pub fn close(
+    transactions: &[Transaction],
+    year: i32,
+    booking: Booking,
+) -> Result<Closing, LotError> {
+    let (last_day, first_day) = match (
+        NaiveDate::from_ymd_opt(year, 12, 31),
+        year.checked_add(1)
+            .and_then(|next_year| NaiveDate::from_ymd_opt(next_year, 1, 1)),
+    ) {
+        (Some(last_day), Some(first_day)) => (last_day, first_day),
+        _ => return Ok(Closing::default()),
+    };
+
+    let balances = balances(transactions, last_day)?;
+    let closed_transactions: Vec<Transaction> = transactions
+        .iter()
+        .filter(|transaction| transaction.date.date() <= last_day)
+        .cloned()
+        .collect();
+    let holdings = book(&closed_transactions, booking)?.holdings;
+    let mut opening = vec![];
+
+    for (account, amount) in balances
+        .iter()
+        .filter(|(account, _)| has_root(account, &BALANCE_SHEET_ROOTS))
+    {
+        let mut remaining = amount.quantity;
+
+        for holding in holdings.iter().filter(|holding| {
+            holding.account == account.to_string() && holding.commodity == amount.currency
+        }) {
+            remaining = remaining
+                .checked_sub(holding.quantity)
+                .ok_or(OverflowError)?;
+            opening.push(opening_transaction(
+                first_day,
+                account,
+                Amount {
+                    quantity: holding.quantity,
+                    currency: amount.currency.clone(),
+                },
+                Some(Lot {
+                    cost: holding.cost.clone(),
+                    date: Some(holding.date),
+                }),
+            ));
+        }
+
+        if !remaining.is_zero() {
+            opening.push(opening_transaction(
+                first_day,
+                account,
+                Amount {
+                    quantity: remaining,
+                    currency: amount.currency.clone(),
+                },
+                None,
+            ));
+        }
+    }
+
+    Ok(Closing {
+        closing: balances
+            .iter()
+            .filter(|(account, _)| has_root(account, &PROFIT_AND_LOSS_ROOTS))
+            .map(|(account, amount)| Transaction {
+                date: ParsedDate::Full(last_day),
+                payee: "Closing balances".to_owned(),
+                tags: vec!["closing".to_owned()],
+                postings: (
+                    Posting {
+                        account: account.clone(),
+                        amount: Some(Amount {
+                            quantity: -amount.quantity,
+                            currency: amount.currency.clone(),
+                        }),
+                        lot: None,
+                        price: None,
+                    },
+                    posting_without_amount(RETAINED_EARNINGS),
+                ),
+                ..Transaction::default()
+            })
+            .collect(),
+        opening,
+    })
+}
+
+fn opening_transaction(
+    date: NaiveDate,
+    account: &Account,
+    amount: Amount,
+    lot: Option<Lot>,
+) -> Transaction {
+    Transaction {
+        date: ParsedDate::Full(date),
+        payee: "Opening balances".to_owned(),
+        tags: vec!["opening".to_owned()],
+        postings: (
+            Posting {
+                account: account.clone(),
+                amount: Some(amount),
+                lot,
+                price: None,
+            },
+            posting_without_amount(OPENING_BALANCES),
+        ),
+        ..Transaction::default()
+    }
+}
+
+/// Returns the non zero balance of every account and currency up to the given date, sorted by
+/// account.
+fn balances(
+    transactions: &[Transaction],
+    until: NaiveDate,
+) -> Result<Vec<(Account, Amount)>, OverflowError> {
+    let mut balances: BTreeMap<(String, String), (Account, Decimal)> = BTreeMap::new();
+
+    for transaction in transactions
+        .iter()
+        .filter(|transaction| transaction.date.date() <= until)
+    {
+        for posting in postings(transaction) {
+            if let Some(amount) = &posting.amount {
+                let (_, balance) = balances
+                    .entry((posting.account.to_string(), amount.currency.clone()))
+                    .or_insert_with(|| (posting.account.clone(), Decimal::ZERO));
+                add_to(balance, amount.quantity)?;
+            }
+        }
+    }
+
+    Ok(balances
+        .into_iter()
+        .filter(|(_, (_, quantity))| !quantity.is_zero())
+        .map(|((_, currency), (account, quantity))| (account, Amount { quantity, currency }))
+        .collect())
+}
+
+fn posting_without_amount(account: &str) -> Posting {
+    let mut segments = account.split(':').map(str::to_owned);
+
+    Posting {
+        account: Account {
+            name: segments.next().unwrap_or_default(),
+            children: segments.collect(),
+        },
+        amount: None,
+        lot: None,
+        price: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Datelike;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    use super::close;
+
+    use crate::error::OverflowError;
+    use crate::journal::resolve;
+    use crate::lots::{book, Booking, LotError};
+    use crate::parser::ast::*;
+    use crate::parser::parse_journal;
+    use crate::report::balance;
+
+    fn transactions(content: &str) -> Vec<Transaction> {
+        resolve(parse_journal(content).unwrap())
+    }
+
+    const JOURNAL: &str = "2021-01-05 ACME\nincome:salary -1000 EUR\nassets:bank\n\n\
+                           2021-02-03 Tesco\nexpenses:food 30 EUR\nassets:bank\n\n\
+                           2021-03-01 Bank\nassets:bank -200 EUR\nliabilities:card\n\n\
+                           2022-01-10 Tesco\nexpenses:food 5 EUR\nassets:bank\n";
+
+    #[test]
+    fn closes_income_and_expense_accounts_into_retained_earnings() {
+        let closing = close(&transactions(JOURNAL), 2021, Booking::Fifo)
+            .unwrap()
+            .closing;
+
+        assert_eq!(
+            closing
+                .iter()
+                .map(|transaction| (
+                    transaction.postings.0.account.to_string(),
+                    transaction.postings.0.amount.clone().unwrap().quantity,
+                    transaction.postings.1.account.to_string()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "expenses:food".to_owned(),
+                    dec!(-30),
+                    "equity:retained-earnings".to_owned()
+                ),
+                (
+                    "income:salary".to_owned(),
+                    dec!(1000),
+                    "equity:retained-earnings".to_owned()
+                ),
+            ]
+        );
+        assert!(closing
+            .iter()
+            .all(|transaction| transaction.date.date().to_string() == "2021-12-31"));
+    }
+
+    #[test]
+    fn opens_asset_and_liability_accounts_with_their_balances() {
+        let opening = close(&transactions(JOURNAL), 2021, Booking::Fifo)
+            .unwrap()
+            .opening;
+
+        assert_eq!(
+            opening
+                .iter()
+                .map(|transaction| (
+                    transaction.postings.0.account.to_string(),
+                    transaction.postings.0.amount.clone().unwrap().quantity
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("assets:bank".to_owned(), dec!(770)),
+                ("liabilities:card".to_owned(), dec!(200)),
+            ]
+        );
+        assert!(opening
+            .iter()
+            .all(|transaction| transaction.date.date().to_string() == "2022-01-01"));
+    }
+
+    #[test]
+    fn opening_transactions_keep_the_balance_of_the_closed_year() {
+        let old_transactions: Vec<Transaction> = transactions(JOURNAL)
+            .into_iter()
+            .filter(|transaction| transaction.date.date().year() == 2021)
+            .collect();
+        let query = Query(vec![QueryTerm::Account("assets".to_owned())]);
+
+        assert_eq!(
+            balance::report(
+                &close(&old_transactions, 2021, Booking::Fifo)
+                    .unwrap()
+                    .opening,
+                &query
+            )
+            .unwrap(),
+            balance::report(&old_transactions, &query).unwrap()
+        );
+    }
+
+    #[test]
+    fn fails_when_a_balance_is_too_large_to_be_computed() {
+        let transactions = transactions(&format!(
+            "2021-01-05 ACME\nassets:bank {max} EUR\nincome:salary\n\n\
+             2021-02-05 ACME\nassets:bank {max} EUR\nincome:salary\n",
+            max = Decimal::MAX
+        ));
+
+        assert_eq!(
+            close(&transactions, 2021, Booking::Fifo),
+            Err(LotError::Overflow(OverflowError))
+        );
+    }
+
+    #[test]
+    fn opens_every_lot_held_with_its_cost_and_date() {
+        let old_transactions = transactions(
+            "2021-01-10 Broker\nassets:broker 10 VWCE {90 EUR}\nassets:bank\n\n\
+             2021-06-10 Broker\nassets:broker 10 VWCE {100 EUR}\nassets:bank\n\n\
+             2021-09-01 Broker\nassets:broker -5 VWCE @ 95 EUR\nassets:bank\n",
+        );
+        let mut new_transactions = close(&old_transactions, 2021, Booking::Fifo)
+            .unwrap()
+            .opening;
+        new_transactions.extend(transactions(
+            "2022-03-01 Broker\nassets:broker -5 VWCE {100 EUR} [2021-06-10] @ 110 EUR\nassets:bank\n\n\
+             2022-03-02 Broker\nassets:broker -10 VWCE @ 110 EUR\nassets:bank\n",
+        ));
+
+        let lots = book(&new_transactions, Booking::Fifo).unwrap();
+
+        assert_eq!(
+            lots.sales
+                .iter()
+                .map(|sale| (sale.cost.quantity, sale.gain.quantity))
+                .collect::<Vec<_>>(),
+            vec![(dec!(500), dec!(50)), (dec!(950), dec!(150))]
+        );
+        assert!(lots.holdings.is_empty());
+    }
+}
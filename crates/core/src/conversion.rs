@@ -1,10 +1,233 @@
-use crate::parser::ast::{Amount, CurrencyCode};
-
-/// Transforms a given amount to the specified currency.
-pub fn convert_to(currency: &CurrencyCode, origin: &Amount) -> Amount {
-    Amount {
-        // TODO: Implement actual currency conversion.
-        quantity: origin.quantity.clone(),
-        currency: currency.clone(),
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::error::OverflowError;
+use crate::journal::resolved_postings;
+use crate::parser::ast::{Amount, CurrencyCode, JournalElement, Posting, Transaction};
+
+/// Currency that amounts are valued in, and that transactions are balanced in, when no other one
+/// is given.
+pub const DEFAULT_CURRENCY: &str = "EUR";
+
+/// Represents the prices of a journal, by date, of every currency in the currencies it was priced
+/// in.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Prices(BTreeMap<(CurrencyCode, CurrencyCode), BTreeMap<NaiveDate, Decimal>>);
+
+impl Prices {
+    /// Collects the price directives of the journal.
+    pub fn from_journal(journal: &[JournalElement]) -> Self {
+        let mut prices = Prices::default();
+
+        for element in journal {
+            if let JournalElement::Price(price) = element {
+                prices
+                    .0
+                    .entry((price.currency.clone(), price.amount.currency.clone()))
+                    .or_default()
+                    .insert(price.date.date(), price.amount.quantity);
+            }
+        }
+
+        prices
+    }
+
+    /// Returns what a unit of `from` is worth in `to` at the given date, using the latest price
+    /// known at that date of either currency in the other one.
+    pub fn rate(&self, from: &str, to: &str, date: NaiveDate) -> Option<Decimal> {
+        if from == to {
+            return Some(Decimal::ONE);
+        }
+
+        let latest = |from: &str, to: &str| {
+            self.0
+                .get(&(from.to_owned(), to.to_owned()))
+                .and_then(|prices| prices.range(..=date).next_back())
+                .map(|(date, rate)| (*date, *rate))
+        };
+        let direct = latest(from, to);
+        let inverse = latest(to, from)
+            .filter(|(_, rate)| !rate.is_zero())
+            .map(|(date, rate)| (date, Decimal::ONE / rate));
+
+        match (direct, inverse) {
+            (Some((direct_date, _)), Some((inverse_date, rate))) if inverse_date > direct_date => {
+                Some(rate)
+            }
+            (Some((_, rate)), _) | (None, Some((_, rate))) => Some(rate),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Defines how the amounts of the postings are valued in reports.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Valuation {
+    /// Values the amounts at their price, or at the cost of their lot.
+    Cost,
+    /// Values the amounts in the currency with the latest price known at the date. Amounts without
+    /// a known price are kept as they are.
+    Market {
+        currency: CurrencyCode,
+        date: NaiveDate,
+    },
+}
+
+/// Transforms a given amount to the specified currency with the latest price known at the given
+/// date, or returns None if there is no price between both currencies or the converted amount is
+/// too large to be computed.
+pub fn convert_to(
+    currency: &CurrencyCode,
+    origin: &Amount,
+    prices: &Prices,
+    date: NaiveDate,
+) -> Option<Amount> {
+    checked_convert_to(currency, origin, prices, date)
+        .ok()
+        .flatten()
+}
+
+/// Transforms a given amount like `convert_to`, but failing when the converted amount is too large
+/// to be computed instead of returning None.
+pub fn checked_convert_to(
+    currency: &CurrencyCode,
+    origin: &Amount,
+    prices: &Prices,
+    date: NaiveDate,
+) -> Result<Option<Amount>, OverflowError> {
+    prices
+        .rate(&origin.currency, currency, date)
+        .map(|rate| {
+            Ok(Amount {
+                quantity: origin.quantity.checked_mul(rate).ok_or(OverflowError)?,
+                currency: currency.clone(),
+            })
+        })
+        .transpose()
+}
+
+/// Returns the transactions with the amount of every posting valued as requested. The postings
+/// of the valued transactions always have an amount and never a lot or a price. Fails when the
+/// value of an amount is too large to be computed.
+pub fn value(
+    transactions: &[Transaction],
+    valuation: &Valuation,
+    prices: &Prices,
+) -> Result<Vec<Transaction>, OverflowError> {
+    transactions
+        .iter()
+        .map(|transaction| {
+            let (first_posting, second_posting) = resolved_postings(transaction);
+
+            Ok(Transaction {
+                postings: (
+                    value_posting(&first_posting, valuation, prices)?,
+                    value_posting(&second_posting, valuation, prices)?,
+                ),
+                automated_postings: transaction
+                    .automated_postings
+                    .iter()
+                    .map(|posting| value_posting(posting, valuation, prices))
+                    .collect::<Result<Vec<Posting>, OverflowError>>()?,
+                ..transaction.clone()
+            })
+        })
+        .collect()
+}
+
+fn value_posting(
+    posting: &Posting,
+    valuation: &Valuation,
+    prices: &Prices,
+) -> Result<Posting, OverflowError> {
+    let amount = match (valuation, &posting.amount) {
+        (Valuation::Cost, _) => posting.weight()?,
+        (Valuation::Market { currency, date }, Some(amount)) => Some(
+            checked_convert_to(currency, amount, prices, *date)?.unwrap_or_else(|| amount.clone()),
+        ),
+        (Valuation::Market { .. }, None) => None,
+    };
+
+    Ok(Posting {
+        amount,
+        lot: None,
+        price: None,
+        ..posting.clone()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    use super::{value, Prices, Valuation};
+
+    use crate::journal::resolve;
+    use crate::parser::ast::{Amount, JournalElement};
+    use crate::parser::parse_journal;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2021, month, day).unwrap()
+    }
+
+    fn journal() -> Vec<JournalElement> {
+        parse_journal(
+            "P 2021-10-01 VWCE 100 EUR\n\
+             P 2021-11-01 VWCE 105 EUR\n\
+             P 2021-10-01 EUR 1.25 USD\n\
+             P 2021-12-01 USD 0.9 EUR\n\n\
+             2021-10-07 Broker\n\
+             assets:broker 10 VWCE {95 EUR}\n\
+             assets:bank\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn uses_the_latest_price_known_at_the_date() {
+        let prices = Prices::from_journal(&journal());
+
+        assert_eq!(prices.rate("VWCE", "EUR", date(10, 15)), Some(dec!(100)));
+        assert_eq!(prices.rate("VWCE", "EUR", date(12, 31)), Some(dec!(105)));
+        assert_eq!(prices.rate("VWCE", "EUR", date(9, 30)), None);
+        assert_eq!(prices.rate("EUR", "EUR", date(9, 30)), Some(dec!(1)));
+    }
+
+    #[test]
+    fn uses_inverse_prices_when_they_are_more_recent() {
+        let prices = Prices::from_journal(&journal());
+
+        assert_eq!(prices.rate("USD", "EUR", date(10, 15)), Some(dec!(0.8)));
+        assert_eq!(prices.rate("USD", "EUR", date(12, 15)), Some(dec!(0.9)));
+        assert_eq!(prices.rate("EUR", "VWCE", date(10, 15)), Some(dec!(0.01)));
+    }
+
+    #[test]
+    fn values_postings_at_cost_or_market() {
+        let journal = journal();
+        let prices = Prices::from_journal(&journal);
+        let transactions = resolve(journal);
+        let amounts = |valuation| {
+            let transaction = value(&transactions, &valuation, &prices).unwrap().remove(0);
+            (transaction.postings.0.amount, transaction.postings.1.amount)
+        };
+        let eur = |quantity| {
+            Some(Amount {
+                quantity,
+                currency: "EUR".to_owned(),
+            })
+        };
+
+        assert_eq!(amounts(Valuation::Cost), (eur(dec!(950)), eur(dec!(-950))));
+        assert_eq!(
+            amounts(Valuation::Market {
+                currency: "EUR".to_owned(),
+                date: date(12, 31),
+            }),
+            (eur(dec!(1050)), eur(dec!(-950)))
+        );
     }
 }
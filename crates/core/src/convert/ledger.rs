@@ -0,0 +1,982 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use regex::Regex;
+use rust_decimal::Decimal;
+
+use crate::parser::ast::{
+    Account, Amount, CurrencyCode, JournalElement, Metadata, ParsedDate, Posting, Price, Tag,
+    Transaction, TransactionStatus,
+};
+
+use super::{Conversion, ConversionIssue, ReportedIssue};
+
+/// Currency symbols that ledger allows as commodities and the currency code they are mapped to.
+const CURRENCY_SYMBOLS: [(&str, &str); 5] = [
+    ("$", "USD"),
+    ("€", "EUR"),
+    ("£", "GBP"),
+    ("¥", "JPY"),
+    ("₹", "INR"),
+];
+
+/// Converts a journal written in the ledger or hledger syntax. Transactions with more than two
+/// postings are split in transactions of two postings when one of the postings balances all the
+/// others, in which case the amount of the balancing posting is left for the parser to infer.
+/// Costs become the price of their posting and tags and metadata are read from the comments.
+/// Anything that can't be represented is reported as an issue together with the line where it
+/// was found.
+pub fn convert(content: &str) -> Conversion {
+    let mut converter = Converter::new();
+
+    for block in blocks(content) {
+        converter.convert_block(&block);
+    }
+
+    Conversion {
+        journal: converter.journal,
+        issues: converter.issues,
+    }
+}
+
+/// Represents a top level line of the journal and the indented lines that follow it.
+struct Block<'a> {
+    line: usize,
+    header: &'a str,
+    body: Vec<(usize, &'a str)>,
+}
+
+/// Splits the content in blocks, treating `comment` and `test` blocks as a single comment.
+fn blocks(content: &str) -> Vec<Block<'_>> {
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut in_comment_block = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+
+        if in_comment_block {
+            in_comment_block = !(trimmed == "end comment" || trimmed == "end test");
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let is_indented = line.starts_with(|c: char| c.is_whitespace());
+
+        match blocks.last_mut() {
+            Some(block) if is_indented => block.body.push((index + 1, trimmed)),
+            _ => {
+                in_comment_block = trimmed == "comment" || trimmed == "test";
+                blocks.push(Block {
+                    line: index + 1,
+                    header: trimmed,
+                    body: Vec::new(),
+                });
+            }
+        }
+    }
+
+    blocks
+}
+
+/// Comment of a transaction or posting split in its tags, its metadata and the rest of the text.
+#[derive(Default)]
+struct Comment {
+    tags: Vec<Tag>,
+    metadata: Vec<(String, String)>,
+    text: String,
+}
+
+struct Converter {
+    journal: Vec<JournalElement>,
+    issues: Vec<ReportedIssue>,
+    default_currency: Option<CurrencyCode>,
+    renamed_accounts: HashSet<String>,
+    renamed_currencies: HashSet<String>,
+    ledger_tags: Regex,
+    tags: Regex,
+}
+
+impl Converter {
+    fn new() -> Self {
+        Self {
+            journal: Vec::new(),
+            issues: Vec::new(),
+            default_currency: None,
+            renamed_accounts: HashSet::new(),
+            renamed_currencies: HashSet::new(),
+            ledger_tags: Regex::new(r"(^|\s):((?:[^:\s]+:)+)(\s|$)").unwrap(),
+            tags: Regex::new(r"(?:^|\s)([^\s:,]+):(?:\s+(.*))?$").unwrap(),
+        }
+    }
+
+    fn report(&mut self, line: usize, issue: ConversionIssue) {
+        self.issues.push(ReportedIssue { line, issue });
+    }
+
+    fn convert_block(&mut self, block: &Block) {
+        let header = block.header;
+        let first_word = header.split_whitespace().next().unwrap_or_default();
+
+        if header.starts_with(|c: char| c.is_ascii_digit()) {
+            return self.convert_transaction(block);
+        }
+
+        if header.starts_with([';', '#', '%', '|', '*'])
+            || first_word == "comment"
+            || first_word == "test"
+        {
+            return self.report(block.line, ConversionIssue::DroppedComment);
+        }
+
+        match first_word {
+            "P" => self.convert_price(block),
+            "account" => self.convert_account(block),
+            "D" => {
+                self.default_currency = self
+                    .parse_amount(block.line, header[1..].trim())
+                    .ok()
+                    .flatten()
+                    .map(|amount| amount.currency);
+            }
+            "year" | "Y" => self.convert_year(block, header[first_word.len()..].trim()),
+            word if word.starts_with('Y') && word[1..].chars().all(|c| c.is_ascii_digit()) => {
+                self.convert_year(block, &word[1..])
+            }
+            word if word.starts_with('=') => {
+                self.report(block.line, ConversionIssue::DroppedAutomatedTransaction)
+            }
+            word if word.starts_with('~') => {
+                self.report(block.line, ConversionIssue::DroppedPeriodicTransaction)
+            }
+            word => self.report(
+                block.line,
+                ConversionIssue::DroppedDirective(word.to_owned()),
+            ),
+        }
+    }
+
+    fn convert_year(&mut self, block: &Block, year: &str) {
+        match year.parse() {
+            Ok(year) => self.journal.push(JournalElement::Year(year)),
+            Err(_) => self.report(
+                block.line,
+                ConversionIssue::DroppedDirective("year".to_owned()),
+            ),
+        }
+    }
+
+    fn convert_account(&mut self, block: &Block) {
+        let (declaration, _) = split_comment(&block.header["account".len()..]);
+        let account = self.convert_account_name(block.line, declaration.trim());
+        self.journal.push(JournalElement::Account(account));
+
+        for (line, directive) in &block.body {
+            let word = directive.split_whitespace().next().unwrap_or_default();
+
+            if !word.starts_with(';') {
+                self.report(
+                    *line,
+                    ConversionIssue::DroppedDirective(format!("account {}", word)),
+                );
+            }
+        }
+    }
+
+    /// Converts a `P {date} [time] {commodity} {amount}` declaration.
+    fn convert_price(&mut self, block: &Block) {
+        let (declaration, _) = split_comment(&block.header[1..]);
+        let mut parts = declaration.split_whitespace().peekable();
+
+        let date = parts.next().and_then(parse_date);
+        if parts.peek().is_some_and(|part| part.contains(':')) {
+            parts.next();
+        }
+        let currency = parts.next();
+        let amount = parts.collect::<Vec<&str>>().join(" ");
+
+        match (date, currency) {
+            (Some(date), Some(currency)) => {
+                let currency = self.convert_currency(block.line, currency);
+
+                match self.parse_amount(block.line, &amount) {
+                    Ok(Some(amount)) => self.journal.push(JournalElement::Price(Price {
+                        date,
+                        currency,
+                        amount,
+                    })),
+                    _ => self.report(block.line, ConversionIssue::InvalidAmount(amount)),
+                }
+            }
+            _ => self.report(
+                block.line,
+                ConversionIssue::InvalidDate(declaration.trim().to_owned()),
+            ),
+        }
+    }
+
+    fn convert_transaction(&mut self, block: &Block) {
+        let (header, header_comment) = split_comment(block.header);
+        let mut parts = header.trim().splitn(2, char::is_whitespace);
+        let date_part = parts.next().unwrap_or_default();
+        let mut rest = parts.next().unwrap_or_default().trim();
+
+        let mut metadata = Metadata::new();
+        let mut date_parts = date_part.splitn(2, '=');
+        let date = match date_parts.next().and_then(parse_date) {
+            Some(date) => date,
+            None => {
+                return self.report(
+                    block.line,
+                    ConversionIssue::InvalidDate(date_part.to_owned()),
+                )
+            }
+        };
+        if let Some(auxiliary_date) = date_parts.next() {
+            metadata.insert("date2".to_owned(), auxiliary_date.replace('/', "-"));
+        }
+
+        let status = if rest.starts_with('!') {
+            TransactionStatus::Pending
+        } else {
+            TransactionStatus::Cleared
+        };
+        rest = rest.trim_start_matches(['*', '!']).trim();
+
+        if rest.starts_with('(') {
+            if let Some(end) = rest.find(')') {
+                metadata.insert("code".to_owned(), rest[1..end].trim().to_owned());
+                rest = rest[end + 1..].trim();
+            }
+        }
+
+        let (payee, mut description) = match rest.split_once('|') {
+            Some((payee, note)) => (payee.trim().to_owned(), note.trim().to_owned()),
+            None => (rest.to_owned(), String::new()),
+        };
+
+        let mut tags = Vec::new();
+        let comment = self.parse_comment(header_comment.unwrap_or_default());
+        if description.is_empty() {
+            description = comment.text.clone();
+        } else if !comment.text.is_empty() {
+            self.report(block.line, ConversionIssue::DroppedComment);
+        }
+        tags.extend(comment.tags);
+        metadata.extend(comment.metadata);
+
+        let mut postings: Vec<Posting> = Vec::new();
+        let mut is_valid = true;
+
+        for (line, content) in &block.body {
+            if let Some(text) = content.strip_prefix(';') {
+                let comment = self.parse_comment(text);
+                if !comment.text.is_empty() {
+                    if postings.is_empty() && description.is_empty() {
+                        description = comment.text;
+                    } else {
+                        self.report(*line, ConversionIssue::DroppedComment);
+                    }
+                }
+                tags.extend(comment.tags);
+                metadata.extend(comment.metadata);
+                continue;
+            }
+
+            match self.convert_posting(*line, content) {
+                Ok(Some((posting, comment))) => {
+                    if !comment.text.is_empty() {
+                        self.report(*line, ConversionIssue::DroppedComment);
+                    }
+                    tags.extend(comment.tags);
+                    metadata.extend(comment.metadata);
+                    postings.push(posting);
+                }
+                Ok(None) => {}
+                Err(issue) => {
+                    self.report(*line, issue);
+                    is_valid = false;
+                }
+            }
+        }
+
+        if !is_valid {
+            return;
+        }
+
+        let transaction = Transaction {
+            date,
+            status,
+            payee,
+            description,
+            tags: tags.into_iter().fold(Vec::new(), |mut tags, tag| {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+                tags
+            }),
+            metadata,
+            ..Transaction::default()
+        };
+
+        match balance_postings(postings) {
+            Ok(pairs) => {
+                if pairs.len() > 1 {
+                    self.report(block.line, ConversionIssue::SplitTransaction(pairs.len()));
+                }
+
+                self.journal.extend(pairs.into_iter().map(|postings| {
+                    JournalElement::Transaction(Transaction {
+                        postings,
+                        ..transaction.clone()
+                    })
+                }));
+            }
+            Err(issue) => self.report(block.line, issue),
+        }
+    }
+
+    /// Converts a posting, returning `None` if the posting was dropped or the issue that makes the
+    /// whole transaction invalid.
+    fn convert_posting(
+        &mut self,
+        line: usize,
+        content: &str,
+    ) -> Result<Option<(Posting, Comment)>, ConversionIssue> {
+        let (content, comment) = split_comment(content);
+        let comment = self.parse_comment(comment.unwrap_or_default());
+
+        let mut content = content.trim();
+        if content.starts_with('*') || content.starts_with('!') {
+            self.report(line, ConversionIssue::DroppedPostingStatus);
+            content = content[1..].trim_start();
+        }
+
+        let (account, amount) = match content.find("  ").or_else(|| content.find('\t')) {
+            Some(index) => (content[..index].trim(), content[index..].trim()),
+            None => (content, ""),
+        };
+
+        if account.starts_with('(') || account.starts_with('[') {
+            self.report(
+                line,
+                ConversionIssue::DroppedVirtualPosting(account.to_owned()),
+            );
+            return Ok(None);
+        }
+
+        let mut amount = amount;
+        if let Some(index) = amount.find('=') {
+            self.report(line, ConversionIssue::DroppedBalanceAssertion);
+            amount = amount[..index].trim();
+        }
+        let cost = match amount.split_once('@') {
+            Some((quantity, cost)) => {
+                amount = quantity.trim();
+                Some(cost)
+            }
+            None => None,
+        };
+
+        let amount = self.parse_amount(line, amount)?;
+        let price = match cost {
+            Some(cost) => Some(self.parse_cost(line, cost, amount.as_ref())?),
+            None => None,
+        };
+
+        Ok(Some((
+            Posting {
+                account: self.convert_account_name(line, account),
+                amount,
+                lot: None,
+                price,
+            },
+            comment,
+        )))
+    }
+
+    /// Parses the cost that follows the `@` of a posting, which is the price of every unit, or the
+    /// total price of the amount when it is given with `@@`.
+    fn parse_cost(
+        &mut self,
+        line: usize,
+        cost: &str,
+        amount: Option<&Amount>,
+    ) -> Result<Amount, ConversionIssue> {
+        let invalid = || ConversionIssue::InvalidAmount(format!("@{}", cost));
+        let (total, cost) = match cost.strip_prefix('@') {
+            Some(cost) => (true, cost),
+            None => (false, cost),
+        };
+        let price = self.parse_amount(line, cost)?.ok_or_else(invalid)?;
+
+        match (total, amount) {
+            (false, Some(_)) => Ok(price),
+            (true, Some(amount)) => Ok(Amount {
+                quantity: price
+                    .quantity
+                    .abs()
+                    .checked_div(amount.quantity.abs())
+                    .ok_or_else(invalid)?,
+                currency: price.currency,
+            }),
+            (_, None) => Err(invalid()),
+        }
+    }
+
+    /// Parses an amount with the commodity before or after the quantity, returning `None` for an
+    /// empty amount. Quantities without commodity take the one declared with `D`.
+    fn parse_amount(
+        &mut self,
+        line: usize,
+        value: &str,
+    ) -> Result<Option<Amount>, ConversionIssue> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+
+        let invalid = || ConversionIssue::InvalidAmount(value.to_owned());
+
+        if value.contains('(') {
+            return Err(invalid());
+        }
+
+        // Quoted commodities can contain digits and signs, so they are taken out before reading
+        // the quantity from the rest of the amount.
+        let (value, quoted_commodity) = match value.split('"').collect::<Vec<&str>>()[..] {
+            [before, commodity, after] => (format!("{} {}", before, after), Some(commodity)),
+            [_] => (value.to_owned(), None),
+            _ => return Err(invalid()),
+        };
+
+        let number: String = value
+            .chars()
+            .filter(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
+            .collect();
+        let commodity: String = match quoted_commodity {
+            Some(commodity) => commodity.to_owned(),
+            None => value
+                .chars()
+                .filter(|c| !c.is_ascii_digit() && !c.is_whitespace() && !".,-+".contains(*c))
+                .collect(),
+        };
+
+        let number = match (number.contains('.'), number.rfind(',')) {
+            (false, Some(index)) if number.len() - index - 1 != 3 => format!(
+                "{}.{}",
+                number[..index].replace(',', ""),
+                &number[index + 1..]
+            ),
+            _ => number.replace(',', ""),
+        };
+        let mut quantity = Decimal::from_str(&number).map_err(|_| invalid())?;
+        if value.contains('-') {
+            quantity = -quantity;
+        }
+
+        let currency = if commodity.is_empty() {
+            self.default_currency.clone().ok_or_else(invalid)?
+        } else {
+            self.convert_currency(line, &commodity)
+        };
+
+        Ok(Some(Amount { quantity, currency }))
+    }
+
+    /// Maps currency symbols to their code and removes anything that is not a letter from other
+    /// commodities, reporting the first time that a commodity had to be renamed.
+    fn convert_currency(&mut self, line: usize, commodity: &str) -> CurrencyCode {
+        if let Some((_, code)) = CURRENCY_SYMBOLS
+            .iter()
+            .find(|(symbol, _)| *symbol == commodity)
+        {
+            return code.to_string();
+        }
+
+        let currency: String = commodity
+            .chars()
+            .filter(|c| c.is_ascii_alphabetic())
+            .collect();
+        let currency = if currency.is_empty() {
+            "UNKNOWN".to_owned()
+        } else {
+            currency
+        };
+
+        if currency != commodity && self.renamed_currencies.insert(commodity.to_owned()) {
+            self.report(
+                line,
+                ConversionIssue::RenamedCurrency {
+                    from: commodity.to_owned(),
+                    to: currency.clone(),
+                },
+            );
+        }
+
+        currency
+    }
+
+    /// Removes anything that is not alphanumeric or a dash from each segment of the account, as
+    /// well as leading dashes, reporting the first time that an account had to be renamed.
+    fn convert_account_name(&mut self, line: usize, name: &str) -> Account {
+        let segments: Vec<String> = name
+            .split(':')
+            .map(|segment| {
+                segment
+                    .chars()
+                    .filter(|&c| c.is_ascii_alphanumeric() || c == '-')
+                    .collect::<String>()
+                    .trim_start_matches('-')
+                    .to_owned()
+            })
+            .filter(|segment| !segment.is_empty())
+            .collect();
+
+        let account = match segments.split_first() {
+            Some((account_name, children)) => Account {
+                name: account_name.clone(),
+                children: children.to_vec(),
+            },
+            None => Account {
+                name: "unknown".to_owned(),
+                children: vec![],
+            },
+        };
+
+        let converted_name = account.to_string();
+        if converted_name != name && self.renamed_accounts.insert(name.to_owned()) {
+            self.report(
+                line,
+                ConversionIssue::RenamedAccount {
+                    from: name.to_owned(),
+                    to: converted_name,
+                },
+            );
+        }
+
+        account
+    }
+
+    /// Splits a comment in the ledger tags (`:tag1:tag2:`), the hledger tags (`tag:`), the
+    /// metadata (`key: value`) and the rest of the text. Tags and metadata are separated by
+    /// commas, and a word is only read as a key when its colon is followed by a space, so that
+    /// words like `http://` are kept in the text.
+    fn parse_comment(&self, comment: &str) -> Comment {
+        let mut result = Comment::default();
+
+        let comment = self
+            .ledger_tags
+            .replace_all(comment, |captures: &regex::Captures| {
+                result.tags.extend(
+                    captures[2]
+                        .split(':')
+                        .filter(|tag| !tag.is_empty())
+                        .map(str::to_owned),
+                );
+                " "
+            });
+
+        let text = comment
+            .split(',')
+            .map(|part| match self.tags.captures(part) {
+                Some(captures) => {
+                    let value = captures.get(2).map_or("", |value| value.as_str().trim());
+
+                    if value.is_empty() {
+                        result.tags.push(captures[1].to_owned());
+                    } else {
+                        result
+                            .metadata
+                            .push((captures[1].to_owned(), value.to_owned()));
+                    }
+                    &part[..captures.get(0).map_or(0, |key| key.start())]
+                }
+                None => part,
+            })
+            .collect::<Vec<&str>>()
+            .join(" ");
+
+        result.text = text
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<&str>>()
+            .join(" ");
+        result
+    }
+}
+
+/// Splits the content in the part before the first `;` and the comment after it, if any.
+fn split_comment(content: &str) -> (&str, Option<&str>) {
+    match content.split_once(';') {
+        Some((content, comment)) => (content, Some(comment)),
+        None => (content, None),
+    }
+}
+
+/// Parses a full date or a date without year, with `/`, `-` or `.` as separators.
+fn parse_date(value: &str) -> Option<ParsedDate> {
+    let parts: Vec<u32> = value
+        .split(['/', '-', '.'])
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u32>>>()?;
+
+    match parts[..] {
+        [year, month, day] => {
+            NaiveDate::from_ymd_opt(year as i32, month, day).map(ParsedDate::Full)
+        }
+        // Partial dates get the same default year that the parser gives them.
+        [month, day] => NaiveDate::from_ymd_opt(2021, month, day).map(ParsedDate::Partial),
+        _ => None,
+    }
+}
+
+/// Turns the postings of a transaction in pairs of postings. The posting without amount, or the
+/// only posting whose sign is different to the rest, balances all the others, so it is paired with
+/// each one of them. Transactions of two postings keep their amounts, while the balancing posting
+/// of split transactions leaves its amount for the parser to infer, which can only be done when
+/// the rest of postings are valued in its currency.
+fn balance_postings(postings: Vec<Posting>) -> Result<Vec<(Posting, Posting)>, ConversionIssue> {
+    if postings.len() < 2 {
+        return Err(ConversionIssue::TooFewPostings);
+    }
+
+    let without_amount: Vec<usize> = postings
+        .iter()
+        .enumerate()
+        .filter(|(_, posting)| posting.amount.is_none())
+        .map(|(index, _)| index)
+        .collect();
+    let is_positive = |posting: &Posting| {
+        posting
+            .amount
+            .as_ref()
+            .is_some_and(|amount| amount.quantity.is_sign_positive())
+    };
+    let positives = postings
+        .iter()
+        .filter(|posting| is_positive(posting))
+        .count();
+
+    let balancing = match without_amount[..] {
+        [index] => index,
+        [] if postings.len() == 2 => 1,
+        [] if positives == 1 => postings.iter().position(is_positive).unwrap(),
+        [] if positives == postings.len() - 1 => postings
+            .iter()
+            .position(|posting| !is_positive(posting))
+            .unwrap(),
+        _ => return Err(ConversionIssue::UnsplittableTransaction),
+    };
+
+    let mut balancing_posting = postings[balancing].clone();
+
+    if postings.len() > 2 {
+        if let Some(amount) = &balancing_posting.amount {
+            let is_inferable = postings.iter().enumerate().all(|(index, posting)| {
+                index == balancing
+                    || posting
+                        .weight()
+                        .ok()
+                        .flatten()
+                        .is_some_and(|weight| weight.currency == amount.currency)
+            });
+
+            if !is_inferable {
+                return Err(ConversionIssue::UnsplittableTransaction);
+            }
+        }
+        balancing_posting.amount = None;
+    }
+
+    Ok(postings
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| *index != balancing)
+        .map(|(_, posting)| (posting, balancing_posting.clone()))
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal_macros::dec;
+
+    use super::convert;
+
+    use crate::convert::{ConversionIssue, ReportedIssue};
+    use crate::parser::ast::*;
+    use crate::parser::parse_journal;
+
+    /// Parses the elements of the journal, leaving out empty lines.
+    fn journal(content: &str) -> Vec<JournalElement> {
+        parse_journal(content)
+            .unwrap()
+            .into_iter()
+            .filter(|element| *element != JournalElement::Empty)
+            .collect()
+    }
+
+    fn issue(line: usize, issue: ConversionIssue) -> ReportedIssue {
+        ReportedIssue { line, issue }
+    }
+
+    #[test]
+    fn converts_ledger_transaction() {
+        let conversion = convert(
+            "2021/10/07 * (42) Tesco  ; :food:weekly:
+    ; Receipt: 123
+    Expenses:Food    25.30 EUR
+    Assets:Bank     -25.30 EUR
+",
+        );
+
+        assert_eq!(conversion.issues, vec![]);
+        assert_eq!(
+            conversion.journal,
+            journal(
+                "2021-10-07 Tesco ;food,weekly ;code:42,Receipt:123\n\
+                 Expenses:Food 25.30 EUR\nAssets:Bank -25.30 EUR\n"
+            )
+        )
+    }
+
+    #[test]
+    fn converts_hledger_transaction_with_note_and_tags() {
+        let conversion = convert(
+            "2021-10-07 ! Tesco | Weekly shop  ; food:, import-id: ABC
+    assets:bank
+    expenses:food  €25.30
+",
+        );
+
+        assert_eq!(
+            conversion.journal,
+            journal(
+                "2021-10-07 ! Tesco | Weekly shop ;food ;import-id:ABC\n\
+                 expenses:food 25.30 EUR\nassets:bank\n"
+            )
+        )
+    }
+
+    #[test]
+    fn splits_transactions_with_more_than_two_postings() {
+        let conversion = convert(
+            "2021/10/07 Tesco
+    Expenses:Food    20 EUR
+    Expenses:Home    5 EUR
+    Assets:Bank
+",
+        );
+
+        assert_eq!(
+            conversion.journal,
+            journal(
+                "2021-10-07 Tesco\nExpenses:Food 20 EUR\nAssets:Bank\n\n\
+                 2021-10-07 Tesco\nExpenses:Home 5 EUR\nAssets:Bank\n"
+            )
+        );
+        assert_eq!(
+            conversion.issues,
+            vec![issue(1, ConversionIssue::SplitTransaction(2))]
+        )
+    }
+
+    #[test]
+    fn drops_transactions_that_cannot_be_split() {
+        let conversion = convert(
+            "2021/10/07 Exchange
+    Assets:A    20 EUR
+    Assets:B    5 EUR
+    Assets:C    -10 EUR
+    Assets:D    -15 EUR
+",
+        );
+
+        assert_eq!(conversion.journal, vec![]);
+        assert_eq!(
+            conversion.issues,
+            vec![issue(1, ConversionIssue::UnsplittableTransaction)]
+        )
+    }
+
+    #[test]
+    fn converts_directives() {
+        let conversion = convert(
+            "; Main journal
+account Assets:Checking Account
+    note Main account
+Y2021
+P 2021/10/01 12:00:00 USD 0.85 EUR
+commodity EUR
+= /Food/
+    (Savings)  0.1
+",
+        );
+
+        assert_eq!(
+            conversion.journal,
+            journal("account Assets:CheckingAccount\n\ny2021\n\nP 2021-10-01 USD 0.85 EUR\n")
+        );
+        assert_eq!(
+            conversion.issues,
+            vec![
+                issue(1, ConversionIssue::DroppedComment),
+                issue(
+                    2,
+                    ConversionIssue::RenamedAccount {
+                        from: "Assets:Checking Account".to_owned(),
+                        to: "Assets:CheckingAccount".to_owned()
+                    }
+                ),
+                issue(
+                    3,
+                    ConversionIssue::DroppedDirective("account note".to_owned())
+                ),
+                issue(6, ConversionIssue::DroppedDirective("commodity".to_owned())),
+                issue(7, ConversionIssue::DroppedAutomatedTransaction),
+            ]
+        )
+    }
+
+    #[test]
+    fn converts_costs_and_drops_assertions_and_virtual_postings() {
+        let conversion = convert(
+            "10/07 Exchange
+    Assets:Dollars    $100 @ 0.85 EUR
+    Assets:Euros    -85 EUR = 1000 EUR
+    (Budget:Travel)    -100 USD
+",
+        );
+
+        assert_eq!(
+            conversion.journal,
+            journal("10-07 Exchange\nAssets:Dollars 100 USD @ 0.85 EUR\nAssets:Euros -85 EUR\n")
+        );
+        assert_eq!(
+            conversion.issues,
+            vec![
+                issue(3, ConversionIssue::DroppedBalanceAssertion),
+                issue(
+                    4,
+                    ConversionIssue::DroppedVirtualPosting("(Budget:Travel)".to_owned())
+                ),
+            ]
+        )
+    }
+
+    #[test]
+    fn uses_default_commodity_for_quantities_without_one() {
+        let conversion = convert(
+            "D 1,000.00 EUR
+2021/10/07 Tesco
+    Expenses:Food    1,250.5
+    Assets:Bank
+",
+        );
+
+        assert_eq!(
+            conversion.journal,
+            journal("2021-10-07 Tesco\nExpenses:Food 1250.5 EUR\nAssets:Bank\n")
+        )
+    }
+
+    #[test]
+    fn reports_invalid_amounts() {
+        let conversion = convert(
+            "2021/10/07 Tesco
+    Expenses:Food    (10 EUR * 2)
+    Assets:Bank
+",
+        );
+
+        assert_eq!(conversion.journal, vec![]);
+        assert_eq!(
+            conversion.issues,
+            vec![issue(
+                2,
+                ConversionIssue::InvalidAmount("(10 EUR * 2)".to_owned())
+            )]
+        )
+    }
+
+    #[test]
+    fn converts_total_costs_to_the_price_of_every_unit() {
+        let conversion = convert(
+            "2021/10/07 Broker
+    Assets:Broker    10 VWCE @@ 952 EUR
+    Assets:Bank
+",
+        );
+
+        assert_eq!(
+            conversion
+                .journal
+                .iter()
+                .map(|element| match element {
+                    JournalElement::Transaction(transaction) =>
+                        transaction.postings.0.price.clone(),
+                    _ => None,
+                })
+                .collect::<Vec<Option<Amount>>>(),
+            vec![Some(Amount {
+                quantity: dec!(95.2),
+                currency: "EUR".to_owned(),
+            })]
+        );
+        assert_eq!(conversion.issues, vec![]);
+    }
+
+    #[test]
+    fn drops_split_transactions_whose_balancing_amount_cannot_be_inferred() {
+        let conversion = convert(
+            "2021/10/07 Exchange
+    Assets:Dollars    -10 USD
+    Assets:Pounds    -10 GBP
+    Assets:Euros    20 EUR
+",
+        );
+
+        assert_eq!(conversion.journal, vec![]);
+        assert_eq!(
+            conversion.issues,
+            vec![issue(1, ConversionIssue::UnsplittableTransaction)]
+        )
+    }
+
+    #[test]
+    fn keeps_digits_and_signs_of_quoted_commodities_out_of_the_quantity() {
+        let conversion = convert(
+            "2021/10/07 Broker
+    Assets:Broker    10 \"S&P-500\"
+    Assets:Bank    -1,000.50 \"EUR 2\"
+",
+        );
+
+        assert_eq!(
+            conversion.journal,
+            journal("2021-10-07 Broker\nAssets:Broker 10 SP\nAssets:Bank -1000.50 EUR\n")
+        );
+    }
+
+    #[test]
+    fn keeps_words_with_colons_that_are_not_keys_in_the_comment() {
+        let conversion = convert(
+            "2021/10/07 Tesco  ; see http://tesco.com at 12:30, food:, receipt: 123
+    Expenses:Food    25.30 EUR
+    Assets:Bank
+",
+        );
+
+        // The description can't be written in the journal syntax, so it is set afterwards
+        let mut expected =
+            journal("2021-10-07 Tesco ;food ;receipt:123\nExpenses:Food 25.30 EUR\nAssets:Bank\n");
+        if let JournalElement::Transaction(transaction) = &mut expected[0] {
+            transaction.description = "see http://tesco.com at 12:30".to_owned();
+        }
+
+        assert_eq!(conversion.journal, expected);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::parser::ast::JournalElement;
+
+pub mod ledger;
+
+/// Defines the constructs of a journal in another syntax that could not be converted as they were.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ConversionIssue {
+    /// Comments that are not tags or metadata are not kept by the parser.
+    DroppedComment,
+    DroppedDirective(String),
+    DroppedAutomatedTransaction,
+    DroppedPeriodicTransaction,
+    DroppedVirtualPosting(String),
+    DroppedPostingStatus,
+    DroppedBalanceAssertion,
+    /// The transaction was dropped since its date could not be read.
+    InvalidDate(String),
+    /// The transaction was dropped since one of its amounts could not be read.
+    InvalidAmount(String),
+    /// The transaction was dropped since it did not have two postings.
+    TooFewPostings,
+    /// The transaction was dropped since it had more than two postings and none of them balanced
+    /// all the others, or the one that balanced them had an amount that could not be inferred.
+    UnsplittableTransaction,
+    /// The transaction had more than two postings and was split in the given number of
+    /// transactions of two postings each.
+    SplitTransaction(usize),
+    RenamedAccount {
+        from: String,
+        to: String,
+    },
+    RenamedCurrency {
+        from: String,
+        to: String,
+    },
+}
+
+/// Wraps an issue with the line of the original journal in which it happened.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ReportedIssue {
+    pub line: usize,
+    pub issue: ConversionIssue,
+}
+
+/// Result of converting a journal, with the converted elements and the issues found.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Conversion {
+    pub journal: Vec<JournalElement>,
+    pub issues: Vec<ReportedIssue>,
+}
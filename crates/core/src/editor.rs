@@ -0,0 +1,283 @@
+use crate::error::ParseError;
+use crate::formatter::{format_amount, format_element};
+use crate::journal::resolved_postings;
+use crate::parser::{
+    ast::{JournalElement, Position, Span},
+    parse_journal_with_spans,
+};
+
+/// Defines what is being written at the end of a line of a journal, so that editors can suggest
+/// values of the right kind.
+#[derive(Debug, PartialEq)]
+pub enum CompletionKind {
+    Account,
+    Payee,
+    Tag,
+    Currency,
+    Nothing,
+}
+
+/// Represents a change to the content of a journal that replaces the text from `start` until the
+/// character that precedes `end`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Edit {
+    pub start: Position,
+    pub end: Position,
+    pub text: String,
+}
+
+/// Represents the amount that can be written in the posting of a transaction that omitted it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BalancingAmount {
+    pub transaction: Span,
+    pub edit: Edit,
+}
+
+/// Returns what is being written at the end of the given text, which is the part of a line that
+/// precedes the cursor.
+pub fn completion_kind(line: &str) -> CompletionKind {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let current_word = if line.is_empty() || line.ends_with(char::is_whitespace) {
+        words.len()
+    } else {
+        words.len() - 1
+    };
+
+    match line.chars().next() {
+        None | Some('=') | Some('/') => CompletionKind::Nothing,
+        Some(c) if c.is_ascii_digit() => match line.rfind(';') {
+            Some(index) if !line[index..].contains(':') => CompletionKind::Tag,
+            Some(_) => CompletionKind::Nothing,
+            None if current_word > 0 && !line.contains('|') => CompletionKind::Payee,
+            None => CompletionKind::Nothing,
+        },
+        _ if words.first() == Some(&"account") => CompletionKind::Account,
+        _ if words.first() == Some(&"P") => match current_word {
+            2 | 4 => CompletionKind::Currency,
+            _ => CompletionKind::Nothing,
+        },
+        _ => match current_word {
+            0 => CompletionKind::Account,
+            2 => CompletionKind::Currency,
+            _ => CompletionKind::Nothing,
+        },
+    }
+}
+
+/// Returns the account, or any other word, that contains the character at the given column of
+/// the line. Columns start at 1, like in `Position`.
+pub fn word_at(line: &str, column: usize) -> Option<String> {
+    let characters: Vec<char> = line.chars().collect();
+    let is_part_of_word = |c: &char| c.is_alphanumeric() || *c == ':' || *c == '-';
+    let index = column.checked_sub(1)?;
+
+    if !characters.get(index).is_some_and(is_part_of_word) {
+        return None;
+    }
+
+    let start = characters[..index]
+        .iter()
+        .rposition(|c| !is_part_of_word(c))
+        .map_or(0, |position| position + 1);
+    let end = characters[index..]
+        .iter()
+        .position(|c| !is_part_of_word(c))
+        .map_or(characters.len(), |position| index + position);
+
+    Some(characters[start..end].iter().collect())
+}
+
+/// Returns the span of the `account` declaration of the given account, if the journal can be
+/// parsed and declares it.
+pub fn find_declaration(content: &str, account: &str) -> Option<Span> {
+    parse_journal_with_spans(content)
+        .ok()?
+        .into_iter()
+        .find_map(|(element, span)| match element {
+            JournalElement::Account(declared) if declared.to_string() == account => Some(span),
+            _ => None,
+        })
+}
+
+/// Returns the edits that format every element of the journal, leaving comments and empty lines
+/// as they are. Elements that are already formatted don't get an edit.
+pub fn format_edits(content: &str) -> Result<Vec<Edit>, ParseError> {
+    let lines: Vec<&str> = content.lines().collect();
+
+    Ok(parse_journal_with_spans(content)?
+        .into_iter()
+        .filter(|(element, _)| !matches!(element, JournalElement::Empty | JournalElement::Comment))
+        .filter_map(|(element, span)| {
+            let formatted = format_element(&element).trim_end().to_owned();
+            let end = Position {
+                line: span.end.line,
+                column: span.end.column + 1,
+            };
+
+            if text_between(&lines, span.start, end) == formatted {
+                None
+            } else {
+                Some(Edit {
+                    start: span.start,
+                    end,
+                    text: formatted,
+                })
+            }
+        })
+        .collect())
+}
+
+/// Returns the edits that write the amount of every posting that omitted it, which is the amount
+/// that balances the transaction.
+pub fn balancing_amounts(content: &str) -> Vec<BalancingAmount> {
+    let elements = match parse_journal_with_spans(content) {
+        Ok(elements) => elements,
+        Err(_) => return vec![],
+    };
+    let lines: Vec<&str> = content.lines().collect();
+
+    elements
+        .into_iter()
+        .filter_map(|(element, span)| {
+            let transaction = match element {
+                JournalElement::Transaction(transaction) => transaction,
+                _ => return None,
+            };
+            let (first_posting, second_posting) = resolved_postings(&transaction);
+            let (elided_posting, amount) = if transaction.postings.0.amount.is_none() {
+                (&transaction.postings.0, first_posting.amount?)
+            } else if transaction.postings.1.amount.is_none() {
+                (&transaction.postings.1, second_posting.amount?)
+            } else {
+                return None;
+            };
+
+            // The header can't be a posting, so the search starts at the line that follows it
+            let account = elided_posting.account.to_string();
+            let line = (span.start.line + 1..=span.end.line)
+                .rev()
+                .find(|line| lines.get(line - 1).map(|text| text.trim()) == Some(&account))?;
+            let position = Position {
+                line,
+                column: lines[line - 1].trim_end().chars().count() + 1,
+            };
+
+            Some(BalancingAmount {
+                transaction: span,
+                edit: Edit {
+                    start: position,
+                    end: position,
+                    text: format!(" {}", format_amount(&amount)),
+                },
+            })
+        })
+        .collect()
+}
+
+fn text_between(lines: &[&str], start: Position, end: Position) -> String {
+    (start.line..=end.line)
+        .filter_map(|line| {
+            let text = lines.get(line - 1)?;
+            let skipped = if line == start.line {
+                start.column - 1
+            } else {
+                0
+            };
+            let taken = if line == end.line {
+                end.column - 1 - skipped
+            } else {
+                usize::MAX
+            };
+
+            Some(text.chars().skip(skipped).take(taken).collect::<String>())
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parser::ast::Position;
+
+    use super::{
+        balancing_amounts, completion_kind, find_declaration, format_edits, word_at,
+        CompletionKind, Edit,
+    };
+
+    #[test]
+    fn detects_what_is_being_written() {
+        assert_eq!(completion_kind("exp"), CompletionKind::Account);
+        assert_eq!(completion_kind("expenses 10 "), CompletionKind::Currency);
+        assert_eq!(completion_kind("expenses 10 EU"), CompletionKind::Currency);
+        assert_eq!(completion_kind("account ass"), CompletionKind::Account);
+        assert_eq!(completion_kind("2021-10-07 Tes"), CompletionKind::Payee);
+        assert_eq!(completion_kind("2021-10-07 ! "), CompletionKind::Payee);
+        assert_eq!(
+            completion_kind("2021-10-07 Tesco | Fo"),
+            CompletionKind::Nothing
+        );
+        assert_eq!(completion_kind("2021-10-07 Tesco ;fo"), CompletionKind::Tag);
+        assert_eq!(
+            completion_kind("2021-10-07 Tesco ;a:b"),
+            CompletionKind::Nothing
+        );
+        assert_eq!(completion_kind("P 2021-10-07 US"), CompletionKind::Currency);
+        assert_eq!(completion_kind(""), CompletionKind::Nothing);
+    }
+
+    #[test]
+    fn returns_word_at_column() {
+        assert_eq!(
+            word_at("expenses:food 10 EUR", 5),
+            Some("expenses:food".to_owned())
+        );
+        assert_eq!(word_at("expenses:food 10 EUR", 15), Some("10".to_owned()));
+        assert_eq!(word_at("expenses:food 10 EUR", 14), None);
+        assert_eq!(word_at("expenses", 20), None);
+    }
+
+    #[test]
+    fn finds_account_declarations() {
+        let content = "account assets:bank\n\naccount expenses\n";
+
+        assert_eq!(
+            find_declaration(content, "expenses").map(|span| span.start),
+            Some(Position { line: 3, column: 1 })
+        );
+        assert_eq!(find_declaration(content, "assets"), None);
+    }
+
+    #[test]
+    fn formats_elements_keeping_comments() {
+        let content =
+            "// Groceries\n2021-10-07 Tesco\nexpenses   10 EUR\nassets\n\naccount assets\n";
+
+        assert_eq!(
+            format_edits(content),
+            Ok(vec![Edit {
+                start: Position { line: 2, column: 1 },
+                end: Position { line: 4, column: 7 },
+                text: "2021-10-07 Tesco\nexpenses 10 EUR\nassets".to_owned(),
+            }])
+        );
+    }
+
+    #[test]
+    fn returns_amounts_that_balance_transactions() {
+        let content = "2021-10-07 Tesco\nexpenses 10 EUR\nassets\n\n\
+                       2021-10-08 Aldi\nexpenses 5 EUR\nassets -5 EUR\n";
+
+        let balancing_amounts = balancing_amounts(content);
+
+        assert_eq!(balancing_amounts.len(), 1);
+        assert_eq!(
+            balancing_amounts[0].edit,
+            Edit {
+                start: Position { line: 3, column: 7 },
+                end: Position { line: 3, column: 7 },
+                text: " -10 EUR".to_owned(),
+            }
+        );
+        assert_eq!(balancing_amounts[0].transaction.end.line, 3);
+    }
+}
@@ -0,0 +1,237 @@
+use std::fmt::{self, Display};
+use std::io;
+use std::sync::Arc;
+
+use crate::journal::CheckError;
+use crate::parser::ast::Span;
+
+/// Defines every error that can happen while loading, parsing and checking a journal, so that
+/// tools can handle them with a single type.
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// A file could not be read or written.
+    Io(IoError),
+    /// The content is not a valid journal.
+    Parse(ParseError),
+    /// The query is not valid.
+    Query(ParseError),
+    /// The journal was parsed but it is not valid.
+    Check(CheckError),
+    /// A journal included by another one could not be read or parsed.
+    Include { path: String, error: Box<Error> },
+    /// A journal includes itself, directly or through other journals.
+    IncludeCycle(String),
+    /// An amount is too large to be computed.
+    Overflow(OverflowError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "{}", error),
+            Error::Parse(error) => write!(f, "There was an error parsing the journal:\n{}", error),
+            Error::Query(error) => write!(f, "There was an error parsing the query:\n{}", error),
+            Error::Check(error) => write!(f, "{}", error),
+            Error::Include { path, error } => {
+                write!(
+                    f,
+                    "In the journal \"{}\" that is included:\n{}",
+                    path, error
+                )
+            }
+            Error::IncludeCycle(path) => write!(f, "The journal \"{}\" includes itself", path),
+            Error::Overflow(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            Error::Parse(error) | Error::Query(error) => Some(error),
+            Error::Check(error) => Some(error),
+            Error::Include { error, .. } => Some(error.as_ref()),
+            Error::IncludeCycle(_) => None,
+            Error::Overflow(error) => Some(error),
+        }
+    }
+}
+
+impl From<IoError> for Error {
+    fn from(error: IoError) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        Error::Parse(error)
+    }
+}
+
+impl From<CheckError> for Error {
+    fn from(error: CheckError) -> Self {
+        Error::Check(error)
+    }
+}
+
+impl From<OverflowError> for Error {
+    fn from(error: OverflowError) -> Self {
+        Error::Overflow(error)
+    }
+}
+
+/// Describes a file that could not be read or written, keeping the error of the operating system
+/// as its cause.
+#[derive(Debug, Clone)]
+pub struct IoError {
+    pub path: String,
+    cause: Arc<io::Error>,
+}
+
+impl IoError {
+    /// Creates the error of accessing the file in the given path.
+    pub fn new(path: &str, cause: io::Error) -> Self {
+        Self {
+            path: path.to_owned(),
+            cause: Arc::new(cause),
+        }
+    }
+
+    /// Returns the error of the operating system that caused the failure.
+    pub fn cause(&self) -> &io::Error {
+        &self.cause
+    }
+
+    /// Returns the kind of the error of the operating system.
+    pub fn kind(&self) -> io::ErrorKind {
+        self.cause.kind()
+    }
+
+    /// Returns whether the failure was caused by the file not existing.
+    pub fn is_not_found(&self) -> bool {
+        self.kind() == io::ErrorKind::NotFound
+    }
+}
+
+/// Errors are equal when they happened in the same path for the same kind of reason, since the
+/// errors of the operating system can't be compared.
+impl PartialEq for IoError {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path && self.kind() == other.kind()
+    }
+}
+
+impl Display for IoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_not_found() {
+            write!(f, "File \"{}\" not found", self.path)
+        } else {
+            write!(
+                f,
+                "File \"{}\" could not be accessed: {}",
+                self.path, self.cause
+            )
+        }
+    }
+}
+
+impl std::error::Error for IoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.cause.as_ref())
+    }
+}
+
+/// Describes why and where the parsing of some content failed.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseError {
+    /// Short description of the failure, like `Expected ':' while parsing the posting`.
+    pub message: String,
+    /// Part of the content that could not be parsed, which starts where the parsing failed and
+    /// ends at the last character of the word found there.
+    pub span: Span,
+    /// Descriptions of what would have been valid where the parsing failed, like `':'` or `digit`.
+    pub expected: Vec<String>,
+    /// Names of the elements that were being parsed when it failed, from the outermost to the
+    /// innermost one, like `transaction`, `posting` and `amount`.
+    pub context: Vec<String>,
+    /// Line of the content where the parsing failed.
+    pub line: Box<str>,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} at line {}, column {}:",
+            self.message, self.span.start.line, self.span.start.column
+        )?;
+        writeln!(f, "{}", self.line)?;
+        write!(
+            f,
+            "{}^",
+            " ".repeat(self.span.start.column.saturating_sub(1))
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Describes an amount that could not be computed because it does not fit in a decimal, like the
+/// value of a huge quantity at its price or the sum of huge amounts.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct OverflowError;
+
+impl Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The amounts are too large to be computed")
+    }
+}
+
+impl std::error::Error for OverflowError {}
+
+#[cfg(test)]
+mod test {
+    use std::io;
+
+    use super::{Error, IoError, ParseError};
+
+    use crate::parser::ast::{Position, Span};
+
+    #[test]
+    fn describes_parse_errors_with_their_line() {
+        let error = ParseError {
+            message: "Expected a digit".to_owned(),
+            span: Span {
+                start: Position { line: 2, column: 5 },
+                end: Position { line: 2, column: 5 },
+            },
+            expected: vec!["digit".to_owned()],
+            context: vec!["transaction".to_owned()],
+            line: "2021-xx-01 Tesco".into(),
+        };
+
+        assert_eq!(
+            Error::Parse(error).to_string(),
+            "There was an error parsing the journal:\n\
+             Expected a digit at line 2, column 5:\n\
+             2021-xx-01 Tesco\n    ^"
+        )
+    }
+
+    #[test]
+    fn keeps_the_path_and_cause_of_io_errors() {
+        let error = IoError::new(
+            "journal.ledger",
+            io::Error::new(io::ErrorKind::PermissionDenied, "denied"),
+        );
+
+        assert!(!error.is_not_found());
+        assert_eq!(error.cause().to_string(), "denied");
+        assert_eq!(
+            error.to_string(),
+            "File \"journal.ledger\" could not be accessed: denied"
+        );
+    }
+}
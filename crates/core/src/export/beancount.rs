@@ -0,0 +1,409 @@
+use std::collections::BTreeSet;
+
+use chrono::NaiveDate;
+
+use crate::journal::{automated, resolved_postings};
+use crate::parser::ast::{
+    Amount, AutomatedTransaction, JournalElement, Posting, Price, Transaction, TransactionStatus,
+};
+
+use super::{Export, ExportWarning};
+
+/// Root accounts of beancount and the names that are mapped to each of them.
+const ROOT_ACCOUNTS: [(&str, &[&str]); 5] = [
+    ("Assets", &["assets", "asset"]),
+    ("Liabilities", &["liabilities", "liability"]),
+    ("Equity", &["equity"]),
+    ("Income", &["income", "revenue", "revenues"]),
+    ("Expenses", &["expenses", "expense"]),
+];
+
+/// Account that takes the gains of sales of lots at a price, since beancount balances the units of
+/// a lot at their cost.
+const GAINS_ACCOUNT: &str = "income:gains";
+
+/// Exports the given journal to the beancount syntax.
+pub fn export(journal: &[JournalElement]) -> Export {
+    let mut warnings = Vec::new();
+
+    let automated_transactions: Vec<AutomatedTransaction> = journal
+        .iter()
+        .filter_map(|element| match element {
+            JournalElement::AutomatedTransaction(automated_transaction) => {
+                Some(automated_transaction.clone())
+            }
+            _ => None,
+        })
+        .collect();
+    warnings.extend(
+        automated_transactions
+            .iter()
+            .cloned()
+            .map(ExportWarning::ExpandedAutomatedTransaction),
+    );
+
+    let transactions = automated::expand(
+        journal
+            .iter()
+            .filter_map(|element| match element {
+                JournalElement::Transaction(transaction) => Some(transaction.clone()),
+                _ => None,
+            })
+            .collect(),
+        &automated_transactions,
+    );
+    let prices: Vec<&Price> = journal
+        .iter()
+        .filter_map(|element| match element {
+            JournalElement::Price(price) => Some(price),
+            _ => None,
+        })
+        .collect();
+
+    let accounts: BTreeSet<String> = journal
+        .iter()
+        .filter_map(|element| match element {
+            JournalElement::Account(account) => Some(account.to_string()),
+            _ => None,
+        })
+        .chain(transactions.iter().flat_map(|transaction| {
+            vec![&transaction.postings.0, &transaction.postings.1]
+                .into_iter()
+                .chain(transaction.automated_postings.iter())
+                .map(|posting| posting.account.to_string())
+        }))
+        .chain(
+            transactions
+                .iter()
+                .filter(|transaction| sells_at_price(transaction))
+                .map(|_| GAINS_ACCOUNT.to_owned()),
+        )
+        .collect();
+    let mut currencies: BTreeSet<String> = BTreeSet::new();
+
+    let opening_date = transactions
+        .iter()
+        .map(|transaction| transaction.date.date())
+        .chain(prices.iter().map(|price| price.date.date()))
+        .min()
+        .unwrap_or_else(|| NaiveDate::from_ymd_opt(1970, 1, 1).unwrap());
+
+    let mut sections: Vec<String> = Vec::new();
+
+    if !accounts.is_empty() {
+        sections.push(
+            accounts
+                .iter()
+                .map(|account| {
+                    format!(
+                        "{} open {}\n",
+                        opening_date.format("%Y-%m-%d"),
+                        format_account(account, &mut warnings)
+                    )
+                })
+                .collect(),
+        );
+    }
+
+    sections.extend(prices.iter().map(|price| {
+        format!(
+            "{} price {} {}\n",
+            price.date.date().format("%Y-%m-%d"),
+            format_currency(&price.currency, &mut currencies, &mut warnings),
+            format_amount(&price.amount, &mut currencies, &mut warnings)
+        )
+    }));
+
+    sections.extend(
+        transactions
+            .iter()
+            .map(|transaction| format_transaction(transaction, &mut currencies, &mut warnings)),
+    );
+
+    Export {
+        content: sections.join("\n"),
+        warnings,
+    }
+}
+
+fn format_transaction(
+    transaction: &Transaction,
+    currencies: &mut BTreeSet<String>,
+    warnings: &mut Vec<ExportWarning>,
+) -> String {
+    let status = match transaction.status {
+        TransactionStatus::Cleared => '*',
+        TransactionStatus::Pending => '!',
+    };
+    let mut header = format!(
+        "{} {} \"{}\" \"{}\"",
+        transaction.date.date().format("%Y-%m-%d"),
+        status,
+        escape(&transaction.payee),
+        escape(&transaction.description)
+    );
+
+    transaction
+        .tags
+        .iter()
+        .for_each(|tag| header.push_str(&format!(" #{}", tag)));
+
+    let mut lines = vec![header];
+
+    lines.extend(
+        transaction
+            .metadata
+            .iter()
+            .map(|(key, value)| format!("  {}: \"{}\"", key.to_lowercase(), escape(value))),
+    );
+
+    // Beancount balances the units of a lot at their cost instead of at their price, so sales take
+    // the difference in a gains posting, which has to be the only posting without an amount.
+    let sells_at_price = sells_at_price(transaction);
+    let postings = if sells_at_price {
+        resolved_postings(transaction)
+    } else {
+        transaction.postings.clone()
+    };
+
+    lines.extend(
+        vec![&postings.0, &postings.1]
+            .into_iter()
+            .chain(transaction.automated_postings.iter())
+            .map(|posting| format_posting(posting, currencies, warnings)),
+    );
+
+    if sells_at_price {
+        lines.push(format!(
+            "  {}",
+            format_account(GAINS_ACCOUNT, &mut Vec::new())
+        ));
+        warnings.push(ExportWarning::AddedGainsPosting(transaction.clone()));
+    }
+
+    format!("{}\n", lines.join("\n"))
+}
+
+/// Returns whether the transaction sells units of a lot at a price.
+fn sells_at_price(transaction: &Transaction) -> bool {
+    vec![&transaction.postings.0, &transaction.postings.1]
+        .into_iter()
+        .any(|posting| posting.lot.is_some() && posting.price.is_some())
+}
+
+fn format_posting(
+    posting: &Posting,
+    currencies: &mut BTreeSet<String>,
+    warnings: &mut Vec<ExportWarning>,
+) -> String {
+    // Renamed accounts were already reported when opening them.
+    let account = format_account(&posting.account.to_string(), &mut Vec::new());
+
+    let amount = match &posting.amount {
+        Some(amount) => format_amount(amount, currencies, warnings),
+        None => return format!("  {}", account),
+    };
+    let lot = match &posting.lot {
+        Some(lot) => match lot.date {
+            Some(date) => format!(
+                " {{{}, {}}}",
+                format_amount(&lot.cost, currencies, warnings),
+                date.format("%Y-%m-%d")
+            ),
+            None => format!(" {{{}}}", format_amount(&lot.cost, currencies, warnings)),
+        },
+        None => String::new(),
+    };
+    let price = match &posting.price {
+        Some(price) => format!(" @ {}", format_amount(price, currencies, warnings)),
+        None => String::new(),
+    };
+
+    format!("  {}  {}{}{}", account, amount, lot, price)
+}
+
+fn format_amount(
+    amount: &Amount,
+    currencies: &mut BTreeSet<String>,
+    warnings: &mut Vec<ExportWarning>,
+) -> String {
+    format!(
+        "{} {}",
+        amount.quantity,
+        format_currency(&amount.currency, currencies, warnings)
+    )
+}
+
+/// Uppercases the currency, warning the first time that a currency has to be renamed.
+fn format_currency(
+    currency: &str,
+    currencies: &mut BTreeSet<String>,
+    warnings: &mut Vec<ExportWarning>,
+) -> String {
+    let renamed = currency.to_uppercase();
+
+    if renamed != currency && currencies.insert(currency.to_owned()) {
+        warnings.push(ExportWarning::RenamedCurrency {
+            from: currency.to_owned(),
+            to: renamed.clone(),
+        });
+    }
+
+    renamed
+}
+
+/// Capitalises every segment of the account and maps its root to one of the roots of beancount,
+/// moving accounts with any other root under `Equity` and warning about it.
+fn format_account(account: &str, warnings: &mut Vec<ExportWarning>) -> String {
+    let segments: Vec<String> = account.split(':').map(capitalise).collect();
+    let root = account.split(':').next().unwrap_or_default().to_lowercase();
+
+    match ROOT_ACCOUNTS
+        .iter()
+        .find(|(_, names)| names.contains(&root.as_str()))
+    {
+        Some((beancount_root, _)) => std::iter::once(beancount_root.to_string())
+            .chain(segments.into_iter().skip(1))
+            .collect::<Vec<String>>()
+            .join(":"),
+        None => {
+            let renamed = format!("Equity:{}", segments.join(":"));
+            warnings.push(ExportWarning::RenamedAccount {
+                from: account.to_owned(),
+                to: renamed.clone(),
+            });
+            renamed
+        }
+    }
+}
+
+fn capitalise(segment: &str) -> String {
+    let mut chars = segment.chars();
+
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod test {
+    use super::export;
+
+    use crate::export::ExportWarning;
+    use crate::parser::ast::*;
+    use crate::parser::parse_journal;
+
+    fn journal(account: &str) -> Vec<JournalElement> {
+        parse_journal(&format!(
+            "2021-10-07 Tesco ;food ;import-id:ABC\n{} 25.30 EUR\nassets:bank\n",
+            account
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn exports_transaction_with_open_directives() {
+        assert_eq!(
+            export(&journal("expenses:food")).content,
+            "2021-10-07 open Assets:Bank
+2021-10-07 open Expenses:Food
+
+2021-10-07 * \"Tesco\" \"\" #food
+  import-id: \"ABC\"
+  Expenses:Food  25.30 EUR
+  Assets:Bank
+"
+        )
+    }
+
+    #[test]
+    fn moves_accounts_with_unknown_root_under_equity() {
+        let export = export(&journal("savings:holidays"));
+
+        assert!(export
+            .content
+            .contains("  Equity:Savings:Holidays  25.30 EUR"));
+        assert_eq!(
+            export.warnings,
+            vec![ExportWarning::RenamedAccount {
+                from: "savings:holidays".to_owned(),
+                to: "Equity:Savings:Holidays".to_owned()
+            }]
+        )
+    }
+
+    #[test]
+    fn expands_automated_transactions() {
+        let elements = parse_journal(
+            "= expenses:food\nassets:savings 0.1\nassets:bank -0.1\n\n\
+             2021-10-07 Tesco\nexpenses:food 25.30 EUR\nassets:bank\n",
+        )
+        .unwrap();
+
+        let export = export(&elements);
+
+        assert!(export.content.contains(
+            "  Expenses:Food  25.30 EUR\n  Assets:Bank\n  Assets:Savings  2.53 EUR\n  \
+             Assets:Bank  -2.53 EUR\n"
+        ));
+        assert_eq!(
+            export.warnings,
+            vec![ExportWarning::ExpandedAutomatedTransaction(
+                match &elements[0] {
+                    JournalElement::AutomatedTransaction(automated_transaction) => {
+                        automated_transaction.clone()
+                    }
+                    element => panic!("{:?} is not an automated transaction", element),
+                }
+            )]
+        )
+    }
+
+    #[test]
+    fn adds_a_gains_posting_to_sales_at_a_price() {
+        let journal = parse_journal(
+            "2022-03-01 Broker\n\
+             assets:broker -5 VWCE {95.20 EUR} @ 110 EUR\n\
+             assets:bank\n",
+        )
+        .unwrap();
+
+        let export = export(&journal);
+
+        assert_eq!(
+            export.content,
+            "2022-03-01 open Assets:Bank
+2022-03-01 open Assets:Broker
+2022-03-01 open Income:Gains
+
+2022-03-01 * \"Broker\" \"\"
+  Assets:Broker  -5 VWCE {95.20 EUR} @ 110 EUR
+  Assets:Bank  550 EUR
+  Income:Gains
+"
+        );
+        assert!(matches!(
+            export.warnings[..],
+            [ExportWarning::AddedGainsPosting(_)]
+        ));
+    }
+
+    #[test]
+    fn exports_prices_with_uppercase_currencies() {
+        let export = export(&parse_journal("P 2021-10-07 btc 50000 EUR\n").unwrap());
+
+        assert_eq!(export.content, "2021-10-07 price BTC 50000 EUR\n");
+        assert_eq!(
+            export.warnings,
+            vec![ExportWarning::RenamedCurrency {
+                from: "btc".to_owned(),
+                to: "BTC".to_owned()
+            }]
+        )
+    }
+}
@@ -0,0 +1,286 @@
+use crate::formatter::format_amount;
+use crate::parser::ast::{
+    AutomatedAmount, AutomatedTransaction, JournalElement, Posting, Price, Query, QueryTerm,
+    Transaction, TransactionStatus,
+};
+
+use super::{Export, ExportWarning};
+
+/// Defines the flavours of the ledger syntax, which only differ in how descriptions, tags and
+/// automated transactions are written.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Dialect {
+    Ledger,
+    Hledger,
+}
+
+/// Exports the given journal to the ledger or hledger syntax.
+pub fn export(journal: &[JournalElement], dialect: &Dialect) -> Export {
+    let mut warnings = Vec::new();
+    let content = journal
+        .iter()
+        .filter_map(|element| match element {
+            JournalElement::Account(account) => Some(format!("account {}\n", account)),
+            JournalElement::Price(price) => Some(format_price(price)),
+            JournalElement::Transaction(transaction) => {
+                Some(format_transaction(transaction, dialect))
+            }
+            JournalElement::AutomatedTransaction(automated_transaction) => {
+                if *dialect == Dialect::Hledger {
+                    warnings.push(ExportWarning::AutomatedTransactionNeedsFlag(
+                        automated_transaction.clone(),
+                    ));
+                }
+
+                Some(format_automated_transaction(automated_transaction, dialect))
+            }
+            _ => None,
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    Export { content, warnings }
+}
+
+fn format_price(price: &Price) -> String {
+    format!(
+        "P {} {} {}\n",
+        price.date.date().format("%Y-%m-%d"),
+        price.currency,
+        format_amount(&price.amount)
+    )
+}
+
+fn format_transaction(transaction: &Transaction, dialect: &Dialect) -> String {
+    let status = match transaction.status {
+        TransactionStatus::Cleared => '*',
+        TransactionStatus::Pending => '!',
+    };
+    let mut lines = vec![format!(
+        "{} {} {}",
+        transaction.date.date().format("%Y-%m-%d"),
+        status,
+        transaction.payee
+    )];
+    let metadata = transaction
+        .metadata
+        .iter()
+        .map(|(key, value)| format!("{}: {}", key, value));
+
+    match dialect {
+        Dialect::Ledger => {
+            if !transaction.description.is_empty() {
+                lines[0].push_str(&format!("  ; {}", transaction.description));
+            }
+
+            if !transaction.tags.is_empty() {
+                lines.push(format!("    ; :{}:", transaction.tags.join(":")));
+            }
+
+            lines.extend(metadata.map(|metadata| format!("    ; {}", metadata)));
+        }
+        Dialect::Hledger => {
+            if !transaction.description.is_empty() {
+                lines[0].push_str(&format!(" | {}", transaction.description));
+            }
+
+            let tags: Vec<String> = transaction
+                .tags
+                .iter()
+                .map(|tag| format!("{}:", tag))
+                .chain(metadata)
+                .collect();
+
+            if !tags.is_empty() {
+                lines[0].push_str(&format!("  ; {}", tags.join(", ")));
+            }
+        }
+    }
+
+    lines.push(format_posting(&transaction.postings.0));
+    lines.push(format_posting(&transaction.postings.1));
+
+    format!("{}\n", lines.join("\n"))
+}
+
+fn format_posting(posting: &Posting) -> String {
+    let amount = match &posting.amount {
+        Some(amount) => format_amount(amount),
+        None => return format!("    {}", posting.account),
+    };
+    let lot = match &posting.lot {
+        Some(lot) => match lot.date {
+            Some(date) => format!(
+                " {{{}}} [{}]",
+                format_amount(&lot.cost),
+                date.format("%Y-%m-%d")
+            ),
+            None => format!(" {{{}}}", format_amount(&lot.cost)),
+        },
+        None => String::new(),
+    };
+    let price = match &posting.price {
+        Some(price) => format!(" @ {}", format_amount(price)),
+        None => String::new(),
+    };
+
+    format!("    {}  {}{}{}", posting.account, amount, lot, price)
+}
+
+fn format_automated_transaction(
+    automated_transaction: &AutomatedTransaction,
+    dialect: &Dialect,
+) -> String {
+    let query = match dialect {
+        Dialect::Ledger => format_ledger_query(&automated_transaction.query),
+        Dialect::Hledger => format_hledger_query(&automated_transaction.query),
+    };
+    let postings: Vec<String> = automated_transaction
+        .postings
+        .iter()
+        .map(|posting| {
+            let amount = match (&posting.amount, dialect) {
+                (AutomatedAmount::Fixed(amount), _) => format_amount(amount),
+                (AutomatedAmount::Multiplier(multiplier), Dialect::Ledger) => {
+                    multiplier.to_string()
+                }
+                (AutomatedAmount::Multiplier(multiplier), Dialect::Hledger) => {
+                    format!("*{}", multiplier)
+                }
+            };
+
+            format!("    {}  {}", posting.account, amount)
+        })
+        .collect();
+
+    format!("= {}\n{}\n", query, postings.join("\n"))
+}
+
+/// Translates the query to a ledger value expression that requires all of its terms.
+fn format_ledger_query(query: &Query) -> String {
+    query
+        .0
+        .iter()
+        .map(|term| match term {
+            QueryTerm::Account(account) => format!("account =~ /{}/", regex::escape(account)),
+            QueryTerm::Payee(payee) => format!("payee =~ /{}/", regex::escape(payee)),
+            QueryTerm::Description(description) => {
+                format!("note =~ /{}/", regex::escape(description))
+            }
+            QueryTerm::Tag(tag) => format!("has_tag(/^{}$/)", regex::escape(tag)),
+        })
+        .collect::<Vec<String>>()
+        .join(" and ")
+}
+
+/// Translates the query to a hledger query, which only differs in how descriptions are matched.
+fn format_hledger_query(query: &Query) -> String {
+    query
+        .0
+        .iter()
+        .map(|term| match term {
+            QueryTerm::Description(description) => format!("note:{}", description),
+            term => term.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{export, Dialect};
+
+    use crate::export::{Export, ExportWarning};
+    use crate::parser::ast::*;
+    use crate::parser::parse_journal;
+
+    fn element(content: &str) -> JournalElement {
+        parse_journal(content).unwrap().remove(0)
+    }
+
+    fn transaction() -> JournalElement {
+        element(
+            "2021-10-07 ! Tesco | Card payment ;food,weekly ;import-id:ABC\n\
+             expenses:food 25.30 EUR\nassets:bank\n",
+        )
+    }
+
+    fn automated_transaction() -> AutomatedTransaction {
+        match element("= expenses:food desc:weekly\nassets:savings 0.1\n") {
+            JournalElement::AutomatedTransaction(automated_transaction) => automated_transaction,
+            element => panic!("{:?} is not an automated transaction", element),
+        }
+    }
+
+    #[test]
+    fn exports_transaction_to_ledger() {
+        assert_eq!(
+            export(&[transaction()], &Dialect::Ledger).content,
+            "2021-10-07 ! Tesco  ; Card payment
+    ; :food:weekly:
+    ; import-id: ABC
+    expenses:food  25.30 EUR
+    assets:bank
+"
+        )
+    }
+
+    #[test]
+    fn exports_transaction_to_hledger() {
+        assert_eq!(
+            export(&[transaction()], &Dialect::Hledger).content,
+            "2021-10-07 ! Tesco | Card payment  ; food:, weekly:, import-id: ABC
+    expenses:food  25.30 EUR
+    assets:bank
+"
+        )
+    }
+
+    #[test]
+    fn exports_automated_transaction_to_ledger() {
+        assert_eq!(
+            export(
+                &[JournalElement::AutomatedTransaction(automated_transaction())],
+                &Dialect::Ledger
+            ),
+            Export {
+                content: "= account =~ /expenses:food/ and note =~ /weekly/
+    assets:savings  0.1
+"
+                .to_owned(),
+                warnings: vec![],
+            }
+        )
+    }
+
+    #[test]
+    fn exports_automated_transaction_to_hledger_with_warning() {
+        assert_eq!(
+            export(
+                &[JournalElement::AutomatedTransaction(automated_transaction())],
+                &Dialect::Hledger
+            ),
+            Export {
+                content: "= acct:expenses:food note:weekly
+    assets:savings  *0.1
+"
+                .to_owned(),
+                warnings: vec![ExportWarning::AutomatedTransactionNeedsFlag(
+                    automated_transaction()
+                )],
+            }
+        )
+    }
+
+    #[test]
+    fn exports_prices_and_accounts() {
+        assert_eq!(
+            export(
+                &parse_journal("account assets:bank\n\nP 2021-10-07 USD 0.85 EUR\n").unwrap(),
+                &Dialect::Ledger
+            )
+            .content,
+            "account assets:bank\n\nP 2021-10-07 USD 0.85 EUR\n"
+        )
+    }
+}
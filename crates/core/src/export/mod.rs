@@ -0,0 +1,211 @@
+use crate::journal::resolve_date;
+use crate::parser::ast::{
+    AutomatedTransaction, CurrencyCode, JournalElement, ParsedDate, Price, Transaction,
+};
+
+pub mod beancount;
+pub mod ledger;
+
+/// Defines the syntaxes that a journal can be exported to.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ExportFormat {
+    /// Ledger syntax. Descriptions are exported as a comment of the transaction since ledger only
+    /// has payees, tags as a `:tag:` comment and metadata as `key: value` comments. Queries of
+    /// automated transactions are translated to value expressions.
+    Ledger,
+    /// hledger syntax. Descriptions are exported as the note of the transaction, after a `|`, and
+    /// tags and metadata as tags in the comment of the transaction. Automated transactions are
+    /// kept, but hledger only applies them with `--auto`.
+    Hledger,
+    /// Beancount syntax. Accounts are capitalised and must belong to one of the five root accounts
+    /// of beancount, currencies are uppercased and metadata keys lowercased. Every account is
+    /// opened on the date of the first transaction and automated transactions are replaced by the
+    /// postings that they generate, since beancount does not support them.
+    Beancount,
+}
+
+/// Defines the constructs of the journal that could not be exported as they are.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, PartialEq, Clone)]
+pub enum ExportWarning {
+    /// Comments are dropped by the parser, so they can't be exported.
+    DroppedComments,
+    /// The journal was not expanded, so the journal in the path of the include directive was not
+    /// exported.
+    DroppedInclude(String),
+    /// The transaction omitted the year and no year was declared before it, so it was exported
+    /// with the default year.
+    PartialDateWithoutYear(Transaction),
+    /// hledger only applies automated transactions when given `--auto`.
+    AutomatedTransactionNeedsFlag(AutomatedTransaction),
+    /// The automated transaction was replaced by the postings it generates.
+    ExpandedAutomatedTransaction(AutomatedTransaction),
+    /// The transaction sells units of a lot at a price, so a posting to `Income:Gains` was added
+    /// to take the difference with their cost.
+    AddedGainsPosting(Transaction),
+    RenamedAccount {
+        from: String,
+        to: String,
+    },
+    RenamedCurrency {
+        from: CurrencyCode,
+        to: CurrencyCode,
+    },
+}
+
+/// Result of exporting a journal, with the content in the target syntax and the warnings found.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Export {
+    pub content: String,
+    pub warnings: Vec<ExportWarning>,
+}
+
+/// Exports the given journal to the given format. Partial dates are given the year declared
+/// before them, so every exported date is a full date.
+pub fn export(journal: Vec<JournalElement>, format: &ExportFormat) -> Export {
+    let mut warnings = Vec::new();
+    let journal = resolve_dates(journal, &mut warnings);
+
+    let export = match format {
+        ExportFormat::Ledger => ledger::export(&journal, &ledger::Dialect::Ledger),
+        ExportFormat::Hledger => ledger::export(&journal, &ledger::Dialect::Hledger),
+        ExportFormat::Beancount => beancount::export(&journal),
+    };
+
+    warnings.extend(export.warnings);
+
+    Export {
+        content: export.content,
+        warnings,
+    }
+}
+
+fn resolve_dates(
+    journal: Vec<JournalElement>,
+    warnings: &mut Vec<ExportWarning>,
+) -> Vec<JournalElement> {
+    let mut year = None;
+    let mut has_comments = false;
+    let mut elements = Vec::new();
+
+    for element in journal {
+        match element {
+            JournalElement::Year(journal_year) => year = Some(journal_year),
+            JournalElement::Comment => has_comments = true,
+            JournalElement::Include(path) => warnings.push(ExportWarning::DroppedInclude(path)),
+            JournalElement::Transaction(transaction) => {
+                let transaction = Transaction {
+                    date: resolve_date(transaction.date.clone(), year),
+                    ..transaction
+                };
+
+                if let ParsedDate::Partial(_) = transaction.date {
+                    warnings.push(ExportWarning::PartialDateWithoutYear(transaction.clone()));
+                }
+
+                elements.push(JournalElement::Transaction(transaction));
+            }
+            JournalElement::Price(price) => elements.push(JournalElement::Price(Price {
+                date: resolve_date(price.date.clone(), year),
+                ..price
+            })),
+            element => elements.push(element),
+        }
+    }
+
+    if has_comments {
+        warnings.insert(0, ExportWarning::DroppedComments);
+    }
+
+    elements
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use super::{export, ExportFormat, ExportWarning};
+
+    use crate::parser::ast::*;
+
+    fn transaction(date: ParsedDate) -> Transaction {
+        Transaction {
+            date,
+            postings: (
+                Posting {
+                    account: Account {
+                        name: "expenses".to_owned(),
+                        children: vec![],
+                    },
+                    amount: Some(Amount {
+                        quantity: 1.into(),
+                        currency: "EUR".to_owned(),
+                    }),
+                    lot: None,
+                    price: None,
+                },
+                Posting {
+                    account: Account {
+                        name: "assets".to_owned(),
+                        children: vec![],
+                    },
+                    amount: None,
+                    lot: None,
+                    price: None,
+                },
+            ),
+            ..Transaction::default()
+        }
+    }
+
+    #[test]
+    fn exports_partial_dates_with_declared_year() {
+        let journal = vec![
+            JournalElement::Year(2019),
+            JournalElement::Transaction(transaction(ParsedDate::Partial(
+                NaiveDate::from_ymd_opt(2021, 10, 7).unwrap(),
+            ))),
+        ];
+
+        let export = export(journal, &ExportFormat::Ledger);
+
+        assert!(export.content.starts_with("2019-10-07"));
+        assert!(export.warnings.is_empty());
+    }
+
+    #[test]
+    fn warns_about_partial_dates_without_year() {
+        let partial_transaction = transaction(ParsedDate::Partial(
+            NaiveDate::from_ymd_opt(2021, 10, 7).unwrap(),
+        ));
+
+        assert_eq!(
+            export(
+                vec![JournalElement::Transaction(partial_transaction.clone())],
+                &ExportFormat::Hledger
+            )
+            .warnings,
+            vec![ExportWarning::PartialDateWithoutYear(partial_transaction)]
+        )
+    }
+
+    #[test]
+    fn warns_about_dropped_comments() {
+        assert_eq!(
+            export(vec![JournalElement::Comment], &ExportFormat::Beancount).warnings,
+            vec![ExportWarning::DroppedComments]
+        )
+    }
+
+    #[test]
+    fn warns_about_dropped_includes() {
+        assert_eq!(
+            export(
+                vec![JournalElement::Include("prices.journal".to_owned())],
+                &ExportFormat::Ledger
+            )
+            .warnings,
+            vec![ExportWarning::DroppedInclude("prices.journal".to_owned())]
+        )
+    }
+}
@@ -0,0 +1,313 @@
+use crate::parser::ast::{
+    Amount, AutomatedAmount, AutomatedTransaction, JournalElement, ParsedDate, Posting, Price,
+    Transaction, TransactionStatus,
+};
+
+/// Formats the given journal elements in the journal syntax, separating them with an empty line.
+pub fn format_journal(journal: &[JournalElement]) -> String {
+    journal
+        .iter()
+        .map(format_element)
+        .filter(|element| !element.is_empty())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Formats the given journal element in the journal syntax, ending it with a line ending. Empty
+/// elements and comments, whose content is not kept by the parser, are formatted as an empty string.
+pub fn format_element(element: &JournalElement) -> String {
+    match element {
+        JournalElement::Empty | JournalElement::Comment => String::new(),
+        JournalElement::Account(account) => format!("account {}\n", account),
+        JournalElement::Year(year) => format!("y{}\n", year),
+        JournalElement::Transaction(transaction) => format_transaction(transaction),
+        JournalElement::AutomatedTransaction(automated_transaction) => {
+            format_automated_transaction(automated_transaction)
+        }
+        JournalElement::Price(price) => format_price(price),
+        JournalElement::Include(path) => format!("include {}\n", path),
+    }
+}
+
+/// Formats the given transaction in the journal syntax, ending it with a line ending. Characters
+/// that the parser does not accept in payees, descriptions, tags or metadata are removed so that
+/// the output can always be parsed back.
+pub fn format_transaction(transaction: &Transaction) -> String {
+    let mut header = format_date(&transaction.date);
+
+    if transaction.status == TransactionStatus::Pending {
+        header.push_str(" !");
+    }
+
+    let payee = sanitize(&transaction.payee, is_allowed_in_text);
+    let description = sanitize(&transaction.description, is_allowed_in_text);
+
+    if !payee.is_empty() {
+        header.push(' ');
+        header.push_str(&payee);
+
+        if !description.is_empty() {
+            header.push_str(" | ");
+            header.push_str(&description);
+        }
+    }
+
+    let tags: Vec<String> = transaction
+        .tags
+        .iter()
+        .map(|tag| sanitize(tag, |c| c.is_alphanumeric() || c == '-'))
+        .filter(|tag| !tag.is_empty())
+        .collect();
+
+    if !tags.is_empty() {
+        header.push_str(&format!(" ;{}", tags.join(",")));
+    }
+
+    let metadata: Vec<String> = transaction
+        .metadata
+        .iter()
+        .map(|(key, value)| {
+            (
+                sanitize(key, |c| c.is_alphanumeric() || c == '-'),
+                sanitize(value, |c| {
+                    c.is_alphanumeric() || c == '-' || c == '.' || c == '/' || c == '_'
+                }),
+            )
+        })
+        .filter(|(key, value)| !key.is_empty() && !value.is_empty())
+        .map(|(key, value)| format!("{}:{}", key, value))
+        .collect();
+
+    if !metadata.is_empty() {
+        header.push_str(&format!(" ;{}", metadata.join(",")));
+    }
+
+    format!(
+        "{}\n{}\n{}\n",
+        header,
+        format_posting(&transaction.postings.0),
+        format_posting(&transaction.postings.1)
+    )
+}
+
+/// Formats the given automated transaction in the journal syntax, ending it with a line ending.
+pub fn format_automated_transaction(automated_transaction: &AutomatedTransaction) -> String {
+    let postings: Vec<String> = automated_transaction
+        .postings
+        .iter()
+        .map(|posting| match &posting.amount {
+            AutomatedAmount::Fixed(amount) => {
+                format!("{} {}", posting.account, format_amount(amount))
+            }
+            AutomatedAmount::Multiplier(multiplier) => {
+                format!("{} {}", posting.account, multiplier)
+            }
+        })
+        .collect();
+
+    format!(
+        "= {}\n{}\n",
+        automated_transaction.query,
+        postings.join("\n")
+    )
+}
+
+/// Formats the given price declaration, ending it with a line ending.
+pub fn format_price(price: &Price) -> String {
+    format!(
+        "P {} {} {}\n",
+        format_date(&price.date),
+        price.currency,
+        format_amount(&price.amount)
+    )
+}
+
+/// Formats the given posting as the account followed by the amount, if any, and the lot and price
+/// of the amount.
+pub fn format_posting(posting: &Posting) -> String {
+    let mut formatted = match &posting.amount {
+        Some(amount) => format!("{} {}", posting.account, format_amount(amount)),
+        None => return posting.account.to_string(),
+    };
+
+    if let Some(lot) = &posting.lot {
+        formatted.push_str(&format!(" {{{}}}", format_amount(&lot.cost)));
+
+        if let Some(date) = lot.date {
+            formatted.push_str(&format!(" [{}]", date.format("%Y-%m-%d")));
+        }
+    }
+    if let Some(price) = &posting.price {
+        formatted.push_str(&format!(" @ {}", format_amount(price)));
+    }
+
+    formatted
+}
+
+/// Formats the given amount as the quantity followed by the currency.
+pub fn format_amount(amount: &Amount) -> String {
+    format!("{} {}", amount.quantity, amount.currency)
+}
+
+/// Formats the given date as year-month-day, omitting the year for partial dates.
+pub fn format_date(date: &ParsedDate) -> String {
+    match date {
+        ParsedDate::Full(date) => date.format("%Y-%m-%d").to_string(),
+        ParsedDate::Partial(date) => date.format("%m-%d").to_string(),
+    }
+}
+
+/// Returns whether the payee or description can be written as it is, since `format_transaction`
+/// removes the characters that the parser does not accept.
+pub fn is_writable_text(text: &str) -> bool {
+    text.chars().all(is_allowed_in_text)
+}
+
+fn is_allowed_in_text(c: char) -> bool {
+    c.is_alphanumeric() || c == ' '
+}
+
+fn sanitize(value: &str, is_allowed: impl Fn(char) -> bool) -> String {
+    value
+        .chars()
+        .filter(|&c| is_allowed(c))
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    use super::{format_journal, format_transaction};
+
+    use crate::parser::ast::*;
+    use crate::parser::parse_journal;
+
+    fn transaction() -> Transaction {
+        Transaction {
+            date: ParsedDate::Full(NaiveDate::from_ymd_opt(2021, 10, 7).unwrap()),
+            status: TransactionStatus::Pending,
+            payee: "Albert".to_owned(),
+            description: "Weekly groceries".to_owned(),
+            tags: vec!["food".to_owned(), "weekly".to_owned()],
+            metadata: vec![("import-id".to_owned(), "ABC123".to_owned())]
+                .into_iter()
+                .collect(),
+            postings: (
+                Posting {
+                    account: Account {
+                        name: "expenses".to_owned(),
+                        children: vec!["food".to_owned()],
+                    },
+                    amount: Some(Amount {
+                        quantity: dec!(10.50),
+                        currency: "EUR".to_owned(),
+                    }),
+                    lot: None,
+                    price: None,
+                },
+                Posting {
+                    account: Account {
+                        name: "assets".to_owned(),
+                        children: vec!["bank".to_owned()],
+                    },
+                    amount: None,
+                    lot: None,
+                    price: None,
+                },
+            ),
+            ..Transaction::default()
+        }
+    }
+
+    #[test]
+    fn formats_transaction_in_journal_syntax() {
+        assert_eq!(
+            format_transaction(&transaction()),
+            "2021-10-07 ! Albert | Weekly groceries ;food,weekly ;import-id:ABC123\nexpenses:food 10.50 EUR\nassets:bank\n"
+        )
+    }
+
+    #[test]
+    fn formats_transaction_without_optional_sections() {
+        let transaction = Transaction {
+            status: TransactionStatus::Cleared,
+            description: "".to_owned(),
+            tags: vec![],
+            metadata: Metadata::new(),
+            ..transaction()
+        };
+
+        assert_eq!(
+            format_transaction(&transaction),
+            "2021-10-07 Albert\nexpenses:food 10.50 EUR\nassets:bank\n"
+        )
+    }
+
+    #[test]
+    fn removes_characters_that_cannot_be_parsed() {
+        let transaction = Transaction {
+            payee: "Albert Heijn B.V.".to_owned(),
+            description: "Card #1234".to_owned(),
+            tags: vec!["food & drinks".to_owned()],
+            ..transaction()
+        };
+
+        assert_eq!(
+            format_transaction(&transaction),
+            "2021-10-07 ! Albert Heijn BV | Card 1234 ;fooddrinks ;import-id:ABC123\nexpenses:food 10.50 EUR\nassets:bank\n"
+        )
+    }
+
+    #[test]
+    fn formatted_transaction_can_be_parsed_back() {
+        assert_eq!(
+            parse_journal(&format_transaction(&transaction())),
+            Ok(vec![JournalElement::Transaction(transaction())])
+        )
+    }
+
+    #[test]
+    fn formats_lots_and_prices_of_postings() {
+        let content = "2022-03-01 Broker\nassets:broker -5 VWCE {95.20 EUR} [2021-10-07] @ 110 EUR\nassets:bank\n";
+        let journal = parse_journal(content).unwrap();
+
+        assert_eq!(format_journal(&journal), content);
+    }
+
+    #[test]
+    fn formatted_journal_can_be_parsed_back() {
+        let journal = vec![
+            JournalElement::Account(Account {
+                name: "assets".to_owned(),
+                children: vec!["bank".to_owned()],
+            }),
+            JournalElement::Year(2021),
+            JournalElement::Price(Price {
+                date: ParsedDate::Partial(NaiveDate::from_ymd_opt(2021, 10, 1).unwrap()),
+                currency: "USD".to_owned(),
+                amount: Amount {
+                    quantity: dec!(0.85),
+                    currency: "EUR".to_owned(),
+                },
+            }),
+            JournalElement::AutomatedTransaction(AutomatedTransaction {
+                query: Query(vec![QueryTerm::Account("expenses:food".to_owned())]),
+                postings: vec![AutomatedPosting {
+                    account: Account {
+                        name: "assets".to_owned(),
+                        children: vec!["savings".to_owned()],
+                    },
+                    amount: AutomatedAmount::Multiplier(dec!(0.1)),
+                }],
+            }),
+            JournalElement::Transaction(transaction()),
+        ];
+
+        assert_eq!(parse_journal(&format_journal(&journal)), Ok(journal))
+    }
+}
@@ -0,0 +1,172 @@
+use chrono::NaiveDate;
+
+use crate::parser::ast::Amount;
+
+use super::rules::ImportRules;
+use super::{parse_quantity, ImportError, StatementEntry};
+
+/// Reads the entries of a CSV statement using the columns and settings of the given rules. The
+/// date and amount columns, as well as the currency, are required.
+pub fn read(content: &str, rules: &ImportRules) -> Result<Vec<StatementEntry>, ImportError> {
+    let date_column = required(rules.columns.date, "date")?;
+    let amount_column = required(rules.columns.amount, "amount")?;
+    let currency = required(rules.currency.clone(), "currency")?;
+
+    ::csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(rules.separator as u8)
+        .from_reader(content.as_bytes())
+        .records()
+        .enumerate()
+        .skip(rules.skip)
+        .filter(|(_, record)| {
+            record
+                .as_ref()
+                .map(|record| record.iter().any(|field| !field.trim().is_empty()))
+                .unwrap_or(true)
+        })
+        .map(|(index, record)| {
+            let row = index + 1;
+            let record =
+                record.map_err(|err| ImportError::NonParsableStatement(err.to_string()))?;
+            let field = |column: usize| {
+                column
+                    .checked_sub(1)
+                    .and_then(|index| record.get(index))
+                    .map(|field| field.trim().to_owned())
+                    .ok_or(ImportError::MissingColumn { row, column })
+            };
+            let optional_field = |column: Option<usize>| match column {
+                Some(column) => field(column),
+                None => Ok("".to_owned()),
+            };
+
+            let date = field(date_column)?;
+            let amount = field(amount_column)?;
+
+            Ok(StatementEntry {
+                date: NaiveDate::parse_from_str(&date, &rules.date_format)
+                    .map_err(|_| ImportError::InvalidDate { row, value: date })?,
+                payee: optional_field(rules.columns.payee)?,
+                description: optional_field(rules.columns.description)?,
+                amount: Amount {
+                    quantity: parse_quantity(&amount, rules.decimal_separator)
+                        .ok_or(ImportError::InvalidAmount { row, value: amount })?,
+                    currency: currency.clone(),
+                },
+                id: None,
+            })
+        })
+        .collect()
+}
+
+fn required<T>(value: Option<T>, rule: &str) -> Result<T, ImportError> {
+    value.ok_or_else(|| ImportError::MissingRule(rule.to_owned()))
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    use super::read;
+
+    use crate::import::rules::{self, ImportRules};
+    use crate::import::{ImportError, StatementEntry};
+    use crate::parser::ast::Amount;
+
+    fn rules() -> ImportRules {
+        rules::parse(
+            "skip 1\ndate-format %d/%m/%Y\ndate 1\npayee 2\ndescription 3\namount 4\ncurrency GBP\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn reads_entries_skipping_header_and_empty_rows() {
+        assert_eq!(
+            read(
+                "Date,Payee,Reference,Amount\n07/10/2021,TESCO STORES,Card payment,-25.30\n\n08/10/2021,ACME,Salary,\"1,250.00\"\n",
+                &rules()
+            ),
+            Ok(vec![
+                StatementEntry {
+                    date: NaiveDate::from_ymd_opt(2021, 10, 7).unwrap(),
+                    payee: "TESCO STORES".to_owned(),
+                    description: "Card payment".to_owned(),
+                    amount: Amount {
+                        quantity: dec!(-25.30),
+                        currency: "GBP".to_owned()
+                    },
+                    id: None
+                },
+                StatementEntry {
+                    date: NaiveDate::from_ymd_opt(2021, 10, 8).unwrap(),
+                    payee: "ACME".to_owned(),
+                    description: "Salary".to_owned(),
+                    amount: Amount {
+                        quantity: dec!(1250.00),
+                        currency: "GBP".to_owned()
+                    },
+                    id: None
+                }
+            ])
+        )
+    }
+
+    #[test]
+    fn reads_amounts_with_decimal_comma() {
+        let rules = ImportRules {
+            separator: ';',
+            decimal_separator: ',',
+            ..rules()
+        };
+
+        assert_eq!(
+            read("header\n07/10/2021;ALBERT;Groceries;-1.025,50\n", &rules).map(|entries| entries
+                .into_iter()
+                .map(|entry| entry.amount.quantity)
+                .collect::<Vec<_>>()),
+            Ok(vec![dec!(-1025.50)])
+        )
+    }
+
+    #[test]
+    fn fails_with_invalid_date() {
+        assert_eq!(
+            read("header\n2021-10-07,ACME,Salary,100\n", &rules()),
+            Err(ImportError::InvalidDate {
+                row: 2,
+                value: "2021-10-07".to_owned()
+            })
+        )
+    }
+
+    #[test]
+    fn fails_with_invalid_amount() {
+        assert_eq!(
+            read("header\n07/10/2021,ACME,Salary,abc\n", &rules()),
+            Err(ImportError::InvalidAmount {
+                row: 2,
+                value: "abc".to_owned()
+            })
+        )
+    }
+
+    #[test]
+    fn fails_with_missing_column() {
+        assert_eq!(
+            read("header\n07/10/2021,ACME\n", &rules()),
+            Err(ImportError::MissingColumn { row: 2, column: 4 })
+        )
+    }
+
+    #[test]
+    fn fails_without_required_rules() {
+        assert_eq!(
+            read("header\n", &ImportRules::default()),
+            Err(ImportError::MissingRule("date".to_owned()))
+        )
+    }
+}
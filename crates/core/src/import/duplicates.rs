@@ -0,0 +1,333 @@
+use crate::journal::resolved_postings;
+use crate::parser::ast::{Posting, Transaction, TransactionStatus};
+
+use super::{import_id, IMPORT_ID_KEY};
+
+/// Tag added to the transactions that are imported even though they look like duplicates.
+pub const DUPLICATE_TAG: &str = "duplicate";
+
+/// Defines how close an imported transaction has to be to an existing one to be a duplicate.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DuplicateOptions {
+    /// Maximum number of days between the dates of both transactions.
+    pub date_window: i64,
+    /// Minimum similarity between both payees, from 0 (anything) to 1 (identical).
+    pub payee_similarity: f64,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        Self {
+            date_window: 3,
+            payee_similarity: 0.8,
+        }
+    }
+}
+
+/// Defines why an imported transaction was considered a duplicate.
+#[derive(Debug, PartialEq, Clone)]
+pub enum DuplicateReason {
+    SameImportId,
+    SimilarTransaction,
+}
+
+/// Represents an imported transaction that duplicates one that already exists in the journal.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Duplicate {
+    pub transaction: Transaction,
+    pub existing: Transaction,
+    pub reason: DuplicateReason,
+}
+
+/// Result of comparing the imported transactions to the existing ones.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Deduplication {
+    pub unique: Vec<Transaction>,
+    pub duplicates: Vec<Duplicate>,
+}
+
+/// Splits the imported transactions in the ones that are new and the ones that duplicate an
+/// existing transaction. A transaction is a duplicate if it has the same import identifier as an
+/// existing one, or if both happen within the date window, share a posting with the same account
+/// and amount and have similar payees. Each existing transaction can only be matched by one of
+/// the imported transactions this way, so repeated purchases in the statement are kept.
+pub fn find(
+    transactions: Vec<Transaction>,
+    existing: &[Transaction],
+    options: &DuplicateOptions,
+) -> Deduplication {
+    let mut matched = vec![false; existing.len()];
+
+    transactions
+        .into_iter()
+        .fold(Deduplication::default(), |mut result, transaction| {
+            let same_import_id = transaction
+                .metadata
+                .get(IMPORT_ID_KEY)
+                .map(|id| import_id(id))
+                .and_then(|id| {
+                    existing.iter().position(|other| {
+                        other
+                            .metadata
+                            .get(IMPORT_ID_KEY)
+                            .is_some_and(|other_id| import_id(other_id) == id)
+                    })
+                })
+                .map(|index| (index, DuplicateReason::SameImportId));
+            let similar = || {
+                existing
+                    .iter()
+                    .enumerate()
+                    .position(|(index, other)| {
+                        !matched[index] && are_similar(&transaction, other, options)
+                    })
+                    .map(|index| (index, DuplicateReason::SimilarTransaction))
+            };
+
+            match same_import_id.or_else(similar) {
+                Some((index, reason)) => {
+                    matched[index] = true;
+                    result.duplicates.push(Duplicate {
+                        transaction,
+                        existing: existing[index].clone(),
+                        reason,
+                    });
+                }
+                None => result.unique.push(transaction),
+            }
+
+            result
+        })
+}
+
+/// Marks the given transaction as pending and tags it with `DUPLICATE_TAG` so that it can be
+/// reviewed after importing it.
+pub fn flag(transaction: Transaction) -> Transaction {
+    let mut tags = transaction.tags;
+    if !tags.iter().any(|tag| tag == DUPLICATE_TAG) {
+        tags.push(DUPLICATE_TAG.to_owned());
+    }
+
+    Transaction {
+        status: TransactionStatus::Pending,
+        tags,
+        ..transaction
+    }
+}
+
+fn are_similar(transaction: &Transaction, other: &Transaction, options: &DuplicateOptions) -> bool {
+    let days_between = (transaction.date.date() - other.date.date())
+        .num_days()
+        .abs();
+
+    days_between <= options.date_window
+        && share_posting(transaction, other)
+        && payee_similarity(&transaction.payee, &other.payee) >= options.payee_similarity
+}
+
+fn share_posting(transaction: &Transaction, other: &Transaction) -> bool {
+    let (first_posting, second_posting) = resolved_postings(transaction);
+    let (other_first_posting, other_second_posting) = resolved_postings(other);
+    let same_posting = |posting: &Posting, other_posting: &Posting| {
+        posting.amount.is_some()
+            && posting.amount == other_posting.amount
+            && posting.account.to_string() == other_posting.account.to_string()
+    };
+
+    [&first_posting, &second_posting].iter().any(|posting| {
+        same_posting(posting, &other_first_posting) || same_posting(posting, &other_second_posting)
+    })
+}
+
+/// Compares both payees ignoring case and anything that is not alphanumeric, considering them
+/// identical when one contains the other since banks tend to add references to the payee.
+fn payee_similarity(payee: &str, other: &str) -> f64 {
+    let payee = normalise(payee);
+    let other = normalise(other);
+
+    if payee.is_empty() || other.is_empty() {
+        return if payee == other { 1.0 } else { 0.0 };
+    }
+
+    if payee.contains(&other) || other.contains(&payee) {
+        1.0
+    } else {
+        strsim::normalized_levenshtein(&payee, &other)
+    }
+}
+
+fn normalise(payee: &str) -> String {
+    payee
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    use super::{find, flag, Deduplication, Duplicate, DuplicateOptions, DuplicateReason};
+
+    use crate::journal::resolve;
+    use crate::parser::ast::*;
+    use crate::parser::parse_journal;
+
+    fn parsed(content: &str) -> Transaction {
+        resolve(parse_journal(content).unwrap()).remove(0)
+    }
+
+    fn transaction(day: u32, payee: &str, quantity: Decimal) -> Transaction {
+        parsed(&format!(
+            "2021-10-{:02} {}\nexpenses:unknown {} EUR\nassets:bank\n",
+            day, payee, quantity
+        ))
+    }
+
+    fn existing(day: u32, payee: &str, quantity: Decimal) -> Transaction {
+        parsed(&format!(
+            "2021-10-{:02} {}\nexpenses:groceries\nassets:bank {} EUR\n",
+            day, payee, -quantity
+        ))
+    }
+
+    fn with_import_id(transaction: Transaction, id: &str) -> Transaction {
+        Transaction {
+            metadata: vec![("import-id".to_owned(), id.to_owned())]
+                .into_iter()
+                .collect(),
+            ..transaction
+        }
+    }
+
+    #[test]
+    fn finds_duplicates_with_same_import_id() {
+        let imported = with_import_id(transaction(7, "Tesco", dec!(10)), "ABC");
+        let existing = vec![with_import_id(existing(20, "Other", dec!(50)), "ABC")];
+
+        assert_eq!(
+            find(
+                vec![imported.clone()],
+                &existing,
+                &DuplicateOptions::default()
+            ),
+            Deduplication {
+                unique: vec![],
+                duplicates: vec![Duplicate {
+                    transaction: imported,
+                    existing: existing[0].clone(),
+                    reason: DuplicateReason::SameImportId,
+                }],
+            }
+        )
+    }
+
+    #[test]
+    fn compares_import_ids_as_they_are_written_to_the_journal() {
+        let imported = with_import_id(transaction(7, "Tesco", dec!(10)), "ABC+123 X");
+        let existing = vec![with_import_id(existing(20, "Other", dec!(50)), "ABC-123-X")];
+
+        assert_eq!(
+            find(vec![imported], &existing, &DuplicateOptions::default()).duplicates[0].reason,
+            DuplicateReason::SameImportId
+        )
+    }
+
+    #[test]
+    fn finds_similar_transactions_within_date_window() {
+        let imported = transaction(7, "TESCO STORES 3297", dec!(25.30));
+        let existing = vec![existing(5, "Tesco Stores", dec!(25.30))];
+
+        assert_eq!(
+            find(
+                vec![imported.clone()],
+                &existing,
+                &DuplicateOptions::default()
+            ),
+            Deduplication {
+                unique: vec![],
+                duplicates: vec![Duplicate {
+                    transaction: imported,
+                    existing: existing[0].clone(),
+                    reason: DuplicateReason::SimilarTransaction,
+                }],
+            }
+        )
+    }
+
+    #[test]
+    fn keeps_transactions_outside_date_window() {
+        let imported = transaction(10, "Tesco", dec!(25.30));
+        let existing = vec![existing(5, "Tesco", dec!(25.30))];
+
+        assert_eq!(
+            find(
+                vec![imported.clone()],
+                &existing,
+                &DuplicateOptions::default()
+            )
+            .unique,
+            vec![imported]
+        )
+    }
+
+    #[test]
+    fn keeps_transactions_with_different_amount() {
+        let imported = transaction(5, "Tesco", dec!(25.31));
+        let existing = vec![existing(5, "Tesco", dec!(25.30))];
+
+        assert_eq!(
+            find(
+                vec![imported.clone()],
+                &existing,
+                &DuplicateOptions::default()
+            )
+            .unique,
+            vec![imported]
+        )
+    }
+
+    #[test]
+    fn keeps_transactions_with_different_payee() {
+        let imported = transaction(5, "Albert Heijn", dec!(25.30));
+        let existing = vec![existing(5, "Tesco", dec!(25.30))];
+
+        assert_eq!(
+            find(
+                vec![imported.clone()],
+                &existing,
+                &DuplicateOptions::default()
+            )
+            .unique,
+            vec![imported]
+        )
+    }
+
+    #[test]
+    fn matches_each_existing_transaction_only_once() {
+        let imported = vec![
+            transaction(5, "Coffee", dec!(2.5)),
+            transaction(5, "Coffee", dec!(2.5)),
+        ];
+        let existing = vec![existing(5, "Coffee", dec!(2.5))];
+
+        let result = find(imported, &existing, &DuplicateOptions::default());
+
+        assert_eq!(result.unique.len(), 1);
+        assert_eq!(result.duplicates.len(), 1);
+    }
+
+    #[test]
+    fn flag_marks_transaction_as_pending_and_tags_it() {
+        assert_eq!(
+            flag(transaction(5, "Tesco", dec!(1))),
+            Transaction {
+                status: TransactionStatus::Pending,
+                tags: vec!["duplicate".to_owned()],
+                ..transaction(5, "Tesco", dec!(1))
+            }
+        )
+    }
+}
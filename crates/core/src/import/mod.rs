@@ -0,0 +1,180 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::error::ParseError;
+use crate::parser::ast::{
+    Amount, Description, ParsedDate, Payee, Posting, Transaction, TransactionStatus,
+};
+
+use rules::ImportRules;
+
+pub mod csv;
+pub mod duplicates;
+pub mod ofx;
+pub mod qif;
+pub mod rules;
+
+/// Metadata key that holds the identifier that the bank gave to an imported transaction.
+pub const IMPORT_ID_KEY: &str = "import-id";
+
+/// Returns the identifier given by the bank as it is kept in the metadata, with every character that
+/// metadata values can't contain replaced by a hyphen, so that it is written to the journal as it
+/// is and can be compared with the identifiers read from it.
+pub fn import_id(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '.' || c == '/' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Defines all the different errors that can happen while importing a bank statement.
+#[derive(Debug, PartialEq)]
+pub enum ImportError {
+    NonParsableRules(ParseError),
+    MissingRule(String),
+    NonParsableStatement(String),
+    MissingColumn { row: usize, column: usize },
+    MissingField { row: usize, field: String },
+    InvalidDate { row: usize, value: String },
+    InvalidAmount { row: usize, value: String },
+}
+
+/// Represents a single entry of a bank statement before it's categorised into a transaction.
+#[derive(Debug, PartialEq, Clone)]
+pub struct StatementEntry {
+    pub date: NaiveDate,
+    pub payee: Payee,
+    pub description: Description,
+    pub amount: Amount,
+    /// Identifier given by the bank to the entry, if the statement format provides one.
+    pub id: Option<String>,
+}
+
+/// Categorises the given statement entries with the rules, returning a transaction for each entry.
+/// The first posting of each transaction goes to the account given by the matching rules and the
+/// second one to the account of the statement, which has no amount since it balances the first.
+/// The identifier of the entry, if any, is kept in the metadata under `IMPORT_ID_KEY` as returned
+/// by `import_id`.
+pub fn to_transactions(
+    entries: Vec<StatementEntry>,
+    rules: &ImportRules,
+) -> Result<Vec<Transaction>, ImportError> {
+    let account = rules
+        .account
+        .clone()
+        .ok_or_else(|| ImportError::MissingRule("account".to_owned()))?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            let categorisation = rules::categorise(rules, &entry);
+
+            Transaction {
+                date: ParsedDate::Full(entry.date),
+                status: TransactionStatus::Cleared,
+                payee: categorisation.payee,
+                description: categorisation.description,
+                tags: categorisation.tags,
+                metadata: entry
+                    .id
+                    .map(|id| (IMPORT_ID_KEY.to_owned(), import_id(&id)))
+                    .into_iter()
+                    .collect(),
+                postings: (
+                    Posting {
+                        account: categorisation.account,
+                        amount: Some(Amount {
+                            quantity: -entry.amount.quantity,
+                            currency: entry.amount.currency,
+                        }),
+                        lot: None,
+                        price: None,
+                    },
+                    Posting {
+                        account: account.clone(),
+                        amount: None,
+                        lot: None,
+                        price: None,
+                    },
+                ),
+                ..Transaction::default()
+            }
+        })
+        .collect())
+}
+
+/// Parses a quantity that uses the given decimal separator, ignoring any other separator used to
+/// group thousands.
+fn parse_quantity(value: &str, decimal_separator: char) -> Option<Decimal> {
+    let quantity: String = value
+        .chars()
+        .filter(|&c| c.is_ascii_digit() || c == '-' || c == decimal_separator)
+        .map(|c| if c == decimal_separator { '.' } else { c })
+        .collect();
+
+    Decimal::from_str(&quantity).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    use super::rules::{self, ImportRules};
+    use super::{import_id, to_transactions, ImportError, StatementEntry};
+
+    use crate::journal::resolve;
+    use crate::parser::ast::*;
+    use crate::parser::parse_journal;
+
+    fn entry() -> StatementEntry {
+        StatementEntry {
+            date: NaiveDate::from_ymd_opt(2021, 10, 7).unwrap(),
+            payee: "TESCO STORES".to_owned(),
+            description: "Card payment".to_owned(),
+            amount: Amount {
+                quantity: dec!(-25.30),
+                currency: "GBP".to_owned(),
+            },
+            id: Some("ABC123".to_owned()),
+        }
+    }
+
+    #[test]
+    fn to_transactions_moves_opposite_amount_to_categorised_account() {
+        let rules = rules::parse("account assets:bank\n").unwrap();
+
+        assert_eq!(
+            to_transactions(vec![entry()], &rules),
+            Ok(resolve(
+                parse_journal(
+                    "2021-10-07 TESCO STORES | Card payment ;import-id:ABC123\n\
+                     expenses:unknown 25.30 GBP\nassets:bank\n"
+                )
+                .unwrap()
+            ))
+        )
+    }
+
+    #[test]
+    fn to_transactions_fails_without_account() {
+        assert_eq!(
+            to_transactions(vec![entry()], &ImportRules::default()),
+            Err(ImportError::MissingRule("account".to_owned()))
+        )
+    }
+
+    #[test]
+    fn keeps_import_ids_that_can_be_written_as_metadata() {
+        assert_eq!(import_id("ABC+123 X"), "ABC-123-X");
+        assert_eq!(import_id("2021/10_07.1-é"), "2021/10_07.1-é");
+        assert_eq!(import_id(&import_id("ABC+123 X")), "ABC-123-X");
+    }
+}
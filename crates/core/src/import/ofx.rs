@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use nom::{
+    bytes::complete::{take_till, take_till1},
+    character::complete::char,
+    error::{ErrorKind, ParseError},
+    multi::many0,
+    sequence::{preceded, separated_pair},
+    IResult,
+};
+
+use crate::parser::ast::Amount;
+
+use super::rules::ImportRules;
+use super::{parse_quantity, ImportError, StatementEntry};
+
+/// Reads the transactions of an OFX statement, supporting both the SGML (1.x) and the XML (2.x)
+/// flavours. The currency is taken from the statement and falls back to the one in the rules, and
+/// the FITID of each transaction is kept as the identifier of the entry.
+pub fn read(content: &str, rules: &ImportRules) -> Result<Vec<StatementEntry>, ImportError> {
+    let elements = match parse_elements::<(&str, ErrorKind)>(content) {
+        Ok(("", elements)) => elements,
+        _ => {
+            return Err(ImportError::NonParsableStatement(
+                "The statement is not a valid OFX file".to_owned(),
+            ))
+        }
+    };
+
+    let mut currency = rules.currency.clone();
+    let mut current_transaction: Option<HashMap<String, String>> = None;
+    let mut transactions = Vec::new();
+
+    for (name, value) in elements {
+        match name.trim().to_uppercase().as_str() {
+            "CURDEF" => currency = Some(value.trim().to_owned()),
+            "STMTTRN" => current_transaction = Some(HashMap::new()),
+            "/STMTTRN" => transactions.extend(current_transaction.take()),
+            name if !name.starts_with('/') => {
+                if let Some(fields) = current_transaction.as_mut() {
+                    fields.insert(name.to_owned(), decode_entities(value.trim()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let currency = currency.ok_or_else(|| ImportError::MissingRule("currency".to_owned()))?;
+
+    transactions
+        .into_iter()
+        .enumerate()
+        .map(|(index, fields)| to_entry(index + 1, fields, &currency))
+        .collect()
+}
+
+fn to_entry(
+    row: usize,
+    mut fields: HashMap<String, String>,
+    currency: &str,
+) -> Result<StatementEntry, ImportError> {
+    let mut required = |field: &str| {
+        fields
+            .remove(field)
+            .ok_or_else(|| ImportError::MissingField {
+                row,
+                field: field.to_owned(),
+            })
+    };
+
+    let date = required("DTPOSTED")?;
+    let amount = required("TRNAMT")?;
+
+    Ok(StatementEntry {
+        date: date
+            .get(0..8)
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+            .ok_or_else(|| ImportError::InvalidDate {
+                row,
+                value: date.clone(),
+            })?,
+        amount: Amount {
+            quantity: parse_quantity(&amount.replace(',', "."), '.')
+                .ok_or(ImportError::InvalidAmount { row, value: amount })?,
+            currency: currency.to_owned(),
+        },
+        payee: fields
+            .remove("NAME")
+            .or_else(|| fields.remove("PAYEE"))
+            .unwrap_or_default(),
+        description: fields.remove("MEMO").unwrap_or_default(),
+        id: fields.remove("FITID"),
+    })
+}
+
+/// Splits the content in a list of elements with their name and the text that follows them until
+/// the next element, which works for both closed and unclosed elements. Any header before the first
+/// element is ignored.
+fn parse_elements<'a, E: ParseError<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, Vec<(&'a str, &'a str)>, E> {
+    preceded(
+        take_till(|c| c == '<'),
+        many0(preceded(
+            char('<'),
+            separated_pair(take_till1(|c| c == '>'), char('>'), take_till(|c| c == '<')),
+        )),
+    )(input)
+}
+
+fn decode_entities(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    use super::read;
+
+    use crate::import::rules::ImportRules;
+    use crate::import::{ImportError, StatementEntry};
+    use crate::parser::ast::Amount;
+
+    const SGML_STATEMENT: &str = "OFXHEADER:100
+DATA:OFXSGML
+VERSION:102
+
+<OFX>
+<BANKMSGSRSV1>
+<STMTTRNRS>
+<STMTRS>
+<CURDEF>EUR
+<BANKTRANLIST>
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>20211007120000.000[+1:CET]
+<TRNAMT>-25.30
+<FITID>2021100701
+<NAME>ALBERT HEIJN
+<MEMO>Groceries
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>CREDIT
+<DTPOSTED>20211008
+<TRNAMT>1250.00
+<FITID>2021100801
+<NAME>ACME
+</STMTTRN>
+</BANKTRANLIST>
+</STMTRS>
+</STMTTRNRS>
+</BANKMSGSRSV1>
+</OFX>
+";
+
+    const XML_STATEMENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<?OFX OFXHEADER="200" VERSION="220"?>
+<OFX>
+  <BANKMSGSRSV1>
+    <STMTTRNRS>
+      <STMTRS>
+        <CURDEF>EUR</CURDEF>
+        <BANKTRANLIST>
+          <STMTTRN>
+            <TRNTYPE>DEBIT</TRNTYPE>
+            <DTPOSTED>20211007</DTPOSTED>
+            <TRNAMT>-25.30</TRNAMT>
+            <FITID>2021100701</FITID>
+            <NAME>ALBERT HEIJN &amp; CO</NAME>
+            <MEMO>Groceries</MEMO>
+          </STMTTRN>
+        </BANKTRANLIST>
+      </STMTRS>
+    </STMTTRNRS>
+  </BANKMSGSRSV1>
+</OFX>
+"#;
+
+    fn entry(payee: &str) -> StatementEntry {
+        StatementEntry {
+            date: NaiveDate::from_ymd_opt(2021, 10, 7).unwrap(),
+            payee: payee.to_owned(),
+            description: "Groceries".to_owned(),
+            amount: Amount {
+                quantity: dec!(-25.30),
+                currency: "EUR".to_owned(),
+            },
+            id: Some("2021100701".to_owned()),
+        }
+    }
+
+    #[test]
+    fn reads_sgml_statement() {
+        assert_eq!(
+            read(SGML_STATEMENT, &ImportRules::default()),
+            Ok(vec![
+                entry("ALBERT HEIJN"),
+                StatementEntry {
+                    date: NaiveDate::from_ymd_opt(2021, 10, 8).unwrap(),
+                    payee: "ACME".to_owned(),
+                    description: "".to_owned(),
+                    amount: Amount {
+                        quantity: dec!(1250.00),
+                        currency: "EUR".to_owned(),
+                    },
+                    id: Some("2021100801".to_owned()),
+                }
+            ])
+        )
+    }
+
+    #[test]
+    fn reads_xml_statement() {
+        assert_eq!(
+            read(XML_STATEMENT, &ImportRules::default()),
+            Ok(vec![entry("ALBERT HEIJN & CO")])
+        )
+    }
+
+    #[test]
+    fn fails_when_transaction_has_no_amount() {
+        assert_eq!(
+            read(
+                "<OFX><CURDEF>EUR<STMTTRN><DTPOSTED>20211007</STMTTRN></OFX>",
+                &ImportRules::default()
+            ),
+            Err(ImportError::MissingField {
+                row: 1,
+                field: "TRNAMT".to_owned()
+            })
+        )
+    }
+
+    #[test]
+    fn fails_with_invalid_date() {
+        assert_eq!(
+            read(
+                "<OFX><CURDEF>EUR<STMTTRN><DTPOSTED>2021<TRNAMT>1</STMTTRN></OFX>",
+                &ImportRules::default()
+            ),
+            Err(ImportError::InvalidDate {
+                row: 1,
+                value: "2021".to_owned()
+            })
+        )
+    }
+
+    #[test]
+    fn fails_when_content_is_not_ofx() {
+        assert!(matches!(
+            read("<OFX><STMTTRN", &ImportRules::default()),
+            Err(ImportError::NonParsableStatement(_))
+        ))
+    }
+}
@@ -0,0 +1,168 @@
+use chrono::NaiveDate;
+
+use crate::parser::ast::Amount;
+
+use super::rules::ImportRules;
+use super::{parse_quantity, ImportError, StatementEntry};
+
+/// Date formats tried after the one in the rules, since most banks export US dates in QIF files.
+/// Two digit years go first since four digit years would otherwise be read as the year 20.
+const DEFAULT_DATE_FORMATS: [&str; 2] = ["%m/%d/%y", "%m/%d/%Y"];
+
+/// Reads the transactions of a QIF statement. Each transaction is a list of lines that start with
+/// a field code (`D` for the date, `T` or `U` for the amount, `P` for the payee and `M` for the
+/// memo) and ends with a `^`. QIF files do not define a currency or identifiers for transactions,
+/// so the currency of the rules is required.
+pub fn read(content: &str, rules: &ImportRules) -> Result<Vec<StatementEntry>, ImportError> {
+    let currency = rules
+        .currency
+        .clone()
+        .ok_or_else(|| ImportError::MissingRule("currency".to_owned()))?;
+
+    content
+        .split('^')
+        .map(|record| {
+            record
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('!'))
+                .collect::<Vec<&str>>()
+        })
+        .filter(|lines| !lines.is_empty())
+        .enumerate()
+        .map(|(index, lines)| to_entry(index + 1, &lines, rules, &currency))
+        .collect()
+}
+
+fn to_entry(
+    row: usize,
+    lines: &[&str],
+    rules: &ImportRules,
+    currency: &str,
+) -> Result<StatementEntry, ImportError> {
+    let field = |codes: &[char]| {
+        codes.iter().find_map(|&code| {
+            lines
+                .iter()
+                .find(|line| line.starts_with(code))
+                .map(|line| line[code.len_utf8()..].trim().to_owned())
+        })
+    };
+    let required = |codes: &[char], name: &str| {
+        field(codes).ok_or_else(|| ImportError::MissingField {
+            row,
+            field: name.to_owned(),
+        })
+    };
+
+    let date = required(&['D'], "D")?;
+    let amount = required(&['T', 'U'], "T")?;
+
+    Ok(StatementEntry {
+        date: parse_date(&date, &rules.date_format).ok_or_else(|| ImportError::InvalidDate {
+            row,
+            value: date.clone(),
+        })?,
+        amount: Amount {
+            quantity: parse_quantity(&amount, rules.decimal_separator)
+                .ok_or(ImportError::InvalidAmount { row, value: amount })?,
+            currency: currency.to_owned(),
+        },
+        payee: field(&['P']).unwrap_or_default(),
+        description: field(&['M']).unwrap_or_default(),
+        id: None,
+    })
+}
+
+/// Parses the date with the format of the rules or the default QIF formats. Apostrophes, which
+/// some banks use to separate the year, are treated as slashes.
+fn parse_date(value: &str, rules_format: &str) -> Option<NaiveDate> {
+    let value = value.replace('\'', "/").replace(' ', "");
+
+    std::iter::once(rules_format)
+        .chain(DEFAULT_DATE_FORMATS.iter().copied())
+        .find_map(|format| NaiveDate::parse_from_str(&value, format).ok())
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    use super::read;
+
+    use crate::import::rules::ImportRules;
+    use crate::import::{ImportError, StatementEntry};
+    use crate::parser::ast::Amount;
+
+    fn rules() -> ImportRules {
+        ImportRules {
+            currency: Some("USD".to_owned()),
+            ..ImportRules::default()
+        }
+    }
+
+    #[test]
+    fn reads_statement() {
+        assert_eq!(
+            read(
+                "!Type:Bank\nD10/07/2021\nT-25.30\nPWALMART\nMGroceries\n^\nD10/08'21\nU1,250.00\nPACME\n^\n",
+                &rules()
+            ),
+            Ok(vec![
+                StatementEntry {
+                    date: NaiveDate::from_ymd_opt(2021, 10, 7).unwrap(),
+                    payee: "WALMART".to_owned(),
+                    description: "Groceries".to_owned(),
+                    amount: Amount {
+                        quantity: dec!(-25.30),
+                        currency: "USD".to_owned(),
+                    },
+                    id: None,
+                },
+                StatementEntry {
+                    date: NaiveDate::from_ymd_opt(2021, 10, 8).unwrap(),
+                    payee: "ACME".to_owned(),
+                    description: "".to_owned(),
+                    amount: Amount {
+                        quantity: dec!(1250.00),
+                        currency: "USD".to_owned(),
+                    },
+                    id: None,
+                }
+            ])
+        )
+    }
+
+    #[test]
+    fn reads_dates_with_format_of_the_rules() {
+        let rules = ImportRules {
+            date_format: "%d.%m.%Y".to_owned(),
+            ..rules()
+        };
+
+        assert_eq!(
+            read("D07.10.2021\nT-1\n^", &rules).map(|entries| entries[0].date),
+            Ok(NaiveDate::from_ymd_opt(2021, 10, 7).unwrap())
+        )
+    }
+
+    #[test]
+    fn fails_when_transaction_has_no_date() {
+        assert_eq!(
+            read("T-25.30\nPWALMART\n^", &rules()),
+            Err(ImportError::MissingField {
+                row: 1,
+                field: "D".to_owned()
+            })
+        )
+    }
+
+    #[test]
+    fn fails_without_currency() {
+        assert_eq!(
+            read("D10/07/2021\nT-25.30\n^", &ImportRules::default()),
+            Err(ImportError::MissingRule("currency".to_owned()))
+        )
+    }
+}
@@ -0,0 +1,579 @@
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_until, take_while1},
+    character::complete::{anychar, char, digit1, multispace0, space0, space1},
+    combinator::{eof, map, map_res, opt},
+    error::{context, ContextError, FromExternalError, ParseError, VerboseError},
+    multi::{many_till, separated_list1},
+    sequence::{delimited, preceded, separated_pair, terminated, tuple},
+    Finish, IResult,
+};
+use regex::Regex;
+
+use crate::parser::ast::{Account, CurrencyCode, Description, Payee, Tag};
+use crate::parser::{account, comment, parse_error};
+
+use super::{ImportError, StatementEntry};
+
+/// Defines the columns of a CSV statement that contain each of the fields of an entry. Columns
+/// start at 1.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Columns {
+    pub date: Option<usize>,
+    pub payee: Option<usize>,
+    pub description: Option<usize>,
+    pub amount: Option<usize>,
+}
+
+/// Defines the field of a statement entry that a rule matches against.
+#[derive(Debug, PartialEq, Clone)]
+pub enum RuleField {
+    Payee,
+    Description,
+}
+
+/// Defines the changes that a rule applies to an entry when it matches.
+#[derive(Debug, PartialEq, Clone)]
+pub enum RuleAction {
+    Account(Account),
+    Tag(Tag),
+    Payee(Payee),
+    Description(Description),
+}
+
+/// Represents a rule that applies its actions to every entry whose field matches the pattern.
+#[derive(Debug, Clone)]
+pub struct CategorisationRule {
+    pub field: RuleField,
+    pub pattern: Regex,
+    pub actions: Vec<RuleAction>,
+}
+
+/// Represents a replacement of the text that matches the pattern in the payee and description of
+/// every categorised entry, like the characters that can't be written to a journal.
+#[derive(Debug, Clone)]
+pub struct Replacement {
+    pub pattern: Regex,
+    pub text: String,
+}
+
+/// Defines how the entries of a statement are read and categorised into transactions.
+#[derive(Debug, Clone)]
+pub struct ImportRules {
+    pub columns: Columns,
+    pub skip: usize,
+    pub separator: char,
+    pub date_format: String,
+    pub decimal_separator: char,
+    pub currency: Option<CurrencyCode>,
+    /// Account of the statement, which receives the second posting of every transaction.
+    pub account: Option<Account>,
+    /// Account used for entries that no rule categorises. Defaults to `expenses:unknown` or
+    /// `income:unknown` depending on the sign of the amount.
+    pub default_account: Option<Account>,
+    pub rules: Vec<CategorisationRule>,
+    /// Replacements applied in order once the rules have been applied.
+    pub replacements: Vec<Replacement>,
+}
+
+impl Default for ImportRules {
+    fn default() -> Self {
+        Self {
+            columns: Columns::default(),
+            skip: 0,
+            separator: ',',
+            date_format: "%Y-%m-%d".to_owned(),
+            decimal_separator: '.',
+            currency: None,
+            account: None,
+            default_account: None,
+            rules: Vec::new(),
+            replacements: Vec::new(),
+        }
+    }
+}
+
+/// Result of applying the categorisation rules to a statement entry.
+#[derive(Debug, PartialEq)]
+pub struct Categorisation {
+    pub account: Account,
+    pub payee: Payee,
+    pub description: Description,
+    pub tags: Vec<Tag>,
+}
+
+enum Directive {
+    Empty,
+    Skip(usize),
+    Separator(char),
+    DateFormat(String),
+    DecimalSeparator(char),
+    DateColumn(usize),
+    PayeeColumn(usize),
+    DescriptionColumn(usize),
+    AmountColumn(usize),
+    Currency(CurrencyCode),
+    Account(Account),
+    DefaultAccount(Account),
+    Rule(CategorisationRule),
+    Replacement(Replacement),
+}
+
+/// Attempts to parse a rules file. Each line contains either a comment or one directive:
+///
+/// - `skip 1`, `separator ;`, `date-format %d/%m/%Y` and `decimal-separator ,` define how the
+///   CSV is read.
+/// - `date 1`, `payee 2`, `description 3` and `amount 4` map columns to entry fields.
+/// - `currency EUR`, `account assets:bank` and `default-account expenses:unknown` set defaults.
+/// - `if payee matches /TESCO/ then account expenses:groceries, tag food` categorises entries.
+/// - `replace /\./ with " "` replaces text in the categorised payees and descriptions, removing it
+///   if no text is given. The text only needs quotes to keep its spaces.
+pub fn parse(content: &str) -> Result<ImportRules, ImportError> {
+    many_till(
+        preceded(
+            multispace0,
+            alt((
+                map(comment::parse, |_| Directive::Empty),
+                terminated(parse_directive, space0),
+            )),
+        ),
+        preceded(multispace0, eof),
+    )(content)
+    .finish()
+    .map(|(_, (directives, _))| {
+        directives
+            .into_iter()
+            .fold(ImportRules::default(), apply_directive)
+    })
+    .map_err(|err: VerboseError<&str>| ImportError::NonParsableRules(parse_error(content, err)))
+}
+
+/// Applies all the rules that match the entry in order, so that the actions of later rules
+/// override the account, payee and description set by earlier ones. Tags are accumulated. The
+/// replacements are applied to the resulting payee and description.
+pub fn categorise(rules: &ImportRules, entry: &StatementEntry) -> Categorisation {
+    let default_account = rules.default_account.clone().unwrap_or_else(|| Account {
+        name: if entry.amount.quantity.is_sign_negative() {
+            "expenses".to_owned()
+        } else {
+            "income".to_owned()
+        },
+        children: vec!["unknown".to_owned()],
+    });
+
+    let initial = Categorisation {
+        account: default_account,
+        payee: entry.payee.clone(),
+        description: entry.description.clone(),
+        tags: Vec::new(),
+    };
+
+    let categorisation = rules
+        .rules
+        .iter()
+        .filter(|rule| match rule.field {
+            RuleField::Payee => rule.pattern.is_match(&entry.payee),
+            RuleField::Description => rule.pattern.is_match(&entry.description),
+        })
+        .flat_map(|rule| rule.actions.iter())
+        .fold(initial, |categorisation, action| match action {
+            RuleAction::Account(account) => Categorisation {
+                account: account.clone(),
+                ..categorisation
+            },
+            RuleAction::Payee(payee) => Categorisation {
+                payee: payee.clone(),
+                ..categorisation
+            },
+            RuleAction::Description(description) => Categorisation {
+                description: description.clone(),
+                ..categorisation
+            },
+            RuleAction::Tag(tag) => {
+                let mut tags = categorisation.tags;
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+                Categorisation {
+                    tags,
+                    ..categorisation
+                }
+            }
+        });
+
+    rules
+        .replacements
+        .iter()
+        .fold(categorisation, |categorisation, replacement| {
+            let replace = |text: &str| {
+                replacement
+                    .pattern
+                    .replace_all(text, replacement.text.as_str())
+                    .into_owned()
+            };
+
+            Categorisation {
+                payee: replace(&categorisation.payee),
+                description: replace(&categorisation.description),
+                ..categorisation
+            }
+        })
+}
+
+fn apply_directive(rules: ImportRules, directive: Directive) -> ImportRules {
+    let columns = rules.columns.clone();
+
+    match directive {
+        Directive::Empty => rules,
+        Directive::Skip(skip) => ImportRules { skip, ..rules },
+        Directive::Separator(separator) => ImportRules { separator, ..rules },
+        Directive::DateFormat(date_format) => ImportRules {
+            date_format,
+            ..rules
+        },
+        Directive::DecimalSeparator(decimal_separator) => ImportRules {
+            decimal_separator,
+            ..rules
+        },
+        Directive::DateColumn(column) => ImportRules {
+            columns: Columns {
+                date: Some(column),
+                ..columns
+            },
+            ..rules
+        },
+        Directive::PayeeColumn(column) => ImportRules {
+            columns: Columns {
+                payee: Some(column),
+                ..columns
+            },
+            ..rules
+        },
+        Directive::DescriptionColumn(column) => ImportRules {
+            columns: Columns {
+                description: Some(column),
+                ..columns
+            },
+            ..rules
+        },
+        Directive::AmountColumn(column) => ImportRules {
+            columns: Columns {
+                amount: Some(column),
+                ..columns
+            },
+            ..rules
+        },
+        Directive::Currency(currency) => ImportRules {
+            currency: Some(currency),
+            ..rules
+        },
+        Directive::Account(account) => ImportRules {
+            account: Some(account),
+            ..rules
+        },
+        Directive::DefaultAccount(account) => ImportRules {
+            default_account: Some(account),
+            ..rules
+        },
+        Directive::Rule(rule) => {
+            let mut categorisation_rules = rules.rules;
+            categorisation_rules.push(rule);
+            ImportRules {
+                rules: categorisation_rules,
+                ..rules
+            }
+        }
+        Directive::Replacement(replacement) => {
+            let mut replacements = rules.replacements;
+            replacements.push(replacement);
+            ImportRules {
+                replacements,
+                ..rules
+            }
+        }
+    }
+}
+
+fn parse_directive<
+    'a,
+    E: ParseError<&'a str>
+        + ContextError<&'a str>
+        + FromExternalError<&'a str, std::num::ParseIntError>
+        + FromExternalError<&'a str, regex::Error>,
+>(
+    input: &'a str,
+) -> IResult<&'a str, Directive, E> {
+    context(
+        "directive",
+        alt((
+            map(keyword_value("skip", column), Directive::Skip),
+            map(keyword_value("separator", anychar), Directive::Separator),
+            map(
+                keyword_value("date-format", rest_of_line),
+                Directive::DateFormat,
+            ),
+            map(
+                keyword_value("decimal-separator", anychar),
+                Directive::DecimalSeparator,
+            ),
+            map(keyword_value("date", column), Directive::DateColumn),
+            map(keyword_value("payee", column), Directive::PayeeColumn),
+            map(
+                keyword_value("description", column),
+                Directive::DescriptionColumn,
+            ),
+            map(keyword_value("amount", column), Directive::AmountColumn),
+            map(
+                keyword_value("currency", take_while1(|c: char| c.is_alphabetic())),
+                |currency: &str| Directive::Currency(currency.to_owned()),
+            ),
+            map(keyword_value("account", account::parse), Directive::Account),
+            map(
+                keyword_value("default-account", account::parse),
+                Directive::DefaultAccount,
+            ),
+            map(parse_rule, Directive::Rule),
+            map(parse_replacement, Directive::Replacement),
+        )),
+    )(input)
+}
+
+fn parse_rule<
+    'a,
+    E: ParseError<&'a str> + ContextError<&'a str> + FromExternalError<&'a str, regex::Error>,
+>(
+    input: &'a str,
+) -> IResult<&'a str, CategorisationRule, E> {
+    context(
+        "rule",
+        map(
+            tuple((
+                preceded(
+                    tuple((tag("if"), space1)),
+                    alt((
+                        map(tag("payee"), |_| RuleField::Payee),
+                        map(tag("description"), |_| RuleField::Description),
+                    )),
+                ),
+                preceded(tuple((space1, tag("matches"), space1)), pattern),
+                preceded(
+                    tuple((space1, tag("then"), space1)),
+                    separated_list1(tuple((space0, char(','), space0)), parse_action),
+                ),
+            )),
+            |(field, pattern, actions)| CategorisationRule {
+                field,
+                pattern,
+                actions,
+            },
+        ),
+    )(input)
+}
+
+fn parse_replacement<
+    'a,
+    E: ParseError<&'a str> + ContextError<&'a str> + FromExternalError<&'a str, regex::Error>,
+>(
+    input: &'a str,
+) -> IResult<&'a str, Replacement, E> {
+    context(
+        "replacement",
+        map(
+            tuple((
+                preceded(tuple((tag("replace"), space1)), pattern),
+                preceded(
+                    tuple((space1, tag("with"))),
+                    opt(preceded(
+                        space1,
+                        alt((
+                            map(delimited(char('"'), take_until("\""), char('"')), String::from),
+                            rest_of_line,
+                        )),
+                    )),
+                ),
+            )),
+            |(pattern, text)| Replacement {
+                pattern,
+                text: text.unwrap_or_default(),
+            },
+        ),
+    )(input)
+}
+
+fn pattern<'a, E: ParseError<&'a str> + FromExternalError<&'a str, regex::Error>>(
+    input: &'a str,
+) -> IResult<&'a str, Regex, E> {
+    map_res(delimited(char('/'), take_until("/"), char('/')), Regex::new)(input)
+}
+
+fn parse_action<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, RuleAction, E> {
+    context(
+        "action",
+        alt((
+            map(
+                keyword_value("account", account::parse),
+                RuleAction::Account,
+            ),
+            map(
+                keyword_value(
+                    "tag",
+                    take_while1(|c: char| c.is_alphanumeric() || c == '-'),
+                ),
+                |tag: &str| RuleAction::Tag(tag.to_owned()),
+            ),
+            map(keyword_value("payee", action_text), RuleAction::Payee),
+            map(
+                keyword_value("description", action_text),
+                RuleAction::Description,
+            ),
+        )),
+    )(input)
+}
+
+fn keyword_value<'a, O, E: ParseError<&'a str>>(
+    keyword: &'static str,
+    value: impl FnMut(&'a str) -> IResult<&'a str, O, E>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O, E> {
+    map(separated_pair(tag(keyword), space1, value), |(_, value)| {
+        value
+    })
+}
+
+fn column<'a, E: ParseError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>>(
+    input: &'a str,
+) -> IResult<&'a str, usize, E> {
+    map_res(digit1, |s: &str| s.parse::<usize>())(input)
+}
+
+fn rest_of_line<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, String, E> {
+    map(take_while1(|c: char| c != '\n' && c != '\r'), |s: &str| {
+        s.trim().to_owned()
+    })(input)
+}
+
+fn action_text<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, String, E> {
+    map(
+        take_while1(|c: char| c != ',' && c != '\n' && c != '\r'),
+        |s: &str| s.trim().to_owned(),
+    )(input)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    use super::{categorise, parse, Columns};
+
+    use crate::import::{ImportError, StatementEntry};
+    use crate::parser::ast::{Account, Amount};
+
+    fn name(account: Option<Account>) -> Option<String> {
+        account.as_ref().map(Account::to_string)
+    }
+
+    fn entry(payee: &str, quantity: rust_decimal::Decimal) -> StatementEntry {
+        StatementEntry {
+            date: NaiveDate::from_ymd_opt(2021, 10, 7).unwrap(),
+            payee: payee.to_owned(),
+            description: "Card payment".to_owned(),
+            amount: Amount {
+                quantity,
+                currency: "EUR".to_owned(),
+            },
+            id: None,
+        }
+    }
+
+    #[test]
+    fn parses_settings_and_columns() {
+        let rules = parse(
+            "// Bank export\nskip 1\nseparator ;\ndate-format %d/%m/%Y\ndecimal-separator ,\ndate 1\npayee 3\ndescription 4\namount 5\ncurrency EUR\naccount assets:bank\ndefault-account expenses:other\n",
+        )
+        .unwrap();
+
+        assert_eq!(rules.skip, 1);
+        assert_eq!(rules.separator, ';');
+        assert_eq!(rules.date_format, "%d/%m/%Y");
+        assert_eq!(rules.decimal_separator, ',');
+        assert_eq!(
+            rules.columns,
+            Columns {
+                date: Some(1),
+                payee: Some(3),
+                description: Some(4),
+                amount: Some(5),
+            }
+        );
+        assert_eq!(rules.currency, Some("EUR".to_owned()));
+        assert_eq!(name(rules.account), Some("assets:bank".to_owned()));
+        assert_eq!(
+            name(rules.default_account),
+            Some("expenses:other".to_owned())
+        );
+    }
+
+    #[test]
+    fn fails_to_parse_unknown_directives() {
+        assert!(matches!(
+            parse("account assets:bank\nunknown 1\n"),
+            Err(ImportError::NonParsableRules(_))
+        ))
+    }
+
+    #[test]
+    fn fails_to_parse_invalid_patterns() {
+        assert!(matches!(
+            parse("if payee matches /TESCO(/ then tag food\n"),
+            Err(ImportError::NonParsableRules(_))
+        ))
+    }
+
+    #[test]
+    fn categorises_entries_with_matching_rules() {
+        let rules = parse(
+            "if payee matches /TESCO/ then account expenses:groceries, tag food\nif description matches /(?i)card/ then tag card, payee Tesco\n",
+        )
+        .unwrap();
+
+        let categorisation = categorise(&rules, &entry("TESCO STORES 3297", dec!(-10)));
+
+        assert_eq!(categorisation.account.to_string(), "expenses:groceries");
+        assert_eq!(categorisation.payee, "Tesco");
+        assert_eq!(categorisation.description, "Card payment");
+        assert_eq!(categorisation.tags, vec!["food", "card"]);
+    }
+
+    #[test]
+    fn categorises_entries_without_matching_rules_by_amount_sign() {
+        let rules = parse("if payee matches /TESCO/ then account expenses:groceries\n").unwrap();
+
+        assert_eq!(
+            categorise(&rules, &entry("ACME", dec!(-10)))
+                .account
+                .to_string(),
+            "expenses:unknown"
+        );
+        assert_eq!(
+            categorise(&rules, &entry("ACME", dec!(1000)))
+                .account
+                .to_string(),
+            "income:unknown"
+        );
+    }
+
+    #[test]
+    fn replaces_text_in_categorised_payees_and_descriptions() {
+        let rules = parse(
+            "if payee matches /AMAZON/ then description Order no. 123\nreplace /\\./ with \" \"\nreplace / COM/ with\nreplace /no/ with number\n",
+        )
+        .unwrap();
+
+        let categorisation = categorise(&rules, &entry("AMAZON.COM", dec!(-10)));
+
+        assert_eq!(categorisation.payee, "AMAZON");
+        assert_eq!(categorisation.description, "Order number  123");
+    }
+}
@@ -1,19 +1,40 @@
-use std::{io, fs};
+use std::fs;
+use std::io::Write;
 
-/// Different errors that can happen while reading a file.
-pub enum FileError {
-    NotFound,
-    Unknown
-}
+use crate::error::IoError;
 
-/// Attempts to open and read the content of the given file path. Returns a `FileError` if there
+/// Attempts to open and read the content of the given file path. Returns an `IoError` if there
 /// was something wrong while reading the file.
-pub fn read_content(path: &str) -> Result<String, FileError> {
-    fs::read_to_string(path)
-        .map_err(|err: io::Error| {
-            match err.kind() {
-                io::ErrorKind::NotFound => FileError::NotFound,
-                _ => FileError::Unknown
-            }
+pub fn read_content(path: &str) -> Result<String, IoError> {
+    fs::read_to_string(path).map_err(|err| IoError::new(path, err))
+}
+
+/// Attempts to append the given content at the end of the file in the given path. Returns a
+/// `IoError` if the file does not exist or there was something wrong while writing to it.
+pub fn append_content(path: &str, content: &str) -> Result<(), IoError> {
+    fs::OpenOptions::new()
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|err| IoError::new(path, err))
+}
+
+/// Attempts to replace the content of the file in the given path. The content is first written to
+/// a temporary file next to it that is then renamed, so the file is never left half written.
+/// Returns an `IoError` if there was something wrong while writing the file.
+pub fn replace_content(path: &str, content: &str) -> Result<(), IoError> {
+    let temporary_path = format!("{}.tmp", path);
+
+    fs::write(&temporary_path, content)
+        .and_then(|_| fs::rename(&temporary_path, path))
+        .map_err(|err| {
+            let _ = fs::remove_file(&temporary_path);
+            IoError::new(path, err)
         })
-}
\ No newline at end of file
+}
+
+/// Attempts to create the directory in the given path together with its missing parents, doing
+/// nothing if it already exists. Returns an `IoError` if the directory could not be created.
+pub fn create_directory(path: &str) -> Result<(), IoError> {
+    fs::create_dir_all(path).map_err(|err| IoError::new(path, err))
+}
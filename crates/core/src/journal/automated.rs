@@ -0,0 +1,180 @@
+use crate::parser::ast::{Amount, AutomatedAmount, AutomatedTransaction, Posting, Transaction};
+use crate::query::{matches_posting, matches_transaction};
+
+use super::resolved_postings;
+
+/// Expands the given automated transactions, adding their postings to the `automated_postings` of
+/// every transaction that contains a posting matching their query. Postings are added once per
+/// matching posting, and multipliers are applied to the amount of the posting that matched. The
+/// amount of a generated posting is left unset when it is too large to be computed.
+pub fn expand(
+    transactions: Vec<Transaction>,
+    automated_transactions: &[AutomatedTransaction],
+) -> Vec<Transaction> {
+    transactions
+        .into_iter()
+        .map(|transaction| {
+            let automated_postings = automated_transactions
+                .iter()
+                .flat_map(|automated_transaction| {
+                    generated_postings(&transaction, automated_transaction)
+                })
+                .collect();
+
+            Transaction {
+                automated_postings,
+                ..transaction
+            }
+        })
+        .collect()
+}
+
+/// Returns the automated transactions whose query does not match any of the given transactions.
+pub fn unmatched<'a>(
+    transactions: &[Transaction],
+    automated_transactions: &'a [AutomatedTransaction],
+) -> Vec<&'a AutomatedTransaction> {
+    automated_transactions
+        .iter()
+        .filter(|automated_transaction| {
+            !transactions
+                .iter()
+                .any(|transaction| matches_transaction(&automated_transaction.query, transaction))
+        })
+        .collect()
+}
+
+fn generated_postings(
+    transaction: &Transaction,
+    automated_transaction: &AutomatedTransaction,
+) -> Vec<Posting> {
+    let (first_posting, second_posting) = resolved_postings(transaction);
+
+    vec![first_posting, second_posting]
+        .into_iter()
+        .filter(|posting| matches_posting(&automated_transaction.query, transaction, posting))
+        .flat_map(|matched_posting| {
+            automated_transaction
+                .postings
+                .iter()
+                .map(move |automated_posting| Posting {
+                    account: automated_posting.account.clone(),
+                    amount: match &automated_posting.amount {
+                        AutomatedAmount::Fixed(amount) => Some(amount.clone()),
+                        AutomatedAmount::Multiplier(multiplier) => {
+                            matched_posting.amount.as_ref().and_then(|amount| {
+                                Some(Amount {
+                                    quantity: amount.quantity.checked_mul(*multiplier)?.normalize(),
+                                    currency: amount.currency.clone(),
+                                })
+                            })
+                        }
+                    },
+                    lot: None,
+                    price: None,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{expand, unmatched};
+
+    use crate::formatter::format_posting;
+    use crate::parser::ast::*;
+    use crate::parser::parse_journal;
+
+    const FOOD: &str = "2021-10-07 Tesco\nexpenses:food 10 EUR\nassets:bank\n";
+
+    /// Returns the transactions and the automated transactions of the journal.
+    fn parse(content: &str) -> (Vec<Transaction>, Vec<AutomatedTransaction>) {
+        let mut transactions = vec![];
+        let mut automated_transactions = vec![];
+
+        for element in parse_journal(content).unwrap() {
+            match element {
+                JournalElement::Transaction(transaction) => transactions.push(transaction),
+                JournalElement::AutomatedTransaction(automated_transaction) => {
+                    automated_transactions.push(automated_transaction)
+                }
+                _ => {}
+            }
+        }
+
+        (transactions, automated_transactions)
+    }
+
+    fn tax(query: &str) -> String {
+        format!("= {}\nassets:tax 0.21\nliabilities:vat -0.21\n\n", query)
+    }
+
+    fn automated_postings(transactions: &[Transaction]) -> Vec<String> {
+        transactions[0]
+            .automated_postings
+            .iter()
+            .map(format_posting)
+            .collect()
+    }
+
+    #[test]
+    fn expand_adds_postings_with_multiplied_amounts_to_matching_transactions() {
+        let (transactions, automated_transactions) = parse(&(tax("expenses:food") + FOOD));
+
+        let expanded = expand(transactions.clone(), &automated_transactions);
+
+        assert_eq!(
+            automated_postings(&expanded),
+            vec!["assets:tax 2.1 EUR", "liabilities:vat -2.1 EUR"]
+        );
+        assert_eq!(
+            Transaction {
+                automated_postings: vec![],
+                ..expanded[0].clone()
+            },
+            transactions[0]
+        )
+    }
+
+    #[test]
+    fn expand_uses_opposite_amount_for_matched_postings_without_amount() {
+        let (transactions, automated_transactions) = parse(&(tax("assets:bank") + FOOD));
+
+        assert_eq!(
+            automated_postings(&expand(transactions, &automated_transactions))[0],
+            "assets:tax -2.1 EUR"
+        )
+    }
+
+    #[test]
+    fn expand_adds_fixed_amounts_as_they_are() {
+        let (transactions, automated_transactions) =
+            parse(&("= food\nexpenses:fees 1 CZK\n\n".to_owned() + FOOD));
+
+        assert_eq!(
+            automated_postings(&expand(transactions, &automated_transactions)),
+            vec!["expenses:fees 1 CZK"]
+        )
+    }
+
+    #[test]
+    fn expand_leaves_non_matching_transactions_untouched() {
+        let (transactions, automated_transactions) = parse(&(tax("income") + FOOD));
+
+        assert_eq!(
+            expand(transactions.clone(), &automated_transactions),
+            transactions
+        )
+    }
+
+    #[test]
+    fn unmatched_returns_automated_transactions_that_match_no_transaction() {
+        let (transactions, automated_transactions) =
+            parse(&(tax("expenses:food") + &tax("income") + FOOD));
+
+        assert_eq!(
+            unmatched(&transactions, &automated_transactions),
+            vec![&automated_transactions[1]]
+        )
+    }
+}
@@ -1,17 +1,23 @@
-use crate::conversion::convert_to;
-use crate::parser::ast::{CurrencyCode, Transaction};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
 
-use crate::journal::{CheckError, UnbalancedTransaction};
+use crate::conversion::{checked_convert_to, Prices};
+use crate::error::OverflowError;
+use crate::parser::ast::{Amount, CurrencyCode, Transaction};
+
+use crate::journal::{resolved_postings, CheckError, UnbalancedTransaction};
 
 /// Checks that all the given transactions are contain at least one value and that they are balanced,
-/// which means that the sum of all the specified quantities equals 0.
+/// which means that the sum of the weights of their postings equals 0.
 ///
 /// If any transaction is found to be unbalanced or without at least one value defined, the function
 /// returns a CheckError::UnbalancedTransaction with the list of transactions that were found,
-/// otherwise it returns the given list of transactions.
+/// otherwise it returns the given list of transactions. Transactions whose amounts are too large to
+/// be added are returned in a CheckError::OverflowingTransactions.
 pub fn check_transactions<'t>(
     transactions: &'t [Transaction],
     base_currency: CurrencyCode,
+    prices: &Prices,
 ) -> Result<&'t [Transaction], CheckError> {
     let invalid_transactions = transactions_without_value(transactions);
 
@@ -21,64 +27,75 @@ pub fn check_transactions<'t>(
         ));
     }
 
-    let unbalanced_transactions = unbalanced_transactions(transactions, &base_currency);
+    let mut overflowing_transactions = vec![];
+    let mut unbalanced_transactions = vec![];
 
-    if unbalanced_transactions.is_empty() {
-        Ok(transactions)
-    } else {
+    for transaction in transactions {
+        match difference(transaction, &base_currency, prices) {
+            Ok(difference) if difference.is_zero() => {}
+            Ok(difference) => unbalanced_transactions.push(UnbalancedTransaction {
+                transaction: transaction.clone(),
+                difference,
+            }),
+            Err(OverflowError) => overflowing_transactions.push(transaction.clone()),
+        }
+    }
+
+    if !overflowing_transactions.is_empty() {
+        Err(CheckError::OverflowingTransactions(
+            overflowing_transactions,
+        ))
+    } else if !unbalanced_transactions.is_empty() {
         Err(CheckError::UnbalancedTransactions(unbalanced_transactions))
+    } else {
+        Ok(transactions)
     }
 }
 
 fn transactions_without_value(transactions: &[Transaction]) -> Vec<&Transaction> {
     transactions
-        .into_iter()
+        .iter()
         .filter(|&transaction| {
             transaction.postings.0.amount.is_none() && transaction.postings.1.amount.is_none()
         })
         .collect()
 }
 
-fn unbalanced_transactions(
-    transactions: &[Transaction],
+fn difference(
+    transaction: &Transaction,
     base_currency: &CurrencyCode,
-) -> Vec<UnbalancedTransaction> {
-    transactions
-        .into_iter()
-        .filter_map(|transaction| unbalanced_transaction(transaction, base_currency))
-        .collect()
+    prices: &Prices,
+) -> Result<Decimal, OverflowError> {
+    // Transactions are guaranteed to have at least one value after passing by the previous validation,
+    // so a posting without a value takes the opposite of the other one. Postings are added by their
+    // weight, so that lots balance at their cost, and converted to the base currency to be able to
+    // add them. The postings of automated transactions are added too, so rules have to balance. A
+    // posting is only left without a value when it was too large to be computed while resolving.
+    let (first_posting, second_posting) = resolved_postings(transaction);
+    let date = transaction.date.date();
+
+    [first_posting, second_posting]
+        .iter()
+        .chain(transaction.automated_postings.iter())
+        .try_fold(Decimal::ZERO, |total, posting| {
+            let weight = posting.weight()?.ok_or(OverflowError)?;
+            let quantity = base_quantity(base_currency, &weight, prices, date)?;
+
+            total.checked_add(quantity).ok_or(OverflowError)
+        })
+        .map(|total| total.abs())
 }
 
-fn unbalanced_transaction(
-    transaction: &Transaction,
+/// Returns the quantity of the amount in the base currency with the prices of the journal known at
+/// the date. Amounts without a price in the base currency keep their quantity.
+fn base_quantity(
     base_currency: &CurrencyCode,
-) -> Option<UnbalancedTransaction> {
-    // Transactions are guaranteed to have at least one value after passing by the previous validation
-    // so attempt to fetch at least one amount if the posting does not have a value.
-    let first_amount = transaction.postings.0.amount.as_ref().or(transaction
-        .postings
-        .1
-        .amount
-        .as_ref());
-    let second_amount = transaction.postings.1.amount.as_ref().or(transaction
-        .postings
-        .0
-        .amount
-        .as_ref());
-
-    // Convert to the base currency to be able to compare them.
-    let first_amount = convert_to(base_currency, &first_amount.unwrap());
-    let second_amount = convert_to(base_currency, &second_amount.unwrap());
-
-    if first_amount.quantity == second_amount.quantity {
-        None
-    } else {
-        let difference = first_amount.quantity - second_amount.quantity;
-        Some(UnbalancedTransaction {
-            transaction: transaction.clone(),
-            difference: difference.abs(),
-        })
-    }
+    amount: &Amount,
+    prices: &Prices,
+    date: NaiveDate,
+) -> Result<Decimal, OverflowError> {
+    checked_convert_to(base_currency, amount, prices, date)
+        .map(|converted| converted.map_or(amount.quantity, |converted| converted.quantity))
 }
 
 #[cfg(test)]
@@ -86,8 +103,10 @@ mod test {
     use rust_decimal::Decimal;
     use rust_decimal_macros::dec;
 
+    use crate::conversion::Prices;
     use crate::journal::*;
     use crate::parser::ast::*;
+    use crate::parser::parse_journal;
 
     use super::check_transactions;
 
@@ -100,6 +119,8 @@ mod test {
                         quantity,
                         currency: "EUR".to_owned(),
                     }),
+                    lot: None,
+                    price: None,
                 },
                 Posting::default(),
             ),
@@ -116,13 +137,17 @@ mod test {
                         quantity,
                         currency: "EUR".to_owned(),
                     }),
+                    lot: None,
+                    price: None,
                 },
                 Posting {
                     account: Account::default(),
                     amount: Some(Amount {
-                        quantity: quantity + dec!(10.0),
+                        quantity: -(quantity + dec!(10.0)),
                         currency: "EUR".to_owned(),
                     }),
+                    lot: None,
+                    price: None,
                 },
             ),
             ..Transaction::default()
@@ -142,13 +167,17 @@ mod test {
                         quantity,
                         currency: first_currency,
                     }),
+                    lot: None,
+                    price: None,
                 },
                 Posting {
                     account: Account::default(),
                     amount: Some(Amount {
-                        quantity: quantity,
+                        quantity: -quantity,
                         currency: second_currency,
                     }),
+                    lot: None,
+                    price: None,
                 },
             ),
             ..Transaction::default()
@@ -161,6 +190,8 @@ mod test {
                 Posting {
                     account: Account::default(),
                     amount: None,
+                    lot: None,
+                    price: None,
                 },
                 Posting::default(),
             ),
@@ -176,7 +207,7 @@ mod test {
         ];
 
         assert_eq!(
-            check_transactions(&valid_transactions, "CZK".to_owned()),
+            check_transactions(&valid_transactions, "CZK".to_owned(), &Prices::default()),
             Ok(valid_transactions.as_slice())
         )
     }
@@ -190,7 +221,7 @@ mod test {
         ];
 
         assert_eq!(
-            check_transactions(&valid_transactions, "CZK".to_owned()),
+            check_transactions(&valid_transactions, "CZK".to_owned(), &Prices::default()),
             Ok(valid_transactions.as_slice())
         )
     }
@@ -206,7 +237,7 @@ mod test {
         ];
 
         assert_eq!(
-            check_transactions(&invalid_transactions, "CZK".to_owned()),
+            check_transactions(&invalid_transactions, "CZK".to_owned(), &Prices::default()),
             Err(CheckError::TransactionsWithoutValue(vec![
                 transaction_without_value(),
                 transaction_without_value()
@@ -224,7 +255,11 @@ mod test {
         ];
 
         assert_eq!(
-            check_transactions(&unbalanced_transactions, "CZK".to_owned()),
+            check_transactions(
+                &unbalanced_transactions,
+                "CZK".to_owned(),
+                &Prices::default()
+            ),
             Err(CheckError::UnbalancedTransactions(vec![
                 UnbalancedTransaction {
                     transaction: unbalanced_transaction(dec!(10.0)),
@@ -237,4 +272,82 @@ mod test {
             ]))
         )
     }
+
+    #[test]
+    fn check_transactions_should_balance_explicit_purchases_and_sales_at_cost() {
+        let transactions = resolve(
+            parse_journal(
+                "2021-10-07 Broker\nassets:broker 10 VWCE {95.20 EUR}\nassets:bank -952 EUR\n\n\
+                 2022-03-01 Broker\nassets:broker -5 VWCE {95.20 EUR} @ 110 EUR\nassets:bank 550 EUR\n",
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(
+            check_transactions(&transactions, "EUR".to_owned(), &Prices::default()),
+            Ok(transactions.as_slice())
+        )
+    }
+
+    #[test]
+    fn check_transactions_should_fail_when_both_postings_have_the_same_sign() {
+        let transactions = resolve(
+            parse_journal(
+                "2021-10-07 Broker\nassets:broker 10 VWCE {95.20 EUR}\nassets:bank 952 EUR\n",
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(
+            check_transactions(&transactions, "EUR".to_owned(), &Prices::default()),
+            Err(CheckError::UnbalancedTransactions(vec![
+                UnbalancedTransaction {
+                    transaction: transactions[0].clone(),
+                    difference: dec!(1904.00)
+                }
+            ]))
+        )
+    }
+
+    #[test]
+    fn check_transactions_should_add_the_postings_of_automated_transactions() {
+        let transactions = resolve(
+            parse_journal(
+                "= expenses:food\nassets:savings 0.1\n\n\
+                 = expenses:food\nassets:savings 0.1\nassets:bank -0.1\n\n\
+                 2021-10-07 Tesco\nexpenses:food 30 EUR\nassets:bank\n",
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(
+            check_transactions(&transactions, "EUR".to_owned(), &Prices::default()),
+            Err(CheckError::UnbalancedTransactions(vec![
+                UnbalancedTransaction {
+                    transaction: transactions[0].clone(),
+                    difference: dec!(3)
+                }
+            ]))
+        )
+    }
+
+    #[test]
+    fn check_transactions_should_fail_with_transactions_too_large_to_be_computed() {
+        let transactions = resolve(
+            parse_journal(
+                "2021-10-07 Broker\n\
+                 assets:broker 79228162514264337593543950335 VWCE {2 EUR}\n\
+                 assets:bank\n\n\
+                 2021-10-08 Bank\n\
+                 assets:bank 79228162514264337593543950335 EUR\n\
+                 assets:savings 79228162514264337593543950335 EUR\n",
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(
+            check_transactions(&transactions, "EUR".to_owned(), &Prices::default()),
+            Err(CheckError::OverflowingTransactions(transactions.clone()))
+        )
+    }
 }
@@ -0,0 +1,284 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::{Error, IoError};
+use crate::parser::{ast::JournalElement, parse_journal};
+
+/// Replaces every include directive of the journal with the elements of the journal it includes,
+/// which can include other journals too. The content of every included journal is given by the
+/// `read` function, so that journals can be read from anywhere and not only from the file system.
+///
+/// Relative paths are relative to the journal that includes them, which is in the given `path`
+/// for the top journal, or the current directory if the path is empty.
+pub fn expand<F>(
+    journal: Vec<JournalElement>,
+    path: &str,
+    read: &mut F,
+) -> Result<Vec<JournalElement>, Error>
+where
+    F: FnMut(&str) -> Result<String, IoError>,
+{
+    expand_included(journal, path, read, &mut vec![path.to_owned()])
+}
+
+/// Returns the path of every journal included by the journal, directly or by the journals it
+/// includes, in the order they are included. Journals that can't be read or parsed are listed too,
+/// since fixing them changes the journal, but their includes can't be followed.
+pub fn paths<F>(journal: &[JournalElement], path: &str, read: &mut F) -> Vec<String>
+where
+    F: FnMut(&str) -> Result<String, IoError>,
+{
+    let mut paths = vec![path.to_owned()];
+    included_paths(journal, path, read, &mut paths);
+    paths.split_off(1)
+}
+
+/// Returns the path of the journal that has every transaction of the expanded journal, in the same
+/// order, together with the position of the transaction among the transactions of that journal,
+/// so that the transactions can be edited in the journal they come from. The journal is expected
+/// to have been expanded without errors.
+pub fn origins<F>(journal: &[JournalElement], path: &str, read: &mut F) -> Vec<(String, usize)>
+where
+    F: FnMut(&str) -> Result<String, IoError>,
+{
+    let mut origins = vec![];
+    let mut index = 0;
+
+    for element in journal {
+        match element {
+            JournalElement::Transaction(_) => {
+                origins.push((path.to_owned(), index));
+                index += 1;
+            }
+            JournalElement::Include(include_path) => {
+                let include_path = relative_to(path, include_path);
+                if let Ok(Ok(journal)) = read(&include_path).map(|content| parse_journal(&content))
+                {
+                    origins.extend(self::origins(&journal, &include_path, read));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    origins
+}
+
+fn included_paths<F>(journal: &[JournalElement], path: &str, read: &mut F, paths: &mut Vec<String>)
+where
+    F: FnMut(&str) -> Result<String, IoError>,
+{
+    for element in journal {
+        let include_path = match element {
+            JournalElement::Include(include_path) => relative_to(path, include_path),
+            _ => continue,
+        };
+        if paths.contains(&include_path) {
+            continue;
+        }
+
+        paths.push(include_path.clone());
+        if let Ok(Ok(journal)) = read(&include_path).map(|content| parse_journal(&content)) {
+            included_paths(&journal, &include_path, read, paths);
+        }
+    }
+}
+
+fn expand_included<F>(
+    journal: Vec<JournalElement>,
+    path: &str,
+    read: &mut F,
+    included: &mut Vec<String>,
+) -> Result<Vec<JournalElement>, Error>
+where
+    F: FnMut(&str) -> Result<String, IoError>,
+{
+    let mut elements = Vec::with_capacity(journal.len());
+
+    for element in journal {
+        let include_path = match element {
+            JournalElement::Include(include_path) => relative_to(path, &include_path),
+            element => {
+                elements.push(element);
+                continue;
+            }
+        };
+        if included.contains(&include_path) {
+            return Err(Error::IncludeCycle(include_path));
+        }
+
+        let include_error = |error| Error::Include {
+            path: include_path.clone(),
+            error: Box::new(error),
+        };
+        let content = read(&include_path).map_err(|error| include_error(Error::Io(error)))?;
+        let journal =
+            parse_journal(&content).map_err(|error| include_error(Error::Parse(error)))?;
+
+        included.push(include_path.clone());
+        elements.extend(expand_included(journal, &include_path, read, included)?);
+        included.pop();
+    }
+
+    Ok(elements)
+}
+
+/// Returns the path of the included journal from the path of the journal that includes it, without
+/// `.` and `..` components, so that the same journal always has the same path.
+pub(crate) fn relative_to(path: &str, include_path: &str) -> String {
+    let joined = match Path::new(path).parent() {
+        Some(directory) => directory.join(include_path),
+        None => PathBuf::from(include_path),
+    };
+    let mut normalized = PathBuf::new();
+
+    for component in joined.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::io;
+
+    use super::{expand, origins, paths};
+
+    use crate::error::{Error, IoError};
+    use crate::parser::{ast::JournalElement, parse_journal};
+
+    fn read_from(
+        files: HashMap<&'static str, &'static str>,
+    ) -> impl FnMut(&str) -> Result<String, IoError> {
+        move |path| {
+            files
+                .get(path)
+                .map(|content| content.to_string())
+                .ok_or_else(|| IoError::new(path, io::Error::from(io::ErrorKind::NotFound)))
+        }
+    }
+
+    #[test]
+    fn replaces_includes_relative_to_the_including_journal() {
+        let mut read = read_from(
+            vec![
+                ("books/2021.journal", "include prices.journal\ny2021\n"),
+                ("books/prices.journal", "P 2021-10-01 USD 0.9 EUR\n"),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let journal = parse_journal("include books/2021.journal\naccount assets\n").unwrap();
+
+        let elements: Vec<JournalElement> = expand(journal, "main.journal", &mut read)
+            .unwrap()
+            .into_iter()
+            .filter(|element| *element != JournalElement::Empty)
+            .collect();
+
+        assert!(matches!(elements[0], JournalElement::Price(_)));
+        assert_eq!(elements[1], JournalElement::Year(2021));
+        assert!(matches!(elements[2], JournalElement::Account(_)));
+    }
+
+    #[test]
+    fn fails_when_an_included_journal_fails() {
+        let mut read = read_from(
+            vec![
+                ("a.journal", "include b.journal\n"),
+                ("b.journal", "include ./books/../a.journal\n"),
+                ("bad.journal", "2021-10-05 Tesco\n"),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        assert_eq!(
+            expand(
+                parse_journal("include a.journal").unwrap(),
+                "a.journal",
+                &mut read
+            ),
+            Err(Error::IncludeCycle("a.journal".to_owned()))
+        );
+        assert!(matches!(
+            expand(parse_journal("include bad.journal").unwrap(), "", &mut read),
+            Err(Error::Include { path, error }) if path == "bad.journal" && matches!(*error, Error::Parse(_))
+        ));
+        assert!(matches!(
+            expand(parse_journal("include missing.journal").unwrap(), "", &mut read),
+            Err(Error::Include { error, .. }) if matches!(*error, Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn lists_the_paths_of_every_included_journal_once() {
+        let mut read = read_from(
+            vec![
+                (
+                    "books/2021.journal",
+                    "include prices.journal\ninclude ../main.journal\n",
+                ),
+                ("books/prices.journal", "include missing.journal\n"),
+                ("bad.journal", "2021-10-05 Tesco\n"),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let journal = parse_journal(
+            "include books/2021.journal\ninclude bad.journal\ninclude books/prices.journal\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            paths(&journal, "main.journal", &mut read),
+            vec![
+                "books/2021.journal",
+                "books/prices.journal",
+                "books/missing.journal",
+                "bad.journal"
+            ]
+        );
+    }
+
+    #[test]
+    fn locates_every_transaction_in_the_journal_it_comes_from() {
+        let mut read = read_from(
+            vec![(
+                "books/2021.journal",
+                "2021-10-05 Tesco\nexpenses:food 30 EUR\nassets:bank\n\n\
+                 2021-10-06 Aldi\nexpenses:food 12 EUR\nassets:bank\n",
+            )]
+            .into_iter()
+            .collect(),
+        );
+        let journal = parse_journal(
+            "2021-10-01 ACME\nincome:salary -1000 EUR\nassets:bank\n\n\
+             include books/2021.journal\n\n\
+             2021-10-07 Lidl\nexpenses:food 7 EUR\nassets:bank\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            origins(&journal, "main.journal", &mut read),
+            vec![
+                ("main.journal".to_owned(), 0),
+                ("books/2021.journal".to_owned(), 0),
+                ("books/2021.journal".to_owned(), 1),
+                ("main.journal".to_owned(), 1),
+            ]
+        );
+    }
+}
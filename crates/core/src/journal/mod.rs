@@ -1,11 +1,23 @@
+use std::fmt::{self, Display};
+
+use chrono::Datelike;
 use rust_decimal::Decimal;
 
+use crate::conversion::{Prices, DEFAULT_CURRENCY};
+use crate::error::{Error, IoError, ParseError};
 use crate::parser::{
-    ast::{JournalElement, Transaction},
-    parse_journal,
+    ast::{
+        Amount, AutomatedTransaction, JournalElement, ParsedDate, Posting, Span, Transaction, Year,
+    },
+    parse_journal, parse_journal_with_spans,
 };
 
+pub mod automated;
 mod checks;
+pub mod include;
+mod model;
+
+pub use model::Journal;
 
 /// Wraps a transaction that is unbalanced with the difference that caused it to be unbalanced.
 #[derive(Debug, PartialEq)]
@@ -17,28 +29,898 @@ pub struct UnbalancedTransaction {
 /// Defines all the different types of checking errors that can happen when validating a journal.
 #[derive(Debug, PartialEq)]
 pub enum CheckError {
-    NonParsable(String),
+    NonParsable(ParseError),
     TransactionsWithoutValue(Vec<Transaction>),
     UnbalancedTransactions(Vec<UnbalancedTransaction>),
+    OverflowingTransactions(Vec<Transaction>),
 }
 
-/// Validates the given journal, if correct returns Ok with nothing wrapped or otherwise the
-/// `CheckError` that happened during validation.
-pub fn validate(content: &str) -> Result<(), CheckError> {
+impl Display for CheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckError::NonParsable(error) => {
+                write!(f, "There was an error parsing the journal:\n{}", error)
+            }
+            CheckError::TransactionsWithoutValue(transactions) => write!(
+                f,
+                "{} transactions have no values associated with them",
+                transactions.len()
+            ),
+            CheckError::UnbalancedTransactions(transactions) => {
+                write!(f, "{} transactions are unbalanced", transactions.len())
+            }
+            CheckError::OverflowingTransactions(transactions) => write!(
+                f,
+                "{} transactions have amounts too large to be computed",
+                transactions.len()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CheckError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CheckError::NonParsable(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Defines the issues found while validating a journal that do not make it invalid.
+#[derive(Debug, PartialEq)]
+pub enum CheckWarning {
+    UnmatchedAutomatedTransaction(AutomatedTransaction),
+}
+
+/// Defines the problems that can be found while checking a journal. Every problem has a code that
+/// does not change between versions, so that tools can rely on it.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DiagnosticCode {
+    NonParsable,
+    NonIncludable,
+    TransactionWithoutValue,
+    UnbalancedTransaction,
+    OverflowingTransaction,
+    UnmatchedAutomatedTransaction,
+}
+
+impl DiagnosticCode {
+    /// Returns the stable code of the problem, `P` codes being parse errors, `V` codes validation
+    /// errors and `W` codes warnings.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DiagnosticCode::NonParsable => "P001",
+            DiagnosticCode::NonIncludable => "P002",
+            DiagnosticCode::TransactionWithoutValue => "V001",
+            DiagnosticCode::UnbalancedTransaction => "V002",
+            DiagnosticCode::OverflowingTransaction => "V003",
+            DiagnosticCode::UnmatchedAutomatedTransaction => "W001",
+        }
+    }
+
+    /// Returns a readable name of the problem.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DiagnosticCode::NonParsable => "non-parsable",
+            DiagnosticCode::NonIncludable => "non-includable",
+            DiagnosticCode::TransactionWithoutValue => "transaction-without-value",
+            DiagnosticCode::UnbalancedTransaction => "unbalanced-transaction",
+            DiagnosticCode::OverflowingTransaction => "overflowing-transaction",
+            DiagnosticCode::UnmatchedAutomatedTransaction => "unmatched-automated-transaction",
+        }
+    }
+
+    /// Returns whether the problem makes the journal invalid.
+    pub fn is_error(&self) -> bool {
+        !matches!(self, DiagnosticCode::UnmatchedAutomatedTransaction)
+    }
+}
+
+/// Represents a problem found while checking a journal, with the part of the journal where it was
+/// found and the transaction that caused it if there is one.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub code: DiagnosticCode,
+    pub message: String,
+    pub span: Span,
+    /// Path of the included journal where the problem was found, or `None` if it was found in the
+    /// journal that was checked.
+    pub path: Option<String>,
+    pub transaction: Option<Transaction>,
+}
+
+/// Represents an element of a journal with its span and the path of the included journal that has
+/// it, or `None` if it is in the journal that was checked.
+type LocatedElement = (JournalElement, Span, Option<String>);
+
+/// Validates the given journal, if correct returns Ok with the list of `CheckWarning` found or
+/// otherwise the `CheckError` that happened during validation. Automated transactions are expanded
+/// before checking the transactions.
+pub fn validate(content: &str) -> Result<Vec<CheckWarning>, CheckError> {
     parse_journal(content)
-        .map_err(|error| CheckError::NonParsable(error))
-        .and_then(|journal| {
-            checks::transactions::check_transactions(&get_transactions(journal), "EUR".to_owned())
-                .map(|_| ())
+        .map_err(CheckError::NonParsable)
+        .and_then(validate_elements)
+}
+
+/// Validates the journal made of the given elements like `validate`, for journals that were
+/// already parsed.
+pub(crate) fn validate_elements(
+    journal: Vec<JournalElement>,
+) -> Result<Vec<CheckWarning>, CheckError> {
+    let automated_transactions = get_automated_transactions(&journal);
+    let prices = Prices::from_journal(&journal);
+    let transactions = resolve(journal);
+
+    checks::transactions::check_transactions(&transactions, DEFAULT_CURRENCY.to_owned(), &prices)?;
+
+    Ok(automated::unmatched(&transactions, &automated_transactions)
+        .into_iter()
+        .cloned()
+        .map(CheckWarning::UnmatchedAutomatedTransaction)
+        .collect())
+}
+
+/// Checks the given journal like `validate`, but returning every problem found with its location
+/// instead of stopping at the first kind of error.
+pub fn diagnose(content: &str) -> Vec<Diagnostic> {
+    match parse_journal_with_spans(content) {
+        Ok(elements) => diagnose_elements(
+            &elements
+                .into_iter()
+                .map(|(element, span)| (element, span, None))
+                .collect::<Vec<LocatedElement>>(),
+        ),
+        Err(failure) => vec![non_parsable(failure, None)],
+    }
+}
+
+/// Checks the journal in the given path like `diagnose` together with the journals it includes,
+/// whose content is given by the `read` function. Problems found in an included journal have its
+/// path, and included journals that can't be read are reported at their include directive.
+pub fn diagnose_with<F>(content: &str, path: &str, read: &mut F) -> Vec<Diagnostic>
+where
+    F: FnMut(&str) -> Result<String, IoError>,
+{
+    let mut elements = vec![];
+    let mut diagnostics = expand_located(
+        content,
+        path,
+        None,
+        read,
+        &mut vec![path.to_owned()],
+        &mut elements,
+    );
+
+    diagnostics.extend(diagnose_elements(&elements));
+    diagnostics
+}
+
+/// Adds the elements of the journal in the content to the given elements, replacing its include
+/// directives with the elements of the journals they include, and returns the problems found while
+/// parsing or including them.
+fn expand_located<F>(
+    content: &str,
+    path: &str,
+    included_path: Option<&str>,
+    read: &mut F,
+    included: &mut Vec<String>,
+    elements: &mut Vec<LocatedElement>,
+) -> Vec<Diagnostic>
+where
+    F: FnMut(&str) -> Result<String, IoError>,
+{
+    let parsed = match parse_journal_with_spans(content) {
+        Ok(parsed) => parsed,
+        Err(failure) => return vec![non_parsable(failure, included_path)],
+    };
+    let mut diagnostics = vec![];
+
+    for (element, span) in parsed {
+        let include_path = match &element {
+            JournalElement::Include(include_path) => include::relative_to(path, include_path),
+            _ => {
+                elements.push((element, span, included_path.map(str::to_owned)));
+                continue;
+            }
+        };
+        let content = if included.contains(&include_path) {
+            Err(Error::IncludeCycle(include_path.clone()))
+        } else {
+            read(&include_path).map_err(|error| Error::Include {
+                path: include_path.clone(),
+                error: Box::new(Error::Io(error)),
+            })
+        };
+
+        match content {
+            Ok(content) => {
+                included.push(include_path.clone());
+                diagnostics.extend(expand_located(
+                    &content,
+                    &include_path,
+                    Some(&include_path),
+                    read,
+                    included,
+                    elements,
+                ));
+                included.pop();
+            }
+            Err(error) => diagnostics.push(Diagnostic {
+                code: DiagnosticCode::NonIncludable,
+                message: error.to_string(),
+                span,
+                path: included_path.map(str::to_owned),
+                transaction: None,
+            }),
+        }
+    }
+
+    diagnostics
+}
+
+fn non_parsable(failure: ParseError, path: Option<&str>) -> Diagnostic {
+    Diagnostic {
+        code: DiagnosticCode::NonParsable,
+        message: failure.message,
+        span: failure.span,
+        path: path.map(str::to_owned),
+        transaction: None,
+    }
+}
+
+fn diagnose_elements(elements: &[LocatedElement]) -> Vec<Diagnostic> {
+    let transaction_locations = elements
+        .iter()
+        .filter(|(element, _, _)| matches!(element, JournalElement::Transaction(_)))
+        .map(|(_, span, path)| (*span, path.clone()));
+    let automated_transactions = elements
+        .iter()
+        .filter_map(|(element, span, path)| match element {
+            JournalElement::AutomatedTransaction(automated_transaction) => {
+                Some((automated_transaction.clone(), *span, path.clone()))
+            }
+            _ => None,
+        })
+        .collect::<Vec<(AutomatedTransaction, Span, Option<String>)>>();
+    let journal = elements
+        .iter()
+        .map(|(element, _, _)| element.clone())
+        .collect::<Vec<JournalElement>>();
+    let prices = Prices::from_journal(&journal);
+    let transactions = resolve(journal);
+
+    let mut diagnostics = transactions
+        .iter()
+        .zip(transaction_locations)
+        .flat_map(|(transaction, (span, path))| {
+            transaction_diagnostics(transaction, span, path, &prices)
+        })
+        .collect::<Vec<Diagnostic>>();
+
+    diagnostics.extend(
+        automated_transactions
+            .iter()
+            .filter(|(automated_transaction, _, _)| {
+                !automated::unmatched(&transactions, std::slice::from_ref(automated_transaction))
+                    .is_empty()
+            })
+            .map(|(automated_transaction, span, path)| Diagnostic {
+                code: DiagnosticCode::UnmatchedAutomatedTransaction,
+                message: format!(
+                    "The automated transaction \"= {}\" does not match any posting",
+                    automated_transaction.query
+                ),
+                span: *span,
+                path: path.clone(),
+                transaction: None,
+            }),
+    );
+
+    diagnostics
+}
+
+/// Represents the diagnostics that appeared or disappeared between two checks of a journal.
+#[derive(Debug, PartialEq)]
+pub struct DiagnosticChanges<'a> {
+    pub appeared: Vec<&'a Diagnostic>,
+    pub disappeared: Vec<&'a Diagnostic>,
+}
+
+/// Compares the diagnostics of two checks of the same journal. Diagnostics are compared without
+/// their spans, so that a problem is not reported as new when lines are added above it.
+pub fn compare_diagnostics<'a>(
+    previous: &'a [Diagnostic],
+    current: &'a [Diagnostic],
+) -> DiagnosticChanges<'a> {
+    DiagnosticChanges {
+        appeared: missing_from(current, previous),
+        disappeared: missing_from(previous, current),
+    }
+}
+
+fn missing_from<'a>(diagnostics: &'a [Diagnostic], others: &[Diagnostic]) -> Vec<&'a Diagnostic> {
+    let mut others = others.iter().collect::<Vec<&Diagnostic>>();
+
+    diagnostics
+        .iter()
+        .filter(|diagnostic| {
+            match others.iter().position(|other| {
+                other.code == diagnostic.code
+                    && other.message == diagnostic.message
+                    && other.path == diagnostic.path
+                    && other.transaction == diagnostic.transaction
+            }) {
+                Some(position) => {
+                    others.remove(position);
+                    false
+                }
+                None => true,
+            }
+        })
+        .collect()
+}
+
+fn transaction_diagnostics(
+    transaction: &Transaction,
+    span: Span,
+    path: Option<String>,
+    prices: &Prices,
+) -> Vec<Diagnostic> {
+    let error = match check_transaction(transaction, prices) {
+        Ok(_) => return vec![],
+        Err(error) => error,
+    };
+
+    let (code, message) = match error {
+        CheckError::TransactionsWithoutValue(_) => (
+            DiagnosticCode::TransactionWithoutValue,
+            "The transaction has no values associated with it".to_owned(),
+        ),
+        CheckError::UnbalancedTransactions(unbalanced_transactions) => (
+            DiagnosticCode::UnbalancedTransaction,
+            format!(
+                "The transaction is unbalanced. Difference between postings: {}",
+                unbalanced_transactions
+                    .first()
+                    .map(|unbalanced_transaction| unbalanced_transaction.difference)
+                    .unwrap_or_default()
+            ),
+        ),
+        CheckError::OverflowingTransactions(_) => (
+            DiagnosticCode::OverflowingTransaction,
+            "The amounts of the transaction are too large to be computed".to_owned(),
+        ),
+        CheckError::NonParsable(_) => return vec![],
+    };
+
+    vec![Diagnostic {
+        code,
+        message,
+        span,
+        path,
+        transaction: Some(transaction.clone()),
+    }]
+}
+
+/// Checks that the given transaction has values and is balanced with the prices of its journal,
+/// running the same checks that are run on every transaction when validating a journal.
+pub fn check_transaction(transaction: &Transaction, prices: &Prices) -> Result<(), CheckError> {
+    checks::transactions::check_transactions(
+        std::slice::from_ref(transaction),
+        DEFAULT_CURRENCY.to_owned(),
+        prices,
+    )
+    .map(|_| ())
+}
+
+/// Returns the payees of the transactions of the journal, sorted and without repetitions.
+pub fn payees(journal: &[JournalElement]) -> Vec<String> {
+    let mut payees: Vec<String> = journal
+        .iter()
+        .filter_map(|element| match element {
+            JournalElement::Transaction(transaction) if !transaction.payee.is_empty() => {
+                Some(transaction.payee.clone())
+            }
+            _ => None,
+        })
+        .collect();
+
+    payees.sort();
+    payees.dedup();
+    payees
+}
+
+/// Returns every account of the journal, either declared or used by a posting, together with all
+/// of its parents, sorted and without repetitions.
+pub fn accounts(journal: &[JournalElement]) -> Vec<String> {
+    let mut accounts: Vec<String> = journal
+        .iter()
+        .flat_map(|element| match element {
+            JournalElement::Account(account) => vec![account.clone()],
+            JournalElement::Transaction(transaction) => vec![
+                transaction.postings.0.account.clone(),
+                transaction.postings.1.account.clone(),
+            ],
+            JournalElement::AutomatedTransaction(automated_transaction) => automated_transaction
+                .postings
+                .iter()
+                .map(|posting| posting.account.clone())
+                .collect(),
+            _ => vec![],
+        })
+        .flat_map(|account| {
+            (0..=account.children.len())
+                .map(|depth| {
+                    std::iter::once(account.name.clone())
+                        .chain(account.children[..depth].iter().cloned())
+                        .collect::<Vec<String>>()
+                        .join(":")
+                })
+                .collect::<Vec<String>>()
         })
+        .collect();
+
+    accounts.sort();
+    accounts.dedup();
+    accounts
+}
+
+/// Returns the tags of the transactions of the journal, sorted and without repetitions.
+pub fn tags(journal: &[JournalElement]) -> Vec<String> {
+    let mut tags: Vec<String> = journal
+        .iter()
+        .flat_map(|element| match element {
+            JournalElement::Transaction(transaction) => transaction.tags.clone(),
+            _ => vec![],
+        })
+        .collect();
+
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Returns the currencies used by the amounts and prices of the journal, sorted and without
+/// repetitions.
+pub fn currencies(journal: &[JournalElement]) -> Vec<String> {
+    let mut currencies: Vec<String> = journal
+        .iter()
+        .flat_map(|element| match element {
+            JournalElement::Transaction(transaction) => [
+                &transaction.postings.0.amount,
+                &transaction.postings.1.amount,
+            ]
+            .iter()
+            .filter_map(|amount| amount.as_ref().map(|amount| amount.currency.clone()))
+            .collect(),
+            JournalElement::Price(price) => {
+                vec![price.currency.clone(), price.amount.currency.clone()]
+            }
+            _ => vec![],
+        })
+        .collect();
+
+    currencies.sort();
+    currencies.dedup();
+    currencies
+}
+
+/// Resolves the transactions of the given journal, giving partial dates the year of the closest
+/// year declaration that precedes them and expanding the automated transactions.
+pub fn resolve(journal: Vec<JournalElement>) -> Vec<Transaction> {
+    let automated_transactions = get_automated_transactions(&journal);
+    automated::expand(get_transactions(journal), &automated_transactions)
+}
+
+/// Returns the postings of the transaction with the amount of a posting without one set to the
+/// opposite of the weight of the other posting, which values lots at their cost or price. The
+/// amount stays unset when the weight is too large to be computed.
+pub fn resolved_postings(transaction: &Transaction) -> (Posting, Posting) {
+    let (first_posting, second_posting) = &transaction.postings;
+
+    (
+        Posting {
+            amount: first_posting
+                .amount
+                .clone()
+                .or_else(|| negated(&second_posting.weight().ok().flatten())),
+            ..first_posting.clone()
+        },
+        Posting {
+            amount: second_posting
+                .amount
+                .clone()
+                .or_else(|| negated(&first_posting.weight().ok().flatten())),
+            ..second_posting.clone()
+        },
+    )
+}
+
+fn negated(amount: &Option<Amount>) -> Option<Amount> {
+    amount.as_ref().map(|amount| Amount {
+        quantity: -amount.quantity,
+        currency: amount.currency.clone(),
+    })
 }
 
 fn get_transactions(journal: Vec<JournalElement>) -> Vec<Transaction> {
     journal
         .into_iter()
+        .scan(None, |year, element| match element {
+            JournalElement::Year(journal_year) => {
+                *year = Some(journal_year);
+                Some(None)
+            }
+            JournalElement::Transaction(transaction) => Some(Some(Transaction {
+                date: resolve_date(transaction.date.clone(), *year),
+                ..transaction
+            })),
+            _ => Some(None),
+        })
+        .flatten()
+        .collect()
+}
+
+/// Gives the date the given year if it was declared without one.
+pub fn resolve_date(date: ParsedDate, year: Option<Year>) -> ParsedDate {
+    match (date, year) {
+        (ParsedDate::Partial(date), Some(year)) => date
+            .with_year(year as i32)
+            .map(ParsedDate::Full)
+            .unwrap_or(ParsedDate::Partial(date)),
+        (date, _) => date,
+    }
+}
+
+fn get_automated_transactions(journal: &[JournalElement]) -> Vec<AutomatedTransaction> {
+    journal
+        .iter()
         .filter_map(|element| match element {
-            JournalElement::Transaction(transaction) => Some(transaction),
+            JournalElement::AutomatedTransaction(automated_transaction) => {
+                Some(automated_transaction.clone())
+            }
             _ => None,
         })
         .collect()
 }
+
+#[cfg(test)]
+mod test {
+    use std::io;
+
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    use super::{
+        accounts, check_transaction, compare_diagnostics, currencies, diagnose, diagnose_with,
+        payees, resolve, resolved_postings, tags, DiagnosticCode,
+    };
+
+    use crate::conversion::Prices;
+    use crate::error::IoError;
+
+    use crate::parser::parse_journal;
+
+    use crate::parser::ast::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn transaction(date: ParsedDate) -> Transaction {
+        Transaction {
+            date,
+            ..Transaction::default()
+        }
+    }
+
+    #[test]
+    fn resolve_gives_partial_dates_the_year_of_the_previous_year_declaration() {
+        let journal = vec![
+            JournalElement::Transaction(transaction(ParsedDate::Partial(date(2021, 1, 2)))),
+            JournalElement::Year(2019),
+            JournalElement::Transaction(transaction(ParsedDate::Partial(date(2021, 3, 4)))),
+            JournalElement::Year(2020),
+            JournalElement::Transaction(transaction(ParsedDate::Partial(date(2021, 5, 6)))),
+            JournalElement::Transaction(transaction(ParsedDate::Full(date(2018, 7, 8)))),
+        ];
+
+        assert_eq!(
+            resolve(journal),
+            vec![
+                transaction(ParsedDate::Partial(date(2021, 1, 2))),
+                transaction(ParsedDate::Full(date(2019, 3, 4))),
+                transaction(ParsedDate::Full(date(2020, 5, 6))),
+                transaction(ParsedDate::Full(date(2018, 7, 8))),
+            ]
+        )
+    }
+
+    #[test]
+    fn resolved_postings_take_opposite_amount_when_missing() {
+        let amount = Amount {
+            quantity: dec!(10),
+            currency: "EUR".to_owned(),
+        };
+        let transaction = Transaction {
+            postings: (
+                Posting {
+                    account: Account::default(),
+                    amount: Some(amount.clone()),
+                    lot: None,
+                    price: None,
+                },
+                Posting::default(),
+            ),
+            ..Transaction::default()
+        };
+
+        let (first_posting, second_posting) = resolved_postings(&transaction);
+
+        assert_eq!(first_posting.amount, Some(amount));
+        assert_eq!(
+            second_posting.amount,
+            Some(Amount {
+                quantity: dec!(-10),
+                currency: "EUR".to_owned(),
+            })
+        );
+    }
+
+    #[test]
+    fn lots_balance_at_their_cost_or_price() {
+        let content = "2021-10-07 Broker\nassets:broker 10 VWCE {95.20 EUR}\nassets:bank\n\n\
+                       2022-03-01 Broker\nassets:broker -5 VWCE {95.20 EUR} @ 110 EUR\nassets:bank\n";
+        let transactions = resolve(parse_journal(content).unwrap());
+
+        assert_eq!(
+            resolved_postings(&transactions[0]).1.amount,
+            Some(Amount {
+                quantity: dec!(-952),
+                currency: "EUR".to_owned(),
+            })
+        );
+        assert_eq!(
+            resolved_postings(&transactions[1]).1.amount,
+            Some(Amount {
+                quantity: dec!(550),
+                currency: "EUR".to_owned(),
+            })
+        );
+        assert!(diagnose(content).is_empty());
+    }
+
+    #[test]
+    fn diagnose_returns_position_of_parse_errors() {
+        let diagnostics = diagnose("2021-10-07 Tesco\nexpenses 10 EUR\nassets\n\nwrong\n");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::NonParsable);
+        assert_eq!(diagnostics[0].span.start, Position { line: 5, column: 1 });
+    }
+
+    #[test]
+    fn diagnose_returns_every_invalid_transaction_with_its_span() {
+        let diagnostics = diagnose(
+            "2021-10-07 Tesco\nexpenses 10 EUR\nassets 5 EUR\n\n\
+             2021-10-08 Aldi\nexpenses 10 EUR\nassets\n\n\
+             2021-10-09 Lidl\nexpenses 10 EUR\nassets 3 EUR\n",
+        );
+
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| (
+                    diagnostic.code,
+                    diagnostic.span.start.line,
+                    diagnostic.transaction.as_ref().map(|t| t.payee.clone())
+                ))
+                .collect::<Vec<(DiagnosticCode, usize, Option<String>)>>(),
+            vec![
+                (
+                    DiagnosticCode::UnbalancedTransaction,
+                    1,
+                    Some("Tesco".to_owned())
+                ),
+                (
+                    DiagnosticCode::UnbalancedTransaction,
+                    9,
+                    Some("Lidl".to_owned())
+                ),
+            ]
+        );
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.code.is_error()));
+    }
+
+    #[test]
+    fn compares_diagnostics_ignoring_their_position() {
+        let previous = diagnose(
+            "2021-10-07 Tesco\nexpenses 10 EUR\nassets 5 EUR\n\n\
+             2021-10-08 Aldi\nexpenses 10 EUR\nassets\n",
+        );
+        let current = diagnose(
+            "2021-10-06 Lidl\nexpenses 10 EUR\nassets 3 EUR\n\n\
+             2021-10-07 Tesco\nexpenses 10 EUR\nassets 5 EUR\n",
+        );
+
+        let changes = compare_diagnostics(&previous, &current);
+
+        assert_eq!(changes.appeared.len(), 1);
+        assert_eq!(
+            changes.appeared[0]
+                .transaction
+                .as_ref()
+                .map(|t| t.payee.as_str()),
+            Some("Lidl")
+        );
+        assert!(changes.disappeared.is_empty());
+
+        let changes = compare_diagnostics(&current, &previous);
+
+        assert!(changes.appeared.is_empty());
+        assert_eq!(changes.disappeared.len(), 1);
+    }
+
+    #[test]
+    fn diagnose_warns_about_unmatched_automated_transactions() {
+        let diagnostics = diagnose(
+            "= payee:Nobody\nassets:savings 0.1\n\n\
+             2021-10-08 Aldi\nexpenses 10 EUR\nassets\n",
+        );
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].code,
+            DiagnosticCode::UnmatchedAutomatedTransaction
+        );
+        assert!(!diagnostics[0].code.is_error());
+        assert_eq!(diagnostics[0].span.start.line, 1);
+    }
+
+    #[test]
+    fn diagnose_with_reports_included_journals_that_fail_at_their_directive() {
+        let diagnostics = diagnose_with(
+            "include prices.journal\n\ninclude missing.journal\n",
+            "",
+            &mut |path| match path {
+                "prices.journal" => Ok("P 2021-10-01 USD 0.9 EUR\n".to_owned()),
+                _ => Err(IoError::new(path, io::Error::from(io::ErrorKind::NotFound))),
+            },
+        );
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::NonIncludable);
+        assert_eq!(diagnostics[0].code.code(), "P002");
+        assert_eq!(diagnostics[0].span.start.line, 3);
+        assert_eq!(diagnostics[0].path, None);
+    }
+
+    #[test]
+    fn diagnose_with_reports_the_problems_of_included_journals_with_their_path() {
+        let diagnostics = diagnose_with(
+            "include books/2021.journal\ninclude bad.journal\n\n\
+             2021-10-08 Shop\nexpenses 10 USD\nassets -9 EUR\n",
+            "main.journal",
+            &mut |path| match path {
+                "books/2021.journal" => Ok("include prices.journal\n\n\
+                                            2021-10-07 Tesco\nexpenses 30 EUR\nassets -20 EUR\n\n\
+                                            include ../main.journal\n"
+                    .to_owned()),
+                "books/prices.journal" => Ok("P 2021-10-01 USD 0.9 EUR\n".to_owned()),
+                "bad.journal" => Ok("2021-10-05 Tesco\n".to_owned()),
+                _ => Err(IoError::new(path, io::Error::from(io::ErrorKind::NotFound))),
+            },
+        );
+
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| (
+                    diagnostic.code,
+                    diagnostic.path.as_deref(),
+                    diagnostic.span.start.line
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (DiagnosticCode::NonIncludable, Some("books/2021.journal"), 7),
+                (DiagnosticCode::NonParsable, Some("bad.journal"), 2),
+                (
+                    DiagnosticCode::UnbalancedTransaction,
+                    Some("books/2021.journal"),
+                    3
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn returns_sorted_payees_and_accounts_with_their_parents() {
+        let journal = parse_journal(
+            "account liabilities:card\n\n\
+             2021-10-07 Tesco\nexpenses:food 10 EUR\nassets:bank\n\n\
+             2021-10-08 Aldi\nexpenses:food 5 EUR\nassets:bank\n\n\
+             2021-10-09 Tesco\nexpenses:home 5 EUR\nassets:bank\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            payees(&journal),
+            vec!["Aldi".to_owned(), "Tesco".to_owned()]
+        );
+        assert_eq!(
+            accounts(&journal),
+            vec![
+                "assets",
+                "assets:bank",
+                "expenses",
+                "expenses:food",
+                "expenses:home",
+                "liabilities",
+                "liabilities:card"
+            ]
+        );
+    }
+
+    #[test]
+    fn returns_sorted_tags_and_currencies() {
+        let journal = parse_journal(
+            "P 2021-10-01 USD 0.9 EUR\n\n\
+             2021-10-07 Tesco ;food,weekly\nexpenses:food 10 GBP\nassets:bank\n\n\
+             2021-10-08 Aldi ;food\nexpenses:food 5 EUR\nassets:bank\n",
+        )
+        .unwrap();
+
+        assert_eq!(tags(&journal), vec!["food".to_owned(), "weekly".to_owned()]);
+        assert_eq!(
+            currencies(&journal),
+            vec!["EUR".to_owned(), "GBP".to_owned(), "USD".to_owned()]
+        );
+    }
+
+    #[test]
+    fn checks_single_transaction() {
+        let transactions =
+            resolve(parse_journal("2021-10-07 Tesco\nexpenses 10 EUR\nassets 5 EUR\n").unwrap());
+
+        assert!(check_transaction(&transactions[0], &Prices::default()).is_err());
+    }
+
+    #[test]
+    fn balances_transactions_in_several_currencies_with_the_prices_of_the_journal() {
+        assert!(diagnose(
+            "P 2021-10-01 USD 0.9 EUR\n\n\
+             2021-10-07 Amazon\nexpenses:books 10 USD\nassets:bank -9 EUR\n"
+        )
+        .is_empty());
+        assert_eq!(
+            diagnose("2021-10-07 Amazon\nexpenses:books 10 USD\nassets:bank -9 EUR\n")[0].code,
+            DiagnosticCode::UnbalancedTransaction
+        );
+    }
+
+    #[test]
+    fn diagnose_reports_transactions_with_amounts_too_large_to_be_computed() {
+        let diagnostics = diagnose(
+            "2021-10-07 Broker\n\
+             assets:broker 79228162514264337593543950335 VWCE {2 EUR}\n\
+             assets:bank\n",
+        );
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::OverflowingTransaction);
+        assert_eq!(diagnostics[0].code.code(), "V003");
+        assert_eq!(
+            diagnose(
+                "= expenses:food\n\
+                 assets:savings 79228162514264337593543950335\n\
+                 assets:bank -79228162514264337593543950335\n\n\
+                 2021-10-07 Tesco\nexpenses:food 30 EUR\nassets:bank\n",
+            )[0]
+            .code,
+            DiagnosticCode::OverflowingTransaction
+        );
+    }
+}
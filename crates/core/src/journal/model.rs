@@ -0,0 +1,249 @@
+use chrono::NaiveDate;
+
+use crate::conversion::{convert_to, Prices};
+use crate::error::{Error, IoError};
+use crate::io::file;
+use crate::parser::ast::{Amount, CurrencyCode, JournalElement, Price, Query, Transaction};
+use crate::parser::{parse_journal, parse_query};
+use crate::query::matches_transaction;
+use crate::report::balance::{self, BalanceReport};
+use crate::report::register::{self, RegisterReport};
+
+use super::{
+    accounts, currencies, include, payees, resolve, tags, validate_elements, CheckError,
+    CheckWarning,
+};
+
+/// Represents a journal ready to be used by other tools, which keeps the elements that were parsed
+/// and the transactions once resolved, so that tools don't need to resolve the syntax tree
+/// themselves.
+///
+/// The transactions of a journal have the year of the journal in their partial dates and the
+/// postings of the automated transactions that matched them. Postings can still omit their amount,
+/// which `journal::resolved_postings` fills, and reports already take into account.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Journal {
+    elements: Vec<JournalElement>,
+    transactions: Vec<Transaction>,
+    prices: Prices,
+}
+
+impl Journal {
+    /// Reads and parses the journal in the given path, together with the journals it includes.
+    pub fn from_path(path: &str) -> Result<Self, Error> {
+        let content = file::read_content(path)?;
+        let elements = parse_journal(&content).map_err(Error::Parse)?;
+
+        include::expand(elements, path, &mut file::read_content).map(Self::from_elements)
+    }
+
+    /// Parses the journal in the given content. Include directives are not followed, since there
+    /// is no file to read them from, use `parse_with` to follow them.
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        parse_journal(content)
+            .map(Self::from_elements)
+            .map_err(Error::Parse)
+    }
+
+    /// Parses the journal in the given content together with the journals it includes, whose
+    /// content is given by the `read` function from their path.
+    pub fn parse_with<F>(content: &str, read: &mut F) -> Result<Self, Error>
+    where
+        F: FnMut(&str) -> Result<String, IoError>,
+    {
+        let elements = parse_journal(content).map_err(Error::Parse)?;
+
+        include::expand(elements, "", read).map(Self::from_elements)
+    }
+
+    /// Creates a journal from elements that were already parsed.
+    pub fn from_elements(elements: Vec<JournalElement>) -> Self {
+        Self {
+            prices: Prices::from_journal(&elements),
+            transactions: resolve(elements.clone()),
+            elements,
+        }
+    }
+
+    /// Returns the elements of the journal as they were parsed, with the elements of the included
+    /// journals in place of the directives that included them.
+    pub fn elements(&self) -> &[JournalElement] {
+        &self.elements
+    }
+
+    /// Returns the resolved transactions in the order of the journal.
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    /// Returns the accounts that are declared or used by a posting together with their parents,
+    /// sorted and without duplicates.
+    pub fn accounts(&self) -> Vec<String> {
+        accounts(&self.elements)
+    }
+
+    /// Returns the payees of the transactions, sorted and without duplicates.
+    pub fn payees(&self) -> Vec<String> {
+        payees(&self.elements)
+    }
+
+    /// Returns the tags of the transactions, sorted and without duplicates.
+    pub fn tags(&self) -> Vec<String> {
+        tags(&self.elements)
+    }
+
+    /// Returns the currencies and commodities of the postings and prices, sorted and without
+    /// duplicates.
+    pub fn commodities(&self) -> Vec<String> {
+        currencies(&self.elements)
+    }
+
+    /// Returns the price directives in the order of the journal.
+    pub fn prices(&self) -> impl Iterator<Item = &Price> {
+        self.elements.iter().filter_map(|element| match element {
+            JournalElement::Price(price) => Some(price),
+            _ => None,
+        })
+    }
+
+    /// Values the amount in the currency with the latest price of the journal known at the date,
+    /// returning None if there is no price between both currencies.
+    pub fn value(
+        &self,
+        amount: &Amount,
+        currency: &CurrencyCode,
+        date: NaiveDate,
+    ) -> Option<Amount> {
+        convert_to(currency, amount, &self.prices, date)
+    }
+
+    /// Returns the transactions that have a posting that matches the query.
+    pub fn filter(&self, query: &Query) -> Vec<&Transaction> {
+        self.transactions
+            .iter()
+            .filter(|transaction| matches_transaction(query, transaction))
+            .collect()
+    }
+
+    /// Returns the transactions that match the query written in the query language of the
+    /// commands, like `expenses tag:food`.
+    pub fn query(&self, query: &str) -> Result<Vec<&Transaction>, Error> {
+        parse_query(query)
+            .map(|query| self.filter(&query))
+            .map_err(Error::Query)
+    }
+
+    /// Computes the balance of the accounts of the postings that match the query.
+    pub fn balance(&self, query: &Query) -> Result<BalanceReport, Error> {
+        balance::report(&self.transactions, query).map_err(Error::Overflow)
+    }
+
+    /// Lists the postings that match the query with the running total of every currency.
+    pub fn register(&self, query: &Query) -> Result<RegisterReport, Error> {
+        register::report(&self.transactions, query).map_err(Error::Overflow)
+    }
+
+    /// Checks that every transaction is balanced, like the `check` command, returning the warnings
+    /// found if it is.
+    pub fn check(&self) -> Result<Vec<CheckWarning>, CheckError> {
+        validate_elements(self.elements.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    use super::Journal;
+
+    use crate::error::Error;
+    use crate::journal::CheckError;
+    use crate::parser::ast::{Amount, Query, QueryTerm};
+
+    const JOURNAL: &str = "P 2021-10-01 USD 0.9 EUR\n\n\
+                           = expenses:food\nassets:savings 0.1\nassets:bank -0.1\n\n\
+                           2021-10-05 Tesco ;food\nexpenses:food 30 EUR\nassets:bank\n\n\
+                           2021-10-07 Amazon\nexpenses:books 20 USD\nassets:card\n";
+
+    #[test]
+    fn lists_the_contents_of_the_journal() {
+        let journal = Journal::parse(JOURNAL).unwrap();
+
+        assert_eq!(journal.transactions().len(), 2);
+        assert_eq!(journal.transactions()[0].automated_postings.len(), 2);
+        assert_eq!(
+            journal.accounts(),
+            vec![
+                "assets",
+                "assets:bank",
+                "assets:card",
+                "assets:savings",
+                "expenses",
+                "expenses:books",
+                "expenses:food"
+            ]
+        );
+        assert_eq!(journal.payees(), vec!["Amazon", "Tesco"]);
+        assert_eq!(journal.tags(), vec!["food"]);
+        assert_eq!(journal.commodities(), vec!["EUR", "USD"]);
+        assert_eq!(journal.prices().count(), 1);
+        assert_eq!(
+            journal.value(
+                &Amount {
+                    quantity: dec!(20),
+                    currency: "USD".to_owned(),
+                },
+                &"EUR".to_owned(),
+                NaiveDate::from_ymd_opt(2021, 10, 7).unwrap()
+            ),
+            Some(Amount {
+                quantity: dec!(18.0),
+                currency: "EUR".to_owned(),
+            })
+        );
+    }
+
+    #[test]
+    fn runs_queries_and_reports() {
+        let journal = Journal::parse(JOURNAL).unwrap();
+
+        assert_eq!(journal.query("tag:food").unwrap()[0].payee, "Tesco");
+        assert!(matches!(journal.query(""), Err(Error::Query(_))));
+        assert_eq!(
+            journal
+                .balance(&Query(vec![QueryTerm::Account("assets".to_owned())]))
+                .unwrap()
+                .totals
+                .len(),
+            2
+        );
+        assert_eq!(
+            journal
+                .register(&Query(vec![QueryTerm::Account("expenses".to_owned())]))
+                .unwrap()
+                .entries
+                .len(),
+            2
+        );
+        assert_eq!(journal.check().map(|warnings| warnings.len()), Ok(0));
+    }
+
+    #[test]
+    fn fails_with_typed_errors() {
+        assert!(matches!(
+            Journal::from_path("missing.journal"),
+            Err(Error::Io(error)) if error.is_not_found() && error.path == "missing.journal"
+        ));
+        assert!(matches!(
+            Journal::parse("2021-10-05 Tesco\nexpenses:food 30 EUR\n"),
+            Err(Error::Parse(_))
+        ));
+        assert!(matches!(
+            Journal::parse("2021-10-05 Tesco\nexpenses:food 30 EUR\nassets:bank -20 EUR\n")
+                .unwrap()
+                .check(),
+            Err(CheckError::UnbalancedTransactions(_))
+        ));
+    }
+}
@@ -1,6 +1,28 @@
 extern crate nom;
 
+pub mod chart;
+pub mod close;
 pub mod conversion;
+pub mod convert;
+pub mod editor;
+pub mod error;
+pub mod export;
+pub mod formatter;
+pub mod import;
 pub mod io;
 pub mod journal;
+pub mod lots;
 pub mod parser;
+pub mod period;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod query;
+pub mod reconcile;
+pub mod report;
+pub mod site;
+pub mod template;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use error::Error;
+pub use journal::Journal;
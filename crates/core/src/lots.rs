@@ -0,0 +1,469 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::Decimal;
+
+use crate::error::OverflowError;
+use crate::journal::resolved_postings;
+use crate::parser::ast::{Amount, Lot, Posting, Transaction};
+use crate::report::add_to;
+
+/// Defines how the lots reduced by a sale are chosen when the sale does not name a lot.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Booking {
+    /// Reduces the lots acquired first.
+    Fifo,
+    /// Reduces the lots acquired last.
+    Lifo,
+    /// Merges every lot into one whose cost is the average cost of the units before reducing it.
+    Average,
+}
+
+/// Represents units of a commodity held in an account that were acquired together.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Holding {
+    pub account: String,
+    pub commodity: String,
+    pub quantity: Decimal,
+    /// Cost of every unit of the lot.
+    pub cost: Amount,
+    pub date: NaiveDate,
+}
+
+/// Represents the sale of units of a commodity with the gain, or loss, that it realized.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Sale {
+    pub date: NaiveDate,
+    pub account: String,
+    pub commodity: String,
+    pub quantity: Decimal,
+    pub proceeds: Amount,
+    pub cost: Amount,
+    pub gain: Amount,
+}
+
+/// Represents the lots held once every transaction has been booked and the sales that reduced them.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Lots {
+    pub holdings: Vec<Holding>,
+    pub sales: Vec<Sale>,
+}
+
+/// Defines the problems that prevent booking the sales of a journal.
+#[derive(Debug, PartialEq, Clone)]
+pub enum LotError {
+    /// A sale reduced more units than the account held.
+    InsufficientUnits {
+        date: NaiveDate,
+        account: String,
+        commodity: String,
+        missing: Decimal,
+    },
+    /// A sale named a lot that the account does not hold.
+    LotNotFound {
+        date: NaiveDate,
+        account: String,
+        lot: Lot,
+    },
+    /// A sale had no price and its proceeds could not be taken from the other posting.
+    MissingPrice {
+        date: NaiveDate,
+        account: String,
+        commodity: String,
+    },
+    /// The costs of the lots reduced by a sale and its proceeds are in different currencies.
+    CurrencyMismatch {
+        date: NaiveDate,
+        account: String,
+        commodity: String,
+    },
+    /// The cost or the proceeds of the lots are too large to be computed.
+    Overflow(OverflowError),
+}
+
+impl From<OverflowError> for LotError {
+    fn from(error: OverflowError) -> Self {
+        LotError::Overflow(error)
+    }
+}
+
+/// Books the transactions in date order. Postings with a lot and a positive amount open a lot, while
+/// negative amounts of a commodity held in the account, or that name a lot, are sales that reduce
+/// the lots chosen by the booking method, or the named lot. The proceeds of a sale are its price,
+/// or the amount of the other posting if it's in another currency.
+pub fn book(transactions: &[Transaction], booking: Booking) -> Result<Lots, LotError> {
+    let mut transactions: Vec<&Transaction> = transactions.iter().collect();
+    transactions.sort_by_key(|transaction| transaction.date.date());
+
+    let mut lots = Lots {
+        holdings: vec![],
+        sales: vec![],
+    };
+
+    for transaction in transactions {
+        let date = transaction.date.date();
+        let (first_posting, second_posting) = resolved_postings(transaction);
+
+        for (posting, other_posting) in [
+            (&first_posting, &second_posting),
+            (&second_posting, &first_posting),
+        ] {
+            let amount = match &posting.amount {
+                Some(amount) => amount,
+                None => continue,
+            };
+            let account = posting.account.to_string();
+
+            if amount.quantity > Decimal::ZERO {
+                if let Some(lot) = &posting.lot {
+                    lots.holdings.push(Holding {
+                        account,
+                        commodity: amount.currency.clone(),
+                        quantity: amount.quantity,
+                        cost: lot.cost.clone(),
+                        date: lot.date.unwrap_or(date),
+                    });
+                }
+            } else if posting.lot.is_some()
+                || lots.holdings.iter().any(|holding| {
+                    holding.account == account && holding.commodity == amount.currency
+                })
+            {
+                let sale = sell(
+                    &mut lots.holdings,
+                    date,
+                    posting,
+                    amount,
+                    other_posting,
+                    booking,
+                )?;
+                lots.sales.push(sale);
+            }
+        }
+    }
+
+    Ok(lots)
+}
+
+/// Adds up the gains of the sales of every year, by currency. Returns an error if the gains are too
+/// large to be computed.
+pub fn gains_per_year(sales: &[Sale]) -> Result<BTreeMap<(i32, String), Decimal>, OverflowError> {
+    let mut gains: BTreeMap<(i32, String), Decimal> = BTreeMap::new();
+
+    for sale in sales {
+        add_to(
+            gains
+                .entry((sale.date.year(), sale.gain.currency.clone()))
+                .or_default(),
+            sale.gain.quantity,
+        )?;
+    }
+
+    Ok(gains)
+}
+
+fn sell(
+    holdings: &mut Vec<Holding>,
+    date: NaiveDate,
+    posting: &Posting,
+    amount: &Amount,
+    other_posting: &Posting,
+    booking: Booking,
+) -> Result<Sale, LotError> {
+    let account = posting.account.to_string();
+    let commodity = amount.currency.clone();
+    let quantity = -amount.quantity;
+    let proceeds = match (&posting.price, &other_posting.amount) {
+        (Some(price), _) => Amount {
+            quantity: price.quantity.checked_mul(quantity).ok_or(OverflowError)?,
+            currency: price.currency.clone(),
+        },
+        (None, Some(other_amount)) if other_amount.currency != commodity => Amount {
+            quantity: other_amount.quantity.abs(),
+            currency: other_amount.currency.clone(),
+        },
+        _ => {
+            return Err(LotError::MissingPrice {
+                date,
+                account,
+                commodity,
+            })
+        }
+    };
+
+    let mut candidates: Vec<usize> = (0..holdings.len())
+        .filter(|index| {
+            let holding = &holdings[*index];
+            holding.account == account
+                && holding.commodity == commodity
+                && posting.lot.as_ref().is_none_or(|lot| {
+                    holding.cost == lot.cost && lot.date.is_none_or(|date| holding.date == date)
+                })
+        })
+        .collect();
+
+    if let Some(lot) = &posting.lot {
+        if candidates.is_empty() {
+            return Err(LotError::LotNotFound {
+                date,
+                account,
+                lot: lot.clone(),
+            });
+        }
+    } else {
+        match booking {
+            Booking::Fifo => candidates.sort_by_key(|index| holdings[*index].date),
+            Booking::Lifo => {
+                candidates.sort_by_key(|index| holdings[*index].date);
+                candidates.reverse();
+            }
+            Booking::Average => {
+                if candidates
+                    .iter()
+                    .any(|index| holdings[*index].cost.currency != proceeds.currency)
+                {
+                    return Err(LotError::CurrencyMismatch {
+                        date,
+                        account,
+                        commodity,
+                    });
+                }
+
+                candidates = merge(holdings, &candidates)?.into_iter().collect();
+            }
+        }
+    }
+
+    let mut remaining = quantity;
+    let mut cost = Decimal::ZERO;
+
+    for index in candidates {
+        if remaining.is_zero() {
+            break;
+        }
+
+        let holding = &mut holdings[index];
+
+        if holding.cost.currency != proceeds.currency {
+            return Err(LotError::CurrencyMismatch {
+                date,
+                account,
+                commodity,
+            });
+        }
+
+        let reduced = remaining.min(holding.quantity);
+        holding.quantity -= reduced;
+        remaining -= reduced;
+        add_to(
+            &mut cost,
+            reduced
+                .checked_mul(holding.cost.quantity)
+                .ok_or(OverflowError)?,
+        )?;
+    }
+    holdings.retain(|holding| holding.quantity > Decimal::ZERO);
+
+    if remaining > Decimal::ZERO {
+        return Err(LotError::InsufficientUnits {
+            date,
+            account,
+            commodity,
+            missing: remaining,
+        });
+    }
+
+    Ok(Sale {
+        date,
+        account,
+        commodity,
+        quantity,
+        gain: Amount {
+            quantity: proceeds.quantity.checked_sub(cost).ok_or(OverflowError)?,
+            currency: proceeds.currency.clone(),
+        },
+        cost: Amount {
+            quantity: cost,
+            currency: proceeds.currency.clone(),
+        },
+        proceeds,
+    })
+}
+
+/// Merges the given holdings, whose costs are in the same currency, into the first one, which takes
+/// the average cost of the units and the earliest date. Returns the index of the merged holding, or
+/// an error if the average cost is too large to be computed.
+fn merge(holdings: &mut [Holding], indexes: &[usize]) -> Result<Option<usize>, OverflowError> {
+    let first = match indexes.first() {
+        Some(first) => *first,
+        None => return Ok(None),
+    };
+    let (mut quantity, mut total_cost) = (Decimal::ZERO, Decimal::ZERO);
+    for index in indexes {
+        let holding = &holdings[*index];
+        add_to(&mut quantity, holding.quantity)?;
+        add_to(
+            &mut total_cost,
+            holding
+                .quantity
+                .checked_mul(holding.cost.quantity)
+                .ok_or(OverflowError)?,
+        )?;
+    }
+    let date = indexes.iter().map(|index| holdings[*index].date).min();
+
+    for index in &indexes[1..] {
+        holdings[*index].quantity = Decimal::ZERO;
+    }
+
+    let merged = &mut holdings[first];
+    merged.quantity = quantity;
+    merged.cost.quantity = total_cost.checked_div(quantity).ok_or(OverflowError)?;
+    merged.date = date.unwrap_or(merged.date);
+
+    Ok(Some(first))
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    use super::{book, gains_per_year, Booking, LotError};
+
+    use crate::error::OverflowError;
+    use crate::journal::resolve;
+    use crate::parser::ast::Transaction;
+    use crate::parser::parse_journal;
+
+    fn transactions(sale: &str) -> Vec<Transaction> {
+        resolve(
+            parse_journal(&format!(
+                "2021-01-10 Broker\nassets:broker 10 VWCE {{90 EUR}}\nassets:bank\n\n\
+                 2021-06-10 Broker\nassets:broker 10 VWCE {{100 EUR}}\nassets:bank\n\n\
+                 2022-03-01 Broker\n{}\nassets:bank\n",
+                sale
+            ))
+            .unwrap(),
+        )
+    }
+
+    fn gain(sale: &str, booking: Booking) -> Decimal {
+        book(&transactions(sale), booking).unwrap().sales[0]
+            .gain
+            .quantity
+    }
+
+    #[test]
+    fn books_sales_with_every_method() {
+        let sale = "assets:broker -15 VWCE @ 110 EUR";
+
+        assert_eq!(gain(sale, Booking::Fifo), dec!(250));
+        assert_eq!(gain(sale, Booking::Lifo), dec!(200));
+        assert_eq!(gain(sale, Booking::Average), dec!(225));
+    }
+
+    #[test]
+    fn books_sales_of_specific_lots() {
+        let lots = book(
+            &transactions("assets:broker -5 VWCE {100 EUR} [2021-06-10] @ 110 EUR"),
+            Booking::Fifo,
+        )
+        .unwrap();
+
+        assert_eq!(lots.sales[0].cost.quantity, dec!(500));
+        assert_eq!(lots.sales[0].gain.quantity, dec!(50));
+        assert_eq!(
+            lots.holdings
+                .iter()
+                .map(|holding| (holding.quantity, holding.cost.quantity))
+                .collect::<Vec<(Decimal, Decimal)>>(),
+            vec![(dec!(10), dec!(90)), (dec!(5), dec!(100))]
+        );
+    }
+
+    #[test]
+    fn fails_when_selling_more_than_held() {
+        assert_eq!(
+            book(
+                &transactions("assets:broker -25 VWCE @ 110 EUR"),
+                Booking::Fifo
+            ),
+            Err(LotError::InsufficientUnits {
+                date: NaiveDate::from_ymd_opt(2022, 3, 1).unwrap(),
+                account: "assets:broker".to_owned(),
+                commodity: "VWCE".to_owned(),
+                missing: dec!(5),
+            })
+        );
+        assert!(matches!(
+            book(
+                &transactions("assets:broker -5 VWCE {95 EUR} @ 110 EUR"),
+                Booking::Fifo
+            ),
+            Err(LotError::LotNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn only_requires_the_currency_of_the_sale_for_the_lots_that_are_sold() {
+        let transactions = resolve(
+            parse_journal(
+                "2021-01-10 Broker\nassets:broker 10 VWCE {90 EUR}\nassets:bank\n\n\
+                 2021-06-10 Broker\nassets:broker 10 VWCE {100 USD}\nassets:bank\n\n\
+                 2022-03-01 Broker\nassets:broker -5 VWCE @ 110 EUR\nassets:bank\n",
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(
+            book(&transactions, Booking::Fifo).unwrap().sales[0]
+                .gain
+                .quantity,
+            dec!(100)
+        );
+        assert!(matches!(
+            book(&transactions, Booking::Lifo),
+            Err(LotError::CurrencyMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn adds_up_gains_per_year() {
+        let lots = book(
+            &transactions("assets:broker -15 VWCE @ 110 EUR"),
+            Booking::Fifo,
+        )
+        .unwrap();
+
+        assert_eq!(
+            gains_per_year(&lots.sales)
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![((2022, "EUR".to_owned()), dec!(250))]
+        );
+    }
+
+    #[test]
+    fn fails_when_the_cost_of_a_sale_is_too_large_to_be_computed() {
+        let transactions = resolve(
+            parse_journal(&format!(
+                "2021-01-10 Broker\nassets:broker 10 VWCE {{{max} EUR}}\nassets:bank\n\n\
+                 2022-03-01 Broker\nassets:broker -10 VWCE @ 110 EUR\nassets:bank\n",
+                max = Decimal::MAX
+            ))
+            .unwrap(),
+        );
+
+        assert_eq!(
+            book(&transactions, Booking::Fifo),
+            Err(LotError::Overflow(OverflowError))
+        );
+        assert_eq!(
+            book(&transactions, Booking::Average),
+            Err(LotError::Overflow(OverflowError))
+        );
+    }
+}
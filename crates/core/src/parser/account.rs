@@ -1,15 +1,17 @@
 use nom::{
+    branch::alt,
     character::complete::{alphanumeric1, char},
-    combinator::{map, opt},
+    combinator::{map, opt, recognize},
     error::{context, ContextError, ParseError},
     multi::many0,
-    sequence::tuple,
+    sequence::{pair, tuple},
     IResult,
 };
 
 use super::ast::Account;
 
-/// Parses an account with the format "parent:child".
+/// Parses an account with the format "parent:child", where every segment starts with an
+/// alphanumeric character and can contain dashes, like "equity:retained-earnings".
 pub fn parse<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
     input: &'a str,
 ) -> IResult<&'a str, Account, E> {
@@ -17,23 +19,28 @@ pub fn parse<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
         "account",
         map(
             tuple((
-                alphanumeric1,  // All accounts start with an alphanumeric name
-                opt(char(':')), // Followed by an optional ':' separator
-                many0(tuple((alphanumeric1, opt(char(':'))))), // And optionally repeating both
+                segment,                                 // All accounts start with a name
+                opt(char(':')),                          // Followed by an optional ':' separator
+                many0(tuple((segment, opt(char(':'))))), // And optionally repeating both
             )),
-            |(account_name, _, children): (&str, Option<char>, Vec<(&str, Option<char>)>)| {
-                Account {
-                    name: account_name.to_owned(),
-                    children: children
-                        .into_iter()
-                        .map(|(name, _)| name.to_owned())
-                        .collect(),
-                }
+            |(account_name, _, children): (&str, _, Vec<(&str, _)>)| Account {
+                name: account_name.to_owned(),
+                children: children
+                    .into_iter()
+                    .map(|(name, _)| name.to_owned())
+                    .collect(),
             },
         ),
     )(input)
 }
 
+fn segment<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, &'a str, E> {
+    recognize(pair(
+        alphanumeric1,
+        many0(alt((alphanumeric1, recognize(char('-'))))),
+    ))(input)
+}
+
 #[cfg(test)]
 mod test {
     use nom::{error::Error, error::ErrorKind::AlphaNumeric, Err};
@@ -117,6 +124,31 @@ mod test {
         )
     }
 
+    #[test]
+    fn parses_valid_account_with_dashes() {
+        assert_eq!(
+            parse::<Error<&str>>("equity:retained-earnings"),
+            Ok((
+                "",
+                Account {
+                    name: "equity".to_owned(),
+                    children: vec!("retained-earnings".to_owned())
+                }
+            ))
+        )
+    }
+
+    #[test]
+    fn errors_when_starts_with_dash() {
+        assert_eq!(
+            parse::<Error<&str>>("-account"),
+            Err(Err::Error(Error {
+                input: "-account",
+                code: AlphaNumeric
+            }))
+        )
+    }
+
     #[test]
     fn errors_when_empty() {
         assert_eq!(
@@ -3,8 +3,8 @@ use std::str::FromStr;
 use nom::{
     bytes::complete::take_while1,
     character::complete::{alpha1, space1},
-    combinator::map,
-    error::{ContextError, ParseError},
+    combinator::{map, map_opt},
+    error::{context, ContextError, ParseError},
     sequence::tuple,
     IResult,
 };
@@ -17,23 +17,31 @@ pub fn parse<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
     input: &'a str,
 ) -> IResult<&'a str, Amount, E> {
     map(
-        tuple((
-            take_while1(|c: char| c.is_numeric() || c == '.' || c == '-'),
-            space1,
-            alpha1,
-        )),
+        tuple((parse_quantity, space1, alpha1)),
         |(quantity, _, currency)| Amount {
-            quantity: Decimal::from_str(quantity).unwrap(),
+            quantity,
             currency: currency.to_owned(),
         },
     )(input)
 }
 
+fn parse_quantity<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, Decimal, E> {
+    context(
+        "quantity",
+        map_opt(
+            take_while1(|c: char| c.is_numeric() || c == '.' || c == '-'),
+            |quantity| Decimal::from_str(quantity).ok(),
+        ),
+    )(input)
+}
+
 #[cfg(test)]
 mod test {
     use nom::{
         error::Error,
-        error::ErrorKind::{Alpha, Space, TakeWhile1},
+        error::ErrorKind::{Alpha, MapOpt, Space, TakeWhile1},
         Err,
     };
     use rust_decimal_macros::dec;
@@ -163,4 +171,23 @@ mod test {
             }))
         )
     }
+
+    #[test]
+    fn fails_when_quantity_is_not_a_number() {
+        assert_eq!(
+            parse::<Error<&str>>("4.0.4 USD"),
+            Err(Err::Error(Error {
+                input: "4.0.4 USD",
+                code: MapOpt
+            }))
+        );
+
+        assert_eq!(
+            parse::<Error<&str>>("- USD"),
+            Err(Err::Error(Error {
+                input: "- USD",
+                code: MapOpt
+            }))
+        )
+    }
 }
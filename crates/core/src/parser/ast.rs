@@ -1,36 +1,50 @@
-use chrono::{Date, Datelike, Utc};
+use chrono::{Datelike, NaiveDate, Utc};
 use rust_decimal::Decimal;
+use std::collections::BTreeMap;
 use std::fmt::{self, Display};
 
+use crate::error::OverflowError;
+
 pub type CurrencyCode = String;
 pub type Description = String;
+pub type Metadata = BTreeMap<String, String>;
 pub type Payee = String;
 pub type Tag = String;
 pub type Year = u32;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum ParsedDate {
-    Full(Date<Utc>),
+    Full(NaiveDate),
     /// Represents dates that omitted the year and were given a default one that should be
     /// switched to the journal year.
-    Partial(Date<Utc>),
+    Partial(NaiveDate),
+}
+
+impl ParsedDate {
+    /// Returns the date regardless of whether it was given in full or not.
+    pub fn date(&self) -> NaiveDate {
+        match self {
+            ParsedDate::Full(date) | ParsedDate::Partial(date) => *date,
+        }
+    }
 }
 
 impl Display for ParsedDate {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            &ParsedDate::Full(date) => write!(f, "{}/{}", date.day(), date.month()),
-            &ParsedDate::Partial(date) => write!(f, "{}/{}", date.day(), date.month()),
+            ParsedDate::Full(date) => write!(f, "{}/{}", date.day(), date.month()),
+            ParsedDate::Partial(date) => write!(f, "{}/{}", date.day(), date.month()),
         }
     }
 }
 
 impl Default for ParsedDate {
     fn default() -> Self {
-        Self::Full(Utc::now().date())
+        Self::Full(Utc::now().naive_utc().date())
     }
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, PartialEq, Clone)]
 pub enum JournalElement {
     Empty,
@@ -38,6 +52,10 @@ pub enum JournalElement {
     Comment,
     Year(Year),
     Transaction(Transaction),
+    AutomatedTransaction(AutomatedTransaction),
+    Price(Price),
+    /// Path of a journal whose elements are part of this one, as written in the journal.
+    Include(String),
 }
 
 impl fmt::Display for JournalElement {
@@ -45,9 +63,14 @@ impl fmt::Display for JournalElement {
         match self {
             JournalElement::Empty => write!(f, ""),
             JournalElement::Account(account) => write!(f, "Account: {:?}", account),
-            JournalElement::Comment => write!(f, "Comment\n"),
-            JournalElement::Year(year) => write!(f, "Year: {}\n", year),
-            JournalElement::Transaction(transaction) => write!(f, "{:?}\n", transaction),
+            JournalElement::Comment => writeln!(f, "Comment"),
+            JournalElement::Year(year) => writeln!(f, "Year: {}", year),
+            JournalElement::Transaction(transaction) => writeln!(f, "{:?}", transaction),
+            JournalElement::AutomatedTransaction(automated_transaction) => {
+                writeln!(f, "{:?}", automated_transaction)
+            }
+            JournalElement::Price(price) => writeln!(f, "{:?}", price),
+            JournalElement::Include(path) => writeln!(f, "Include: {}", path),
         }
     }
 }
@@ -60,6 +83,15 @@ pub struct Account {
     pub children: Vec<String>,
 }
 
+impl Display for Account {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        self.children
+            .iter()
+            .try_for_each(|child| write!(f, ":{}", child))
+    }
+}
+
 /// Represents an amount with its quantity and its currency.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Amount {
@@ -75,26 +107,60 @@ pub enum PayeeSectionType {
     PayeeAndDescription((Payee, Description)),
 }
 
+/// Represents the lot that the units of a commodity belong to, written as `{cost} [date]` after the
+/// amount of a posting. The cost is what was paid for every unit when the lot was acquired.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Lot {
+    pub cost: Amount,
+    pub date: Option<NaiveDate>,
+}
+
 /// Defines an account posting, which indicates either a positive or negative transfer to an account.
 #[derive(Debug, PartialEq, Clone, Default)]
 pub struct Posting {
     pub account: Account,
     pub amount: Option<Amount>,
+    /// Lot of the units of the amount, for commodities that are held at a cost.
+    pub lot: Option<Lot>,
+    /// Price of every unit of the amount, written as `@ {amount}`, usually when a lot is sold.
+    pub price: Option<Amount>,
+}
+
+impl Posting {
+    /// Returns what the posting is worth when balancing its transaction, which is the amount valued
+    /// at its price if it has one, at the cost of its lot otherwise, or the amount itself. Fails
+    /// when the value of the amount is too large to be computed.
+    pub fn weight(&self) -> Result<Option<Amount>, OverflowError> {
+        let amount = match &self.amount {
+            Some(amount) => amount,
+            None => return Ok(None),
+        };
+        let unit_value = self
+            .price
+            .as_ref()
+            .or_else(|| self.lot.as_ref().map(|lot| &lot.cost));
+
+        Ok(Some(match unit_value {
+            Some(unit_value) => Amount {
+                quantity: amount
+                    .quantity
+                    .checked_mul(unit_value.quantity)
+                    .ok_or(OverflowError)?,
+                currency: unit_value.currency.clone(),
+            },
+            None => amount.clone(),
+        }))
+    }
 }
 
 /// Defines the different statuses a transaction can have.
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Default)]
 pub enum TransactionStatus {
+    #[default]
     Cleared,
     Pending,
 }
 
-impl Default for TransactionStatus {
-    fn default() -> Self {
-        Self::Cleared
-    }
-}
-
 /// Represents a transaction that happened in an user's account.
 #[derive(Debug, PartialEq, Clone, Default)]
 pub struct Transaction {
@@ -102,6 +168,92 @@ pub struct Transaction {
     pub status: TransactionStatus,
     pub description: Description,
     pub tags: Vec<Tag>,
+    pub metadata: Metadata,
     pub payee: Payee,
     pub postings: (Posting, Posting),
+    /// Postings that were added by automated transactions. These are never part of the journal
+    /// content and are only filled once the automated transactions have been expanded.
+    pub automated_postings: Vec<Posting>,
+}
+
+/// Defines a single term of a query. Account, payee and description terms match when the value is
+/// contained in the matched element ignoring the case, while tags need to be equal.
+#[derive(Debug, PartialEq, Clone)]
+pub enum QueryTerm {
+    Account(String),
+    Payee(String),
+    Description(String),
+    Tag(Tag),
+}
+
+impl Display for QueryTerm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryTerm::Account(account) => write!(f, "acct:{}", account),
+            QueryTerm::Payee(payee) => write!(f, "payee:{}", payee),
+            QueryTerm::Description(description) => write!(f, "desc:{}", description),
+            QueryTerm::Tag(tag) => write!(f, "tag:{}", tag),
+        }
+    }
+}
+
+/// Represents a query that matches postings and transactions. A query matches when all its terms
+/// match.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Query(pub Vec<QueryTerm>);
+
+impl Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let terms: Vec<String> = self.0.iter().map(QueryTerm::to_string).collect();
+        write!(f, "{}", terms.join(" "))
+    }
+}
+
+/// Defines the amount of an automated posting, which can either be a fixed amount or a multiplier
+/// that is applied to the amount of the posting that matched the query.
+#[derive(Debug, PartialEq, Clone)]
+pub enum AutomatedAmount {
+    Fixed(Amount),
+    Multiplier(Decimal),
+}
+
+/// Defines a posting that is added to a transaction by an automated transaction.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AutomatedPosting {
+    pub account: Account,
+    pub amount: AutomatedAmount,
+}
+
+/// Represents an automated transaction, which adds its postings to every transaction that contains
+/// a posting matching its query. The added postings are part of the transaction when checking that
+/// it is balanced, so they have to balance among themselves.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct AutomatedTransaction {
+    pub query: Query,
+    pub postings: Vec<AutomatedPosting>,
+}
+
+/// Represents the price of a currency in another currency at a given date, declared as
+/// `P {date} {currency} {amount}`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Price {
+    pub date: ParsedDate,
+    pub currency: CurrencyCode,
+    pub amount: Amount,
+}
+
+/// Represents a position in the content of a journal. Lines and columns start at 1 and columns
+/// count characters, not bytes.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Represents the part of the content of a journal that an element was parsed from, the end being
+/// the position of its last character.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
 }
@@ -0,0 +1,183 @@
+use std::str::FromStr;
+
+use nom::{
+    branch::alt,
+    character::complete::{char, digit1, line_ending, space1},
+    combinator::{cut, map, map_opt, opt, recognize},
+    error::{context, ContextError, ParseError},
+    multi::separated_list1,
+    sequence::{pair, preceded, separated_pair, terminated, tuple},
+    IResult,
+};
+use rust_decimal::Decimal;
+
+use super::ast::{AutomatedAmount, AutomatedPosting, AutomatedTransaction};
+use super::{account, amount, query};
+
+/// Parses an automated transaction, which starts with an `=` followed by a query and contains one
+/// or more postings divided by a line ending. Each posting has either a fixed amount or a
+/// multiplier that is applied to the amount of the matched posting.
+pub fn parse<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, AutomatedTransaction, E> {
+    context(
+        "automated transaction",
+        preceded(
+            pair(char('='), space1),
+            cut(map(
+                tuple((
+                    terminated(query::parse, line_ending),
+                    terminated(
+                        separated_list1(line_ending, parse_posting),
+                        opt(line_ending),
+                    ),
+                )),
+                |(query, postings)| AutomatedTransaction { query, postings },
+            )),
+        ),
+    )(input)
+}
+
+fn parse_posting<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, AutomatedPosting, E> {
+    context(
+        "automated posting",
+        map(
+            separated_pair(
+                account::parse,
+                space1,
+                alt((
+                    map(amount::parse, AutomatedAmount::Fixed),
+                    map(parse_multiplier, AutomatedAmount::Multiplier),
+                )),
+            ),
+            |(account, amount)| AutomatedPosting { account, amount },
+        ),
+    )(input)
+}
+
+fn parse_multiplier<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, Decimal, E> {
+    context(
+        "multiplier",
+        map_opt(
+            recognize(tuple((
+                opt(char('-')),
+                digit1,
+                opt(pair(char('.'), digit1)),
+            ))),
+            |multiplier| Decimal::from_str(multiplier).ok(),
+        ),
+    )(input)
+}
+
+#[cfg(test)]
+mod test {
+    use nom::{error::Error, error::ErrorKind::Char, Err};
+    use rust_decimal_macros::dec;
+
+    use super::parse;
+
+    use crate::parser::ast::{
+        Account, Amount, AutomatedAmount, AutomatedPosting, AutomatedTransaction, Query, QueryTerm,
+    };
+
+    fn account(name: &str, child: &str) -> Account {
+        Account {
+            name: name.to_owned(),
+            children: vec![child.to_owned()],
+        }
+    }
+
+    #[test]
+    fn parses_automated_transaction_with_multipliers() {
+        assert_eq!(
+            parse::<Error<&str>>(
+                "= acct:expenses:food\nassets:savings 0.21\nliabilities:vat -0.21\n"
+            ),
+            Ok((
+                "",
+                AutomatedTransaction {
+                    query: Query(vec![QueryTerm::Account("expenses:food".to_owned())]),
+                    postings: vec![
+                        AutomatedPosting {
+                            account: account("assets", "savings"),
+                            amount: AutomatedAmount::Multiplier(dec!(0.21)),
+                        },
+                        AutomatedPosting {
+                            account: account("liabilities", "vat"),
+                            amount: AutomatedAmount::Multiplier(dec!(-0.21)),
+                        },
+                    ],
+                }
+            ))
+        )
+    }
+
+    #[test]
+    fn parses_automated_transaction_with_fixed_amounts() {
+        assert_eq!(
+            parse::<Error<&str>>("= payee:Albert\nexpenses:fees 1 EUR\nassets:bank -1 EUR"),
+            Ok((
+                "",
+                AutomatedTransaction {
+                    query: Query(vec![QueryTerm::Payee("Albert".to_owned())]),
+                    postings: vec![
+                        AutomatedPosting {
+                            account: account("expenses", "fees"),
+                            amount: AutomatedAmount::Fixed(Amount {
+                                quantity: dec!(1),
+                                currency: "EUR".to_owned(),
+                            }),
+                        },
+                        AutomatedPosting {
+                            account: account("assets", "bank"),
+                            amount: AutomatedAmount::Fixed(Amount {
+                                quantity: dec!(-1),
+                                currency: "EUR".to_owned(),
+                            }),
+                        },
+                    ],
+                }
+            ))
+        )
+    }
+
+    #[test]
+    fn stops_parsing_postings_at_empty_line() {
+        assert_eq!(
+            parse::<Error<&str>>("= expenses\nassets:savings 0.5\n\n2021-10-07 Albert"),
+            Ok((
+                "\n2021-10-07 Albert",
+                AutomatedTransaction {
+                    query: Query(vec![QueryTerm::Account("expenses".to_owned())]),
+                    postings: vec![AutomatedPosting {
+                        account: account("assets", "savings"),
+                        amount: AutomatedAmount::Multiplier(dec!(0.5)),
+                    }],
+                }
+            ))
+        )
+    }
+
+    #[test]
+    fn errors_when_not_starting_with_equals_sign() {
+        assert_eq!(
+            parse::<Error<&str>>("expenses\nassets:savings 0.5"),
+            Err(Err::Error(Error {
+                input: "expenses\nassets:savings 0.5",
+                code: Char
+            }))
+        )
+    }
+
+    #[test]
+    fn fails_when_posting_has_no_amount() {
+        assert!(matches!(
+            parse::<Error<&str>>("= expenses\nassets:savings\n"),
+            Err(Err::Failure(_))
+        ))
+    }
+}
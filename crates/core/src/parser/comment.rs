@@ -58,7 +58,7 @@ mod tests {
             assert_eq!(
                 parse::<Error<&str>>(input),
                 Err(Err::Error(Error {
-                    input: input,
+                    input,
                     code: Tag
                 }))
             )
@@ -1,4 +1,4 @@
-use chrono::{Date, NaiveDate, Utc};
+use chrono::NaiveDate;
 use nom::{
     branch::alt,
     bytes::complete::take_while1,
@@ -22,9 +22,9 @@ pub fn parse<
         "date",
         terminated(
             alt((
-                map(|i| parse_full_date('-', i), map_to_utc),
-                map(|i| parse_full_date('/', i), map_to_utc),
-                map(|i| parse_full_date('.', i), map_to_utc),
+                map(|i| parse_full_date('-', i), map_to_full_date),
+                map(|i| parse_full_date('/', i), map_to_full_date),
+                map(|i| parse_full_date('.', i), map_to_full_date),
                 map(|i| parse_partial_date('-', i), map_to_partial_date),
                 map(|i| parse_partial_date('/', i), map_to_partial_date),
                 map(|i| parse_partial_date('.', i), map_to_partial_date),
@@ -71,39 +71,33 @@ fn parse_partial_date<
     )(input)
 }
 
-fn map_to_utc(input: NaiveDate) -> ParsedDate {
-    ParsedDate::Full(Date::<Utc>::from_utc(input, Utc))
+fn map_to_full_date(input: NaiveDate) -> ParsedDate {
+    ParsedDate::Full(input)
 }
 
 fn map_to_partial_date(input: NaiveDate) -> ParsedDate {
-    ParsedDate::Partial(Date::<Utc>::from_utc(input, Utc))
+    ParsedDate::Partial(input)
 }
 
 #[cfg(test)]
 mod tests {
     use super::{parse, ParsedDate};
 
-    use chrono::{Date, NaiveDate, Utc};
+    use chrono::NaiveDate;
     use nom::{
         error::Error,
         error::ErrorKind::{MapRes, TakeWhile1},
         Err,
     };
 
-    fn get_full_test_date<'a>(separator: char) -> (String, Date<Utc>) {
-        let date = NaiveDate::from_ymd(2021, 10, 7);
-        (
-            format!("2021{0}10{0}07", separator),
-            Date::<Utc>::from_utc(date, Utc),
-        )
+    fn get_full_test_date(separator: char) -> (String, NaiveDate) {
+        let date = NaiveDate::from_ymd_opt(2021, 10, 7).unwrap();
+        (format!("2021{0}10{0}07", separator), date)
     }
 
-    fn get_partial_test_date<'a>(separator: char) -> (String, Date<Utc>) {
-        let date = NaiveDate::from_ymd(2021, 10, 7);
-        (
-            format!("10{0}07", separator),
-            Date::<Utc>::from_utc(date, Utc),
-        )
+    fn get_partial_test_date(separator: char) -> (String, NaiveDate) {
+        let date = NaiveDate::from_ymd_opt(2021, 10, 7).unwrap();
+        (format!("10{0}07", separator), date)
     }
 
     #[test]
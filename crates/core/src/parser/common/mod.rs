@@ -1 +1 @@
-pub mod date;
\ No newline at end of file
+pub mod date;
@@ -0,0 +1,50 @@
+use nom::{
+    bytes::complete::{tag, take_till1},
+    character::complete::space1,
+    combinator::{cut, map},
+    error::{context, ContextError, ParseError},
+    sequence::{pair, preceded},
+    IResult,
+};
+
+/// Parses an include directive, which is `include` followed by the path of the journal to include
+/// until the end of the line, and returns the path.
+pub fn parse<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, String, E> {
+    context(
+        "include",
+        preceded(
+            pair(tag("include"), space1),
+            cut(map(take_till1(|c| c == '\n' || c == '\r'), |path: &str| {
+                path.trim_end().to_owned()
+            })),
+        ),
+    )(input)
+}
+
+#[cfg(test)]
+mod test {
+    use nom::{error::Error, error::ErrorKind::TakeTill1, Err};
+
+    use super::parse;
+
+    #[test]
+    fn parses_path_until_end_of_line() {
+        assert_eq!(
+            parse::<Error<&str>>("include prices/2021.journal \nP"),
+            Ok(("\nP", "prices/2021.journal".to_owned()))
+        )
+    }
+
+    #[test]
+    fn fails_without_path() {
+        assert_eq!(
+            parse::<Error<&str>>("include \n"),
+            Err(Err::Failure(Error {
+                input: "\n",
+                code: TakeTill1
+            }))
+        )
+    }
+}
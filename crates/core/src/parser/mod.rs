@@ -1,46 +1,222 @@
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::{multispace0, space1};
-use nom::combinator::{eof, map};
-use nom::error::convert_error;
+use nom::combinator::{consumed, eof, map};
+use nom::error::{ErrorKind, VerboseError, VerboseErrorKind};
 use nom::multi::many_till;
-use nom::sequence::{preceded, tuple};
-use nom::Finish;
+use nom::sequence::{preceded, terminated, tuple};
+use nom::{Finish, IResult, Offset};
 
-mod account;
+use crate::error::ParseError;
+
+pub(crate) mod account;
 mod amount;
 pub mod ast;
-mod comment;
+mod automated_transaction;
+pub(crate) mod comment;
 mod common;
+mod include;
 mod journal_year;
+mod price;
+mod query;
 mod transactions;
 
-/// Attempts to parse a journal from the given content, returning a result specifying
-pub fn parse_journal(content: &str) -> Result<Vec<ast::JournalElement>, String> {
-    many_till(
-        preceded(
-            multispace0,
-            alt((
-                map(
-                    preceded(tuple((tag("account"), space1)), account::parse),
-                    ast::JournalElement::Account,
-                ),
-                map(comment::parse, |_| ast::JournalElement::Comment),
-                map(journal_year::parse, ast::JournalElement::Year),
-                map(transactions::parse, ast::JournalElement::Transaction),
-                map(multispace0, |_| ast::JournalElement::Empty),
-            )),
+/// Attempts to parse a journal from the given content, returning the elements of the journal or
+/// where and why the parsing failed.
+pub fn parse_journal(content: &str) -> Result<Vec<ast::JournalElement>, ParseError> {
+    many_till(preceded(multispace0, parse_element), eof)(content)
+        .finish()
+        .map(|(_, (elements, _))| elements)
+        .map_err(|err| parse_error(content, err))
+}
+
+/// Attempts to parse a journal like `parse_journal`, but returning the span of every element and
+/// the position where the parsing failed instead of a textual description of the error.
+pub fn parse_journal_with_spans(
+    content: &str,
+) -> Result<Vec<(ast::JournalElement, ast::Span)>, ParseError> {
+    many_till(preceded(multispace0, consumed(parse_element)), eof)(content)
+        .finish()
+        .map(|(_, (elements, _))| {
+            elements
+                .into_iter()
+                .map(|(consumed, element)| (element, span(content, consumed)))
+                .collect()
+        })
+        .map_err(|err| parse_error(content, err))
+}
+
+/// Returns the position of the character at the given byte offset of the content.
+pub fn position(content: &str, offset: usize) -> ast::Position {
+    let before = &content[..offset];
+    let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+
+    ast::Position {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    }
+}
+
+fn parse_element(input: &str) -> IResult<&str, ast::JournalElement, VerboseError<&str>> {
+    alt((
+        map(
+            preceded(tuple((tag("account"), space1)), account::parse),
+            ast::JournalElement::Account,
+        ),
+        map(comment::parse, |_| ast::JournalElement::Comment),
+        map(include::parse, ast::JournalElement::Include),
+        map(journal_year::parse, ast::JournalElement::Year),
+        map(
+            automated_transaction::parse,
+            ast::JournalElement::AutomatedTransaction,
         ),
-        eof,
-    )(content)
-    .finish()
-    .map(|(_, (elements, _))| elements)
-    .map_err(|err| convert_error(content, err))
+        map(price::parse, ast::JournalElement::Price),
+        map(transactions::parse, ast::JournalElement::Transaction),
+        map(multispace0, |_| ast::JournalElement::Empty),
+    ))(input)
+}
+
+fn span(content: &str, consumed: &str) -> ast::Span {
+    let start = content.offset(consumed);
+    let last_character = consumed
+        .trim_end()
+        .char_indices()
+        .last()
+        .map(|(index, _)| index)
+        .unwrap_or(0);
+
+    ast::Span {
+        start: position(content, start),
+        end: position(content, start + last_character),
+    }
+}
+
+/// Describes the failure of parsing the content. Errors of nom go from the innermost element that
+/// failed to the outermost one, so the first error is where the parsing stopped.
+pub(crate) fn parse_error(content: &str, err: VerboseError<&str>) -> ParseError {
+    let (input, kind) = match err.errors.first() {
+        Some((input, kind)) => (*input, kind),
+        None => (content, &VerboseErrorKind::Nom(ErrorKind::Fail)),
+    };
+    let description = match kind {
+        VerboseErrorKind::Char(expected) => format!("Expected '{}'", expected),
+        VerboseErrorKind::Context(context) => format!("Invalid {}", context),
+        VerboseErrorKind::Nom(ErrorKind::ManyTill) => "Unrecognised element".to_owned(),
+        VerboseErrorKind::Nom(ErrorKind::Space) | VerboseErrorKind::Nom(ErrorKind::MultiSpace) => {
+            "Expected a space".to_owned()
+        }
+        VerboseErrorKind::Nom(ErrorKind::Digit) => "Expected a digit".to_owned(),
+        VerboseErrorKind::Nom(ErrorKind::Alpha) => "Expected a letter".to_owned(),
+        VerboseErrorKind::Nom(ErrorKind::AlphaNumeric) => "Expected a letter or digit".to_owned(),
+        VerboseErrorKind::Nom(ErrorKind::CrLf) => "Expected the end of the line".to_owned(),
+        VerboseErrorKind::Nom(kind) => format!("Unexpected input ({})", kind.description()),
+    };
+    let context: Vec<String> = err
+        .errors
+        .iter()
+        .rev()
+        .filter_map(|(_, kind)| match kind {
+            VerboseErrorKind::Context(context) => Some((*context).to_owned()),
+            _ => None,
+        })
+        .collect();
+    let mut expected: Vec<String> = err
+        .errors
+        .iter()
+        .filter(|(other, _)| other.as_ptr() == input.as_ptr())
+        .filter_map(|(_, kind)| expected_token(kind))
+        .collect();
+    expected.dedup();
+    if expected.is_empty() {
+        expected.extend(context.last().cloned());
+    }
+
+    let offset = content.offset(input);
+    let start = position(content, offset);
+    let word = input
+        .char_indices()
+        .take_while(|(_, c)| !c.is_whitespace())
+        .last()
+        .map(|(index, _)| index)
+        .unwrap_or(0);
+
+    ParseError {
+        message: match (kind, context.last()) {
+            (VerboseErrorKind::Context(_), _) | (_, None) => description,
+            (_, Some(context)) => format!("{} while parsing the {}", description, context),
+        },
+        span: ast::Span {
+            start,
+            end: position(content, offset + word),
+        },
+        expected,
+        context,
+        line: content
+            .lines()
+            .nth(start.line - 1)
+            .unwrap_or_default()
+            .into(),
+    }
+}
+
+fn expected_token(kind: &VerboseErrorKind) -> Option<String> {
+    match kind {
+        VerboseErrorKind::Char(expected) => Some(format!("'{}'", expected)),
+        VerboseErrorKind::Nom(ErrorKind::Space) | VerboseErrorKind::Nom(ErrorKind::MultiSpace) => {
+            Some("space".to_owned())
+        }
+        VerboseErrorKind::Nom(ErrorKind::Digit) => Some("digit".to_owned()),
+        VerboseErrorKind::Nom(ErrorKind::Alpha) => Some("letter".to_owned()),
+        VerboseErrorKind::Nom(ErrorKind::AlphaNumeric) => Some("letter or digit".to_owned()),
+        VerboseErrorKind::Nom(ErrorKind::CrLf) => Some("end of line".to_owned()),
+        VerboseErrorKind::Nom(ErrorKind::Eof) => Some("end of input".to_owned()),
+        _ => None,
+    }
+}
+
+/// Attempts to parse a query from the given content, which must contain nothing but the query.
+pub fn parse_query(content: &str) -> Result<ast::Query, ParseError> {
+    terminated(query::parse, eof)(content.trim())
+        .finish()
+        .map(|(_, query)| query)
+        .map_err(|err| parse_error(content.trim(), err))
+}
+
+/// Attempts to parse a date, which can omit the year, from the given content, which must contain
+/// nothing but the date.
+pub fn parse_date(content: &str) -> Result<ast::ParsedDate, ParseError> {
+    terminated(common::date::parse, eof)(content.trim())
+        .finish()
+        .map(|(_, date)| date)
+        .map_err(|err: VerboseError<&str>| parse_error(content.trim(), err))
+}
+
+/// Attempts to parse an account from the given content, which must contain nothing but the
+/// account.
+pub fn parse_account(content: &str) -> Result<ast::Account, ParseError> {
+    terminated(account::parse, eof)(content.trim())
+        .finish()
+        .map(|(_, account)| account)
+        .map_err(|err: VerboseError<&str>| parse_error(content.trim(), err))
+}
+
+/// Attempts to parse an amount from the given content, which must contain nothing but the
+/// amount.
+pub fn parse_amount(content: &str) -> Result<ast::Amount, ParseError> {
+    terminated(amount::parse, eof)(content.trim())
+        .finish()
+        .map(|(_, amount)| amount)
+        .map_err(|err: VerboseError<&str>| parse_error(content.trim(), err))
 }
 
 #[cfg(test)]
 mod test {
-    use super::parse_journal;
+    use rust_decimal_macros::dec;
+
+    use super::{
+        parse_account, parse_amount, parse_date, parse_journal, parse_journal_with_spans,
+        parse_query, position, ParseError,
+    };
 
     use crate::parser::ast;
 
@@ -59,15 +235,166 @@ mod test {
     fn fails_if_account_does_not_contain_space() {
         assert_eq!(
             parse_journal("accounttest"),
-            Err("0: at line 1, in ManyTill:\naccounttest\n^\n\n".to_owned())
+            Err(ParseError {
+                message: "Unrecognised element".to_owned(),
+                span: ast::Span {
+                    start: ast::Position { line: 1, column: 1 },
+                    end: ast::Position {
+                        line: 1,
+                        column: 11
+                    },
+                },
+                expected: vec![],
+                context: vec![],
+                line: "accounttest".into(),
+            })
         )
     }
 
     #[test]
     fn fails_if_does_not_contain_account_name() {
         assert_eq!(
-            parse_journal("account"),
-            Err("0: at line 1, in ManyTill:\naccount\n^\n\n".to_owned())
+            parse_journal("account").map_err(|err| err.span.start),
+            Err(ast::Position { line: 1, column: 1 })
+        )
+    }
+
+    #[test]
+    fn parses_automated_transaction_followed_by_transaction() {
+        let elements = parse_journal(
+            "= acct:expenses:food\nassets:savings 0.21\n2021-10-07 Albert\nexpenses:food 10 EUR\nassets:bank\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            elements[0],
+            ast::JournalElement::AutomatedTransaction(ast::AutomatedTransaction {
+                query: ast::Query(vec![ast::QueryTerm::Account("expenses:food".to_owned())]),
+                postings: vec![ast::AutomatedPosting {
+                    account: ast::Account {
+                        name: "assets".to_owned(),
+                        children: vec!["savings".to_owned()]
+                    },
+                    amount: ast::AutomatedAmount::Multiplier(dec!(0.21))
+                }]
+            })
+        );
+        assert!(matches!(elements[1], ast::JournalElement::Transaction(_)));
+    }
+
+    #[test]
+    fn parses_valid_query() {
+        assert_eq!(
+            parse_query(" payee:Albert expenses "),
+            Ok(ast::Query(vec![
+                ast::QueryTerm::Payee("Albert".to_owned()),
+                ast::QueryTerm::Account("expenses".to_owned())
+            ]))
+        )
+    }
+
+    #[test]
+    fn fails_if_query_is_empty() {
+        assert!(parse_query("").is_err())
+    }
+
+    #[test]
+    fn returns_span_of_every_element() {
+        let elements = parse_journal_with_spans(
+            "account assets\n\n2021-10-07 Tesco\nexpenses 10 EUR\nassets\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            elements
+                .iter()
+                .map(|(_, span)| *span)
+                .collect::<Vec<ast::Span>>(),
+            vec![
+                ast::Span {
+                    start: ast::Position { line: 1, column: 1 },
+                    end: ast::Position {
+                        line: 1,
+                        column: 14
+                    },
+                },
+                ast::Span {
+                    start: ast::Position { line: 3, column: 1 },
+                    end: ast::Position { line: 5, column: 6 },
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn returns_position_of_parse_failure() {
+        let failure = parse_journal_with_spans("account assets\n\nP 2021-10-07 EUR\n").unwrap_err();
+
+        assert_eq!(
+            failure.span.start,
+            ast::Position {
+                line: 3,
+                column: 17
+            }
+        );
+        assert_eq!(&*failure.line, "P 2021-10-07 EUR");
+        assert!(!failure.message.is_empty());
+    }
+
+    #[test]
+    fn describes_what_was_expected_and_where() {
+        assert_eq!(
+            parse_journal("2021-10-05 Tesco\nexpenses:food 3x EUR\nassets:bank\n"),
+            Err(ParseError {
+                message: "Expected the end of the line while parsing the postings".to_owned(),
+                span: ast::Span {
+                    start: ast::Position {
+                        line: 2,
+                        column: 15
+                    },
+                    end: ast::Position {
+                        line: 2,
+                        column: 16
+                    },
+                },
+                expected: vec!["end of line".to_owned()],
+                context: vec!["transaction".to_owned(), "postings".to_owned()],
+                line: "expenses:food 3x EUR".into(),
+            })
         )
     }
+
+    #[test]
+    fn computes_position_counting_characters() {
+        assert_eq!(
+            position("a\n€uro", "a\n€u".len()),
+            ast::Position { line: 2, column: 3 }
+        )
+    }
+
+    #[test]
+    fn parses_single_values() {
+        assert!(matches!(
+            parse_date(" 10-07 "),
+            Ok(ast::ParsedDate::Partial(_))
+        ));
+        assert_eq!(
+            parse_account("assets:bank").map(|account| account.to_string()),
+            Ok("assets:bank".to_owned())
+        );
+        assert_eq!(
+            parse_amount("-10.50 EUR"),
+            Ok(ast::Amount {
+                quantity: dec!(-10.50),
+                currency: "EUR".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn fails_if_single_value_has_trailing_content() {
+        assert!(parse_date("2021-10-07 Tesco").is_err());
+        assert!(parse_account("assets bank").is_err());
+        assert!(parse_amount("10 EUR extra").is_err());
+    }
 }
@@ -0,0 +1,86 @@
+use nom::{
+    character::complete::{alpha1, char, space0, space1},
+    combinator::{cut, map},
+    error::{context, ContextError, FromExternalError, ParseError},
+    sequence::{pair, preceded, terminated, tuple},
+    IResult,
+};
+
+use super::ast::Price;
+use super::{amount, common::date};
+
+/// Parses a price declaration, which starts with a `P` followed by the date, the currency that is
+/// priced and the amount it is worth in another currency.
+pub fn parse<
+    'a,
+    E: ParseError<&'a str> + ContextError<&'a str> + FromExternalError<&'a str, chrono::ParseError>,
+>(
+    input: &'a str,
+) -> IResult<&'a str, Price, E> {
+    context(
+        "price",
+        preceded(
+            pair(char('P'), space1),
+            cut(map(
+                tuple((
+                    date::parse,
+                    terminated(alpha1, space1),
+                    terminated(amount::parse, space0),
+                )),
+                |(date, currency, amount)| Price {
+                    date,
+                    currency: currency.to_owned(),
+                    amount,
+                },
+            )),
+        ),
+    )(input)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use nom::{error::Error, error::ErrorKind::Char, Err};
+    use rust_decimal_macros::dec;
+
+    use super::parse;
+
+    use crate::parser::ast::{Amount, ParsedDate, Price};
+
+    #[test]
+    fn parses_price() {
+        assert_eq!(
+            parse::<Error<&str>>("P 2021-10-07 USD 0.85 EUR\n"),
+            Ok((
+                "\n",
+                Price {
+                    date: ParsedDate::Full(NaiveDate::from_ymd_opt(2021, 10, 7).unwrap()),
+                    currency: "USD".to_owned(),
+                    amount: Amount {
+                        quantity: dec!(0.85),
+                        currency: "EUR".to_owned(),
+                    },
+                }
+            ))
+        )
+    }
+
+    #[test]
+    fn errors_when_not_starting_with_p() {
+        assert_eq!(
+            parse::<Error<&str>>("2021-10-07 USD 0.85 EUR"),
+            Err(Err::Error(Error {
+                input: "2021-10-07 USD 0.85 EUR",
+                code: Char
+            }))
+        )
+    }
+
+    #[test]
+    fn fails_when_amount_is_missing() {
+        assert!(matches!(
+            parse::<Error<&str>>("P 2021-10-07 USD"),
+            Err(Err::Failure(_))
+        ))
+    }
+}
@@ -0,0 +1,100 @@
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_while1},
+    character::complete::space1,
+    combinator::map,
+    error::{context, ContextError, ParseError},
+    multi::separated_list1,
+    sequence::preceded,
+    IResult,
+};
+
+use super::ast::{Query, QueryTerm};
+
+/// Parses a query composed of space separated terms. Each term can be prefixed with `acct:`,
+/// `payee:`, `desc:` or `tag:` to specify what it matches, defaulting to accounts when no prefix is
+/// given.
+pub fn parse<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, Query, E> {
+    context("query", map(separated_list1(space1, parse_term), Query))(input)
+}
+
+fn parse_term<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, QueryTerm, E> {
+    context(
+        "query term",
+        alt((
+            map(preceded(tag("acct:"), term_value), QueryTerm::Account),
+            map(preceded(tag("payee:"), term_value), QueryTerm::Payee),
+            map(preceded(tag("desc:"), term_value), QueryTerm::Description),
+            map(preceded(tag("tag:"), term_value), QueryTerm::Tag),
+            map(term_value, QueryTerm::Account),
+        )),
+    )(input)
+}
+
+fn term_value<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, String, E> {
+    map(take_while1(|c: char| !c.is_whitespace()), String::from)(input)
+}
+
+#[cfg(test)]
+mod test {
+    use nom::{error::Error, error::ErrorKind::TakeWhile1, Err};
+
+    use super::parse;
+
+    use crate::parser::ast::{Query, QueryTerm};
+
+    #[test]
+    fn parses_account_term_without_prefix() {
+        assert_eq!(
+            parse::<Error<&str>>("expenses:food"),
+            Ok((
+                "",
+                Query(vec![QueryTerm::Account("expenses:food".to_owned())])
+            ))
+        )
+    }
+
+    #[test]
+    fn parses_prefixed_terms() {
+        assert_eq!(
+            parse::<Error<&str>>("acct:expenses payee:Albert desc:Groceries tag:food"),
+            Ok((
+                "",
+                Query(vec![
+                    QueryTerm::Account("expenses".to_owned()),
+                    QueryTerm::Payee("Albert".to_owned()),
+                    QueryTerm::Description("Groceries".to_owned()),
+                    QueryTerm::Tag("food".to_owned()),
+                ])
+            ))
+        )
+    }
+
+    #[test]
+    fn stops_parsing_at_line_ending() {
+        assert_eq!(
+            parse::<Error<&str>>("acct:expenses\nassets:savings 0.21"),
+            Ok((
+                "\nassets:savings 0.21",
+                Query(vec![QueryTerm::Account("expenses".to_owned())])
+            ))
+        )
+    }
+
+    #[test]
+    fn errors_when_empty() {
+        assert_eq!(
+            parse::<Error<&str>>(""),
+            Err(Err::Error(Error {
+                input: "",
+                code: TakeWhile1
+            }))
+        )
+    }
+}
@@ -0,0 +1,104 @@
+use nom::{
+    bytes::complete::{tag, take_while1},
+    character::complete::char,
+    combinator::map,
+    error::{context, ContextError, ParseError},
+    multi::separated_list1,
+    sequence::{preceded, separated_pair},
+    IResult,
+};
+
+use crate::parser::ast::Metadata;
+
+/// Parses a list of comma separated `key:value` pairs that begin with a ;. Keys can only include
+/// alphanumeric characters and hyphens, while values can also include periods, slashes and
+/// underscores.
+pub fn parse<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, Metadata, E> {
+    context(
+        "metadata",
+        map(
+            preceded(
+                tag(";"),
+                separated_list1(
+                    tag(","),
+                    separated_pair(
+                        take_while1(|c: char| c.is_alphanumeric() || c == '-'),
+                        char(':'),
+                        take_while1(|c: char| {
+                            c.is_alphanumeric() || c == '-' || c == '.' || c == '/' || c == '_'
+                        }),
+                    ),
+                ),
+            ),
+            |entries| {
+                entries
+                    .into_iter()
+                    .map(|(key, value): (&str, &str)| (key.to_owned(), value.to_owned()))
+                    .collect()
+            },
+        ),
+    )(input)
+}
+
+#[cfg(test)]
+mod test {
+    use nom::{
+        error::Error,
+        error::ErrorKind::{Char, Tag},
+        Err,
+    };
+
+    use super::parse;
+
+    use crate::parser::ast::Metadata;
+
+    fn metadata(entries: &[(&str, &str)]) -> Metadata {
+        entries
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_single_entry() {
+        assert_eq!(
+            parse::<Error<&str>>(";import-id:ABC123"),
+            Ok(("", metadata(&[("import-id", "ABC123")])))
+        )
+    }
+
+    #[test]
+    fn parses_multiple_entries() {
+        assert_eq!(
+            parse::<Error<&str>>(";import-id:2021.10/07_1,bank:ing"),
+            Ok((
+                "",
+                metadata(&[("import-id", "2021.10/07_1"), ("bank", "ing")])
+            ))
+        )
+    }
+
+    #[test]
+    fn errors_when_input_does_not_begin_with_semicolon() {
+        assert_eq!(
+            parse::<Error<&str>>("import-id:ABC123"),
+            Err(Err::Error(Error {
+                input: "import-id:ABC123",
+                code: Tag
+            }))
+        );
+    }
+
+    #[test]
+    fn errors_when_entry_has_no_value() {
+        assert_eq!(
+            parse::<Error<&str>>(";food"),
+            Err(Err::Error(Error {
+                input: "",
+                code: Char
+            }))
+        );
+    }
+}
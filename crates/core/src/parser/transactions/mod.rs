@@ -1,10 +1,11 @@
+pub mod metadata;
 pub mod payee_description_section;
 pub mod posting;
 pub mod status;
 pub mod tags;
 
 use nom::character::complete::{line_ending, space0};
-use nom::combinator::{map, opt};
+use nom::combinator::{cut, map, opt};
 use nom::error::{context, ContextError, FromExternalError, ParseError};
 use nom::sequence::{preceded, tuple};
 use nom::IResult;
@@ -23,13 +24,16 @@ pub fn parse<
         map(
             tuple((
                 date::parse,
-                status::parse,
-                payee_description_section::parse,
-                preceded(space0, opt(tags::parse)),
-                line_ending,
-                posting::parse_multiple,
+                cut(tuple((
+                    status::parse,
+                    payee_description_section::parse,
+                    preceded(space0, opt(tags::parse)),
+                    preceded(space0, opt(metadata::parse)),
+                    line_ending,
+                    posting::parse_multiple,
+                ))),
             )),
-            |(date, status, payee_description_section, tags, _, postings)| {
+            |(date, (status, payee_description_section, tags, metadata, _, postings))| {
                 let (payee, description) = match payee_description_section {
                     PayeeSectionType::Empty => ("".to_owned(), "".to_owned()),
                     PayeeSectionType::PayeeOnly(payee) => (payee, "".to_owned()),
@@ -44,7 +48,9 @@ pub fn parse<
                     payee,
                     description,
                     tags: tags.unwrap_or_else(Vec::new),
+                    metadata: metadata.unwrap_or_default(),
                     postings,
+                    automated_postings: Vec::new(),
                 }
             },
         ),
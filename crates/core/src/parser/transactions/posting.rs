@@ -1,12 +1,16 @@
+use chrono::NaiveDate;
 use nom::{
-    character::complete::{line_ending, space0},
-    combinator::{map, opt},
+    character::complete::{char, digit1, line_ending, space0, space1},
+    combinator::{map, map_opt, opt, recognize},
     error::{context, ContextError, ParseError},
-    sequence::{terminated, tuple},
+    sequence::{delimited, preceded, terminated, tuple},
     IResult,
 };
 
-use crate::parser::{account, amount, ast::Posting};
+use crate::parser::{
+    account, amount,
+    ast::{Amount, Lot, Posting},
+};
 
 /// Attempts to parse multiple postings divided by a line ending.
 pub fn parse_multiple<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
@@ -22,19 +26,74 @@ pub fn parse_multiple<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
 }
 
 /// Attempts to parse a posting, ignoring any white space that comes before and stopping once a line
-/// ending is found.
+/// ending is found. The amount can be followed by the lot of its units and by the price of every
+/// unit, as in `assets:broker 10 VWCE {95.20 EUR} [2021-10-07] @ 110 EUR`.
 pub fn parse_one<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
     input: &'a str,
 ) -> IResult<&'a str, Posting, E> {
     context(
         "posting",
         map(
-            tuple((terminated(account::parse, space0), opt(amount::parse))),
-            |(account, amount)| Posting { account, amount },
+            tuple((
+                terminated(account::parse, space0),
+                opt(tuple((
+                    amount::parse,
+                    opt(preceded(space1, lot)),
+                    opt(preceded(space1, price)),
+                ))),
+            )),
+            |(account, valued_amount)| match valued_amount {
+                Some((amount, lot, price)) => Posting {
+                    account,
+                    amount: Some(amount),
+                    lot,
+                    price,
+                },
+                None => Posting {
+                    account,
+                    amount: None,
+                    lot: None,
+                    price: None,
+                },
+            },
+        ),
+    )(input)
+}
+
+/// Parses the cost of every unit between braces, optionally followed by the date in which the lot
+/// was acquired between brackets.
+fn lot<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, Lot, E> {
+    context(
+        "lot",
+        map(
+            tuple((
+                delimited(
+                    char('{'),
+                    delimited(space0, amount::parse, space0),
+                    char('}'),
+                ),
+                opt(preceded(space1, delimited(char('['), lot_date, char(']')))),
+            )),
+            |(cost, date)| Lot { cost, date },
         ),
     )(input)
 }
 
+fn lot_date<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, NaiveDate, E> {
+    map_opt(
+        recognize(tuple((digit1, char('-'), digit1, char('-'), digit1))),
+        |date: &str| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok(),
+    )(input)
+}
+
+fn price<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, Amount, E> {
+    context("price", preceded(tuple((char('@'), space1)), amount::parse))(input)
+}
+
 #[cfg(test)]
 mod test {
     use nom::{
@@ -44,9 +103,11 @@ mod test {
     };
     use rust_decimal_macros::dec;
 
+    use chrono::NaiveDate;
+
     use super::{parse_multiple, parse_one};
 
-    use crate::parser::ast::{Account, Amount, Posting};
+    use crate::parser::ast::{Account, Amount, Lot, Posting};
 
     fn get_test_data() -> (Account, Account, Amount) {
         let sender_account = Account {
@@ -74,7 +135,9 @@ mod test {
                 "",
                 Posting {
                     account: receiver_account,
-                    amount: Some(amount)
+                    amount: Some(amount),
+                    lot: None,
+                    price: None,
                 }
             ))
         )
@@ -90,14 +153,18 @@ mod test {
                 (
                     Posting {
                         account: receiver_account,
-                        amount: Some(amount)
+                        amount: Some(amount),
+                        lot: None,
+                        price: None,
                     },
                     Posting {
                         account: sender_account,
                         amount: Some(Amount {
                             quantity: dec!(-4.05),
                             currency: "USD".to_owned()
-                        })
+                        }),
+                        lot: None,
+                        price: None,
                     }
                 )
             ))
@@ -114,11 +181,15 @@ mod test {
                 (
                     Posting {
                         account: receiver_account,
-                        amount: Some(amount)
+                        amount: Some(amount),
+                        lot: None,
+                        price: None,
                     },
                     Posting {
                         account: sender_account,
-                        amount: None
+                        amount: None,
+                        lot: None,
+                        price: None,
                     }
                 )
             ))
@@ -135,17 +206,55 @@ mod test {
                 (
                     Posting {
                         account: receiver_account,
-                        amount: Some(amount)
+                        amount: Some(amount),
+                        lot: None,
+                        price: None,
                     },
                     Posting {
                         account: sender_account,
-                        amount: None
+                        amount: None,
+                        lot: None,
+                        price: None,
                     }
                 )
             ))
         )
     }
 
+    #[test]
+    fn parses_posting_with_lot_and_price() {
+        let (_, receiver_account, _) = get_test_data();
+        assert_eq!(
+            parse_one::<Error<&str>>("test:receiver -5 VWCE {95.20 EUR} [2021-10-07] @ 110 EUR"),
+            Ok((
+                "",
+                Posting {
+                    account: receiver_account.clone(),
+                    amount: Some(Amount {
+                        quantity: dec!(-5),
+                        currency: "VWCE".to_owned()
+                    }),
+                    lot: Some(Lot {
+                        cost: Amount {
+                            quantity: dec!(95.20),
+                            currency: "EUR".to_owned()
+                        },
+                        date: NaiveDate::from_ymd_opt(2021, 10, 7),
+                    }),
+                    price: Some(Amount {
+                        quantity: dec!(110),
+                        currency: "EUR".to_owned()
+                    }),
+                }
+            ))
+        );
+        assert_eq!(
+            parse_one::<Error<&str>>("test:receiver  10 VWCE { 95.20 EUR }")
+                .map(|(_, posting)| posting.lot.map(|lot| lot.date)),
+            Ok(Some(None))
+        );
+    }
+
     #[test]
     fn errors_if_posting_begins_with_space() {
         assert_eq!(
@@ -1,16 +1,18 @@
 use nom::{
     bytes::complete::{tag, take_while1},
-    combinator::map,
+    character::complete::char,
+    combinator::{map, not},
     error::{context, ContextError, ParseError},
     multi::separated_list1,
-    sequence::preceded,
+    sequence::{preceded, terminated},
     IResult,
 };
 
 use crate::parser::ast::Tag;
 
 /// Parses a list of comma separated tags that begin with a ;. Tags can only include alphanumeric
-/// characters and hyphens to separate words.
+/// characters and hyphens to separate words, and cannot be followed by a colon since that denotes
+/// metadata.
 pub fn parse<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
     input: &'a str,
 ) -> IResult<&'a str, Vec<Tag>, E> {
@@ -21,7 +23,10 @@ pub fn parse<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
                 tag(";"),
                 separated_list1(
                     tag(","),
-                    take_while1(|c: char| c.is_alphanumeric() || c == '-'),
+                    terminated(
+                        take_while1(|c: char| c.is_alphanumeric() || c == '-'),
+                        not(char(':')),
+                    ),
                 ),
             ),
             |tags| tags.into_iter().map(String::from).collect(),
@@ -31,7 +36,11 @@ pub fn parse<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
 
 #[cfg(test)]
 mod test {
-    use nom::{error::Error, error::ErrorKind::Tag, Err};
+    use nom::{
+        error::Error,
+        error::ErrorKind::{Not, Tag},
+        Err,
+    };
 
     use super::parse;
 
@@ -97,6 +106,17 @@ mod test {
         );
     }
 
+    #[test]
+    fn errors_when_tag_is_followed_by_colon() {
+        assert_eq!(
+            parse::<Error<&str>>(";import-id:ABC123"),
+            Err(Err::Error(Error {
+                input: ":ABC123",
+                code: Not
+            }))
+        );
+    }
+
     #[test]
     fn ignores_rest_of_input_if_contains_spaces() {
         assert_eq!(
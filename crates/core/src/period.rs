@@ -0,0 +1,126 @@
+use std::fmt::{self, Display};
+
+use chrono::{Datelike, NaiveDate};
+
+/// Defines the span of time that a report is restricted to.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Period {
+    All,
+    Year(i32),
+    /// Represents a month of a year, with months starting at 1.
+    Month(i32, u32),
+}
+
+impl Period {
+    /// Returns the year that contains the given date.
+    pub fn year_of(date: NaiveDate) -> Self {
+        Period::Year(date.year())
+    }
+
+    /// Returns the month that contains the given date.
+    pub fn month_of(date: NaiveDate) -> Self {
+        Period::Month(date.year(), date.month())
+    }
+
+    /// Checks whether the given date is part of the period.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        match self {
+            Period::All => true,
+            Period::Year(year) => date.year() == *year,
+            Period::Month(year, month) => date.year() == *year && date.month() == *month,
+        }
+    }
+
+    /// Returns the last day of the period, if it has one.
+    pub fn last_day(&self) -> Option<NaiveDate> {
+        match self {
+            Period::All => None,
+            Period::Year(year) => NaiveDate::from_ymd_opt(*year, 12, 31),
+            Period::Month(_, _) => self
+                .next()
+                .first_day()
+                .and_then(|first_day| first_day.pred_opt()),
+        }
+    }
+
+    /// Returns the first day of the period, if it has one.
+    pub fn first_day(&self) -> Option<NaiveDate> {
+        match self {
+            Period::All => None,
+            Period::Year(year) => NaiveDate::from_ymd_opt(*year, 1, 1),
+            Period::Month(year, month) => NaiveDate::from_ymd_opt(*year, *month, 1),
+        }
+    }
+
+    /// Returns the period of the same length that precedes this one.
+    pub fn previous(&self) -> Self {
+        match self {
+            Period::All => Period::All,
+            Period::Year(year) => Period::Year(year - 1),
+            Period::Month(year, 1) => Period::Month(year - 1, 12),
+            Period::Month(year, month) => Period::Month(*year, month - 1),
+        }
+    }
+
+    /// Returns the period of the same length that follows this one.
+    pub fn next(&self) -> Self {
+        match self {
+            Period::All => Period::All,
+            Period::Year(year) => Period::Year(year + 1),
+            Period::Month(year, 12) => Period::Month(year + 1, 1),
+            Period::Month(year, month) => Period::Month(*year, month + 1),
+        }
+    }
+}
+
+impl Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Period::All => write!(f, "all"),
+            Period::Year(year) => write!(f, "{}", year),
+            Period::Month(year, month) => write!(f, "{}-{:02}", year, month),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use super::Period;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn contains_dates_of_the_period() {
+        assert!(Period::All.contains(date(1999, 1, 1)));
+        assert!(Period::Year(2021).contains(date(2021, 12, 31)));
+        assert!(!Period::Year(2021).contains(date(2022, 1, 1)));
+        assert!(Period::Month(2021, 10).contains(date(2021, 10, 7)));
+        assert!(!Period::Month(2021, 10).contains(date(2020, 10, 7)));
+    }
+
+    #[test]
+    fn moves_between_periods_across_years() {
+        assert_eq!(Period::Month(2021, 1).previous(), Period::Month(2020, 12));
+        assert_eq!(Period::Month(2021, 12).next(), Period::Month(2022, 1));
+        assert_eq!(Period::Year(2021).next(), Period::Year(2022));
+        assert_eq!(Period::All.previous(), Period::All);
+    }
+
+    #[test]
+    fn returns_first_and_last_day() {
+        assert_eq!(Period::Month(2020, 2).last_day(), Some(date(2020, 2, 29)));
+        assert_eq!(Period::Month(2021, 12).last_day(), Some(date(2021, 12, 31)));
+        assert_eq!(Period::Year(2021).first_day(), Some(date(2021, 1, 1)));
+        assert_eq!(Period::All.last_day(), None);
+    }
+
+    #[test]
+    fn displays_periods() {
+        assert_eq!(Period::Month(2021, 3).to_string(), "2021-03");
+        assert_eq!(Period::Year(2021).to_string(), "2021");
+    }
+}
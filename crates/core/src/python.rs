@@ -0,0 +1,263 @@
+use pyo3::create_exception;
+use pyo3::exceptions::{PyFileNotFoundError, PyIOError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::error::Error;
+use crate::parser::ast::{Posting, Transaction};
+use crate::report::{postings, report_status};
+use crate::Journal;
+
+create_exception!(
+    pledger,
+    ParseError,
+    PyValueError,
+    "Raised when a journal or query can't be parsed."
+);
+
+/// Columns of the table of postings, in the order of `Journal.to_arrow`.
+const POSTING_COLUMNS: [&str; 10] = [
+    "date",
+    "status",
+    "payee",
+    "description",
+    "account",
+    "quantity",
+    "currency",
+    "tags",
+    "automated",
+    "transaction",
+];
+
+/// Journal loaded from Python, whose transactions are already resolved. Dates are returned as
+/// `datetime.date` and quantities as `decimal.Decimal`, so that no precision is lost.
+#[pyclass(name = "Journal", module = "pledger", frozen)]
+struct PyJournal(Journal);
+
+#[pymethods]
+impl PyJournal {
+    /// Returns the transactions as dictionaries, each one with the list of its postings.
+    fn transactions<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let transactions = self
+            .0
+            .transactions()
+            .iter()
+            .map(|transaction| transaction_dict(py, transaction))
+            .collect::<PyResult<Vec<_>>>()?;
+
+        PyList::new(py, transactions)
+    }
+
+    /// Returns a dictionary for every posting, with the columns of its transaction, so that it can
+    /// be given to `pandas.DataFrame`.
+    fn postings<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let rows = self
+            .posting_rows(py)?
+            .into_iter()
+            .map(|row| {
+                let dict = PyDict::new(py);
+                POSTING_COLUMNS
+                    .iter()
+                    .zip(row)
+                    .try_for_each(|(column, value)| dict.set_item(column, value))?;
+                Ok(dict)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+
+        PyList::new(py, rows)
+    }
+
+    /// Returns the postings as a `pyarrow.Table`, which requires pyarrow to be installed.
+    fn to_arrow<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let rows = self.posting_rows(py)?;
+        let columns = PyDict::new(py);
+        for (index, column) in POSTING_COLUMNS.iter().enumerate() {
+            let values = rows.iter().map(|row| row[index].clone());
+            columns.set_item(column, PyList::new(py, values)?)?;
+        }
+
+        py.import("pyarrow")?.call_method1("table", (columns,))
+    }
+
+    /// Returns the accounts of the journal together with their parents.
+    fn accounts(&self) -> Vec<String> {
+        self.0.accounts()
+    }
+
+    /// Returns the payees of the transactions.
+    fn payees(&self) -> Vec<String> {
+        self.0.payees()
+    }
+
+    /// Returns the tags of the transactions.
+    fn tags(&self) -> Vec<String> {
+        self.0.tags()
+    }
+
+    /// Returns the currencies and commodities of the journal.
+    fn commodities(&self) -> Vec<String> {
+        self.0.commodities()
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.transactions().len()
+    }
+}
+
+impl PyJournal {
+    /// Returns the values of the posting columns for every posting of the journal. Postings added
+    /// by automated transactions are flagged, and every posting has the index of its transaction.
+    fn posting_rows<'py>(&self, py: Python<'py>) -> PyResult<Vec<Vec<Bound<'py, PyAny>>>> {
+        let mut rows = vec![];
+
+        for (index, transaction) in self.0.transactions().iter().enumerate() {
+            for (position, posting) in postings(transaction).iter().enumerate() {
+                let (quantity, currency) = amount_values(py, posting)?;
+                rows.push(vec![
+                    transaction.date.date().into_pyobject(py)?.into_any(),
+                    report_status(&transaction.status)
+                        .into_pyobject(py)?
+                        .into_any(),
+                    transaction.payee.as_str().into_pyobject(py)?.into_any(),
+                    transaction
+                        .description
+                        .as_str()
+                        .into_pyobject(py)?
+                        .into_any(),
+                    posting.account.to_string().into_pyobject(py)?.into_any(),
+                    quantity,
+                    currency,
+                    PyList::new(py, &transaction.tags)?.into_any(),
+                    (position > 1).into_pyobject(py)?.to_owned().into_any(),
+                    index.into_pyobject(py)?.into_any(),
+                ]);
+            }
+        }
+
+        Ok(rows)
+    }
+}
+
+/// Reads and parses the journal in the given path.
+#[pyfunction]
+fn load(path: &str) -> PyResult<PyJournal> {
+    Journal::from_path(path)
+        .map(PyJournal)
+        .map_err(python_error)
+}
+
+/// Parses the journal in the given content.
+#[pyfunction]
+fn parse(content: &str) -> PyResult<PyJournal> {
+    Journal::parse(content).map(PyJournal).map_err(python_error)
+}
+
+/// Reads journals written in the syntax of pledger.
+#[pymodule]
+fn pledger(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyJournal>()?;
+    module.add_function(wrap_pyfunction!(load, module)?)?;
+    module.add_function(wrap_pyfunction!(parse, module)?)?;
+    module.add("ParseError", module.py().get_type::<ParseError>())
+}
+
+fn transaction_dict<'py>(
+    py: Python<'py>,
+    transaction: &Transaction,
+) -> PyResult<Bound<'py, PyDict>> {
+    let postings = postings(transaction)
+        .iter()
+        .enumerate()
+        .map(|(position, posting)| {
+            let (quantity, currency) = amount_values(py, posting)?;
+            let dict = PyDict::new(py);
+            dict.set_item("account", posting.account.to_string())?;
+            dict.set_item("quantity", quantity)?;
+            dict.set_item("currency", currency)?;
+            dict.set_item("automated", position > 1)?;
+            Ok(dict)
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+
+    let dict = PyDict::new(py);
+    dict.set_item("date", transaction.date.date())?;
+    dict.set_item("status", report_status(&transaction.status))?;
+    dict.set_item("payee", &transaction.payee)?;
+    dict.set_item("description", &transaction.description)?;
+    dict.set_item("tags", &transaction.tags)?;
+    dict.set_item("metadata", &transaction.metadata)?;
+    dict.set_item("postings", postings)?;
+    Ok(dict)
+}
+
+/// Returns the quantity and currency of the posting, which are None if it has no amount.
+fn amount_values<'py>(
+    py: Python<'py>,
+    posting: &Posting,
+) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+    Ok(match &posting.amount {
+        Some(amount) => (
+            amount.quantity.into_pyobject(py)?.into_any(),
+            amount.currency.as_str().into_pyobject(py)?.into_any(),
+        ),
+        None => (py.None().into_bound(py), py.None().into_bound(py)),
+    })
+}
+
+fn python_error(error: Error) -> PyErr {
+    match error {
+        Error::Io(error) if error.is_not_found() => PyFileNotFoundError::new_err(error.to_string()),
+        Error::Io(error) => PyIOError::new_err(error.to_string()),
+        error => ParseError::new_err(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ffi::CString;
+
+    use pyo3::prelude::*;
+    use pyo3::types::PyDict;
+
+    use super::{parse, ParseError};
+
+    const JOURNAL: &str =
+        "2021-10-05 ! Tesco | Groceries ;food\nexpenses:food 30.50 EUR\nassets:bank\n";
+
+    #[test]
+    fn returns_transactions_and_postings_as_dictionaries() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let journal = Bound::new(py, parse(JOURNAL).unwrap()).unwrap();
+            let locals = PyDict::new(py);
+            locals.set_item("journal", journal).unwrap();
+
+            let code = CString::new(
+                "import datetime, decimal\n\
+                  transaction = journal.transactions()[0]\n\
+                  assert transaction['date'] == datetime.date(2021, 10, 5)\n\
+                  assert transaction['status'] == 'pending'\n\
+                  assert transaction['tags'] == ['food']\n\
+                  assert transaction['postings'][1]['quantity'] == decimal.Decimal('-30.50')\n\
+                  postings = journal.postings()\n\
+                  assert [posting['account'] for posting in postings] == ['expenses:food', 'assets:bank']\n\
+                  assert postings[0]['description'] == 'Groceries'\n\
+                  assert str(postings[0]['quantity']) == '30.50'\n\
+                  assert len(journal) == 1\n",
+            )
+            .unwrap();
+
+            py.run(&code, None, Some(&locals)).unwrap();
+        })
+    }
+
+    #[test]
+    fn raises_parse_errors() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let error = parse("2021-10-05 Tesco\n").err().unwrap();
+
+            assert!(error.is_instance_of::<ParseError>(py));
+        })
+    }
+}
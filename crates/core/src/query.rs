@@ -0,0 +1,120 @@
+use crate::parser::ast::{Posting, Query, QueryTerm, Transaction};
+
+/// Checks whether the given posting of the transaction matches all the terms of the query. Account
+/// terms are checked against the posting while the rest are checked against the transaction.
+pub fn matches_posting(query: &Query, transaction: &Transaction, posting: &Posting) -> bool {
+    query.0.iter().all(|term| match term {
+        QueryTerm::Account(account) => {
+            contains_ignoring_case(&posting.account.to_string(), account)
+        }
+        QueryTerm::Payee(payee) => contains_ignoring_case(&transaction.payee, payee),
+        QueryTerm::Description(description) => {
+            contains_ignoring_case(&transaction.description, description)
+        }
+        QueryTerm::Tag(tag) => transaction.tags.contains(tag),
+    })
+}
+
+/// Checks whether any of the postings of the transaction matches the query.
+pub fn matches_transaction(query: &Query, transaction: &Transaction) -> bool {
+    let (first_posting, second_posting) = &transaction.postings;
+    matches_posting(query, transaction, first_posting)
+        || matches_posting(query, transaction, second_posting)
+}
+
+fn contains_ignoring_case(value: &str, pattern: &str) -> bool {
+    value.to_lowercase().contains(&pattern.to_lowercase())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{matches_posting, matches_transaction};
+
+    use crate::parser::ast::{Account, Posting, Query, QueryTerm, Transaction};
+
+    fn transaction() -> Transaction {
+        Transaction {
+            payee: "Albert".to_owned(),
+            description: "Weekly groceries".to_owned(),
+            tags: vec!["food".to_owned()],
+            postings: (
+                Posting {
+                    account: Account {
+                        name: "expenses".to_owned(),
+                        children: vec!["food".to_owned()],
+                    },
+                    amount: None,
+                    lot: None,
+                    price: None,
+                },
+                Posting {
+                    account: Account {
+                        name: "assets".to_owned(),
+                        children: vec!["bank".to_owned()],
+                    },
+                    amount: None,
+                    lot: None,
+                    price: None,
+                },
+            ),
+            ..Transaction::default()
+        }
+    }
+
+    #[test]
+    fn matches_posting_by_account_ignoring_case() {
+        let transaction = transaction();
+        let query = Query(vec![QueryTerm::Account("Expenses:Food".to_owned())]);
+
+        assert!(matches_posting(
+            &query,
+            &transaction,
+            &transaction.postings.0
+        ));
+        assert!(!matches_posting(
+            &query,
+            &transaction,
+            &transaction.postings.1
+        ));
+    }
+
+    #[test]
+    fn matches_posting_only_when_all_terms_match() {
+        let transaction = transaction();
+        let matching_query = Query(vec![
+            QueryTerm::Account("food".to_owned()),
+            QueryTerm::Payee("albert".to_owned()),
+            QueryTerm::Description("groceries".to_owned()),
+            QueryTerm::Tag("food".to_owned()),
+        ]);
+        let non_matching_query = Query(vec![
+            QueryTerm::Account("food".to_owned()),
+            QueryTerm::Tag("travel".to_owned()),
+        ]);
+
+        assert!(matches_posting(
+            &matching_query,
+            &transaction,
+            &transaction.postings.0
+        ));
+        assert!(!matches_posting(
+            &non_matching_query,
+            &transaction,
+            &transaction.postings.0
+        ));
+    }
+
+    #[test]
+    fn matches_transaction_when_any_posting_matches() {
+        let transaction = transaction();
+
+        assert!(matches_transaction(
+            &Query(vec![QueryTerm::Account("assets".to_owned())]),
+            &transaction
+        ));
+        assert!(!matches_transaction(
+            &Query(vec![QueryTerm::Account("income".to_owned())]),
+            &transaction
+        ));
+    }
+}
@@ -0,0 +1,361 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::error::OverflowError;
+use crate::parser::ast::{Amount, CurrencyCode, JournalElement, Transaction, TransactionStatus};
+use crate::parser::parse_journal_with_spans;
+use crate::report::{add_to, belongs_to, postings};
+
+/// Maximum number of combinations of pending postings that are tried when looking for the ones
+/// that explain the difference with the statement, so that big journals don't hang.
+const MAX_SUGGESTION_STEPS: usize = 1_000_000;
+
+/// Represents a posting to the reconciled account made by a pending transaction.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PendingPosting {
+    /// Position of the transaction among the transactions of the journal.
+    pub index: usize,
+    pub transaction: Transaction,
+    pub amount: Amount,
+}
+
+/// Represents the comparison between the cleared balance of an account and the balance of its
+/// statement.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Reconciliation {
+    pub cleared_balance: Amount,
+    pub statement_balance: Amount,
+    /// Amount that the cleared balance is missing to match the statement balance.
+    pub difference: Amount,
+    pub pending: Vec<PendingPosting>,
+    /// Positions of the pending postings whose amounts add up to the difference, if any
+    /// combination of them does.
+    pub suggestion: Option<Vec<usize>>,
+}
+
+/// Returns the currencies of the postings to the given account, sorted and without repetitions.
+pub fn currencies(transactions: &[Transaction], account: &str) -> Vec<CurrencyCode> {
+    let mut currencies: Vec<CurrencyCode> = transactions
+        .iter()
+        .flat_map(postings)
+        .filter(|posting| belongs_to(posting, account))
+        .filter_map(|posting| posting.amount.map(|amount| amount.currency))
+        .collect();
+
+    currencies.sort();
+    currencies.dedup();
+    currencies
+}
+
+/// Compares the balance of the cleared postings to the given account or any of its children up to
+/// the given date with the balance of the statement, which also sets the currency of the postings
+/// that are taken into account. Postings of pending transactions are listed separately together
+/// with a suggestion of which of them explain the difference. Returns an error if the balances
+/// are too large to be computed.
+pub fn reconcile(
+    transactions: &[Transaction],
+    account: &str,
+    statement_balance: &Amount,
+    date: NaiveDate,
+) -> Result<Reconciliation, OverflowError> {
+    let mut cleared_balance = Decimal::ZERO;
+    let mut pending = Vec::new();
+
+    for (index, transaction) in transactions
+        .iter()
+        .enumerate()
+        .filter(|(_, transaction)| transaction.date.date() <= date)
+    {
+        for posting in postings(transaction) {
+            let amount = match posting.amount {
+                Some(amount)
+                    if belongs_to(&posting, account)
+                        && amount.currency == statement_balance.currency =>
+                {
+                    amount
+                }
+                _ => continue,
+            };
+
+            match transaction.status {
+                TransactionStatus::Cleared => add_to(&mut cleared_balance, amount.quantity)?,
+                TransactionStatus::Pending => pending.push(PendingPosting {
+                    index,
+                    transaction: transaction.clone(),
+                    amount,
+                }),
+            }
+        }
+    }
+
+    let difference = statement_balance
+        .quantity
+        .checked_sub(cleared_balance)
+        .ok_or(OverflowError)?;
+    let quantities: Vec<Decimal> = pending
+        .iter()
+        .map(|pending_posting| pending_posting.amount.quantity)
+        .collect();
+
+    Ok(Reconciliation {
+        cleared_balance: Amount {
+            quantity: cleared_balance,
+            currency: statement_balance.currency.clone(),
+        },
+        statement_balance: statement_balance.clone(),
+        difference: Amount {
+            quantity: difference,
+            currency: statement_balance.currency.clone(),
+        },
+        pending,
+        suggestion: suggest(&quantities, difference),
+    })
+}
+
+/// Marks the transactions in the given positions among the transactions of the journal as
+/// cleared, removing their `!` and keeping the rest of the content as it was. Returns an error if
+/// the journal can't be parsed.
+pub fn clear(content: &str, indexes: &[usize]) -> Result<String, String> {
+    edit_headers(content, indexes, |line, _| without_pending_mark(line))
+}
+
+/// Switches the status of the transaction in the given position among the transactions of the
+/// journal between pending and cleared, keeping the rest of the content as it was. Returns an
+/// error if the journal can't be parsed.
+pub fn toggle_status(content: &str, index: usize) -> Result<String, String> {
+    edit_headers(content, &[index], |line, transaction| {
+        match transaction.status {
+            TransactionStatus::Pending => without_pending_mark(line),
+            TransactionStatus::Cleared => with_pending_mark(line),
+        }
+    })
+}
+
+/// Applies the edit to the first line of the transactions in the given positions.
+fn edit_headers(
+    content: &str,
+    indexes: &[usize],
+    edit: impl Fn(&str, &Transaction) -> String,
+) -> Result<String, String> {
+    let headers: Vec<(usize, Transaction)> = parse_journal_with_spans(content)
+        .map_err(|failure| failure.message)?
+        .into_iter()
+        .filter_map(|(element, span)| match element {
+            JournalElement::Transaction(transaction) => Some((span.start.line, transaction)),
+            _ => None,
+        })
+        .enumerate()
+        .filter(|(index, _)| indexes.contains(index))
+        .map(|(_, header)| header)
+        .collect();
+
+    Ok(content
+        .split_inclusive('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            match headers
+                .iter()
+                .find(|(header_line, _)| *header_line == index + 1)
+            {
+                Some((_, transaction)) => edit(line, transaction),
+                None => line.to_owned(),
+            }
+        })
+        .collect())
+}
+
+fn without_pending_mark(line: &str) -> String {
+    match line.find('!') {
+        Some(position) => {
+            let rest = &line[position + 1..];
+            let rest = rest.strip_prefix(' ').unwrap_or(rest);
+            format!("{}{}", &line[..position], rest)
+        }
+        None => line.to_owned(),
+    }
+}
+
+/// Adds the `!` right after the date, which is the first word of the line.
+fn with_pending_mark(line: &str) -> String {
+    let date_end = line.find(char::is_whitespace).unwrap_or(line.len());
+    format!("{} !{}", &line[..date_end], &line[date_end..])
+}
+
+/// Looks for the smallest combination of quantities that adds up to the target, trying
+/// combinations of one quantity first, then two and so on.
+fn suggest(quantities: &[Decimal], target: Decimal) -> Option<Vec<usize>> {
+    let mut steps = 0;
+
+    (0..=quantities.len()).find_map(|size| {
+        let mut selected = Vec::with_capacity(size);
+        find_combination(quantities, target, size, 0, &mut selected, &mut steps)
+    })
+}
+
+fn find_combination(
+    quantities: &[Decimal],
+    remaining: Decimal,
+    size: usize,
+    start: usize,
+    selected: &mut Vec<usize>,
+    steps: &mut usize,
+) -> Option<Vec<usize>> {
+    if selected.len() == size {
+        return if remaining.is_zero() {
+            Some(selected.clone())
+        } else {
+            None
+        };
+    }
+
+    for index in start..quantities.len() {
+        *steps += 1;
+        if *steps > MAX_SUGGESTION_STEPS {
+            return None;
+        }
+
+        // Combinations whose sum can't be computed can't add up to the target either.
+        let remaining = match remaining.checked_sub(quantities[index]) {
+            Some(remaining) => remaining,
+            None => continue,
+        };
+
+        selected.push(index);
+        let combination = find_combination(quantities, remaining, size, index + 1, selected, steps);
+        selected.pop();
+
+        if combination.is_some() {
+            return combination;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    use super::{clear, currencies, reconcile, toggle_status};
+
+    use crate::error::OverflowError;
+    use crate::journal::resolve;
+    use crate::parser::ast::{Amount, Transaction};
+    use crate::parser::parse_journal;
+
+    const JOURNAL: &str = "2021-10-01 ACME\nincome:salary -1000 EUR\nassets:bank\n\n\
+                           2021-10-05 ! Tesco\nexpenses:food 30 EUR\nassets:bank\n\n\
+                           2021-10-06 ! Aldi\nexpenses:food 12.50 EUR\nassets:bank\n\n\
+                           2021-11-02 ! Lidl\nexpenses:food 7 EUR\nassets:bank\n";
+
+    fn transactions() -> Vec<Transaction> {
+        resolve(parse_journal(JOURNAL).unwrap())
+    }
+
+    fn eur(quantity: Decimal) -> Amount {
+        Amount {
+            quantity,
+            currency: "EUR".to_owned(),
+        }
+    }
+
+    #[test]
+    fn compares_cleared_balance_with_statement() {
+        let reconciliation = reconcile(
+            &transactions(),
+            "assets",
+            &eur(dec!(970)),
+            NaiveDate::from_ymd_opt(2021, 10, 31).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(reconciliation.cleared_balance, eur(dec!(1000)));
+        assert_eq!(reconciliation.difference, eur(dec!(-30)));
+        assert_eq!(
+            reconciliation
+                .pending
+                .iter()
+                .map(|pending_posting| (pending_posting.index, pending_posting.amount.clone()))
+                .collect::<Vec<(usize, Amount)>>(),
+            vec![(1, eur(dec!(-30))), (2, eur(dec!(-12.50)))]
+        );
+        assert_eq!(reconciliation.suggestion, Some(vec![0]));
+    }
+
+    #[test]
+    fn suggests_combinations_of_pending_postings() {
+        let reconciliation = reconcile(
+            &transactions(),
+            "assets:bank",
+            &eur(dec!(950.50)),
+            NaiveDate::from_ymd_opt(2021, 12, 31).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(reconciliation.suggestion, Some(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn does_not_suggest_when_no_combination_explains_difference() {
+        let reconciliation = reconcile(
+            &transactions(),
+            "assets:bank",
+            &eur(dec!(1)),
+            NaiveDate::from_ymd_opt(2021, 12, 31).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(reconciliation.suggestion, None);
+    }
+
+    #[test]
+    fn fails_when_the_cleared_balance_is_too_large_to_be_computed() {
+        let transactions = resolve(
+            parse_journal(&format!(
+                "2021-10-01 ACME\nassets:bank {max} EUR\nincome:salary\n\n\
+                 2021-10-02 ACME\nassets:bank {max} EUR\nincome:salary\n",
+                max = Decimal::MAX
+            ))
+            .unwrap(),
+        );
+
+        assert_eq!(
+            reconcile(
+                &transactions,
+                "assets:bank",
+                &eur(dec!(1)),
+                NaiveDate::from_ymd_opt(2021, 12, 31).unwrap(),
+            ),
+            Err(OverflowError)
+        )
+    }
+
+    #[test]
+    fn returns_currencies_of_account() {
+        assert_eq!(
+            currencies(&transactions(), "assets:bank"),
+            vec!["EUR".to_owned()]
+        )
+    }
+
+    #[test]
+    fn clears_selected_transactions_keeping_the_rest_of_the_journal() {
+        let content = format!("// Bank\n{}", JOURNAL);
+
+        assert_eq!(
+            clear(&content, &[1, 3]).unwrap(),
+            content
+                .replace("2021-10-05 ! Tesco", "2021-10-05 Tesco")
+                .replace("2021-11-02 ! Lidl", "2021-11-02 Lidl")
+        )
+    }
+
+    #[test]
+    fn toggles_status_of_transaction() {
+        let toggled = toggle_status(JOURNAL, 0).unwrap();
+        assert!(toggled.starts_with("2021-10-01 ! ACME\n"));
+        assert_eq!(toggle_status(&toggled, 0).unwrap(), JOURNAL);
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::BTreeMap;
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::error::OverflowError;
+use crate::parser::ast::{Query, Transaction};
+use crate::query::matches_posting;
+
+use super::{add_to, postings, ReportAmount};
+
+/// Represents the balance of an account in a single currency.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct BalanceRow {
+    pub account: String,
+    pub quantity: String,
+    pub currency: String,
+}
+
+/// Represents the balance of every account and the total of all the postings that were included.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct BalanceReport {
+    pub rows: Vec<BalanceRow>,
+    pub totals: Vec<ReportAmount>,
+}
+
+/// Computes the balance of the accounts of the postings that match the query. The balance of an
+/// account includes the balance of all of its children, so every parent account has its own row.
+/// Fails when a balance is too large to be computed.
+pub fn report(transactions: &[Transaction], query: &Query) -> Result<BalanceReport, OverflowError> {
+    let mut balances: BTreeMap<(String, String), Decimal> = BTreeMap::new();
+    let mut totals: BTreeMap<String, Decimal> = BTreeMap::new();
+
+    for transaction in transactions {
+        for posting in postings(transaction) {
+            let amount = match &posting.amount {
+                Some(amount) if matches_posting(query, transaction, &posting) => amount,
+                _ => continue,
+            };
+
+            let segments: Vec<&String> = std::iter::once(&posting.account.name)
+                .chain(posting.account.children.iter())
+                .collect();
+
+            for depth in 1..=segments.len() {
+                let account = segments[..depth]
+                    .iter()
+                    .map(|segment| segment.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(":");
+
+                add_to(
+                    balances
+                        .entry((account, amount.currency.clone()))
+                        .or_default(),
+                    amount.quantity,
+                )?;
+            }
+
+            add_to(
+                totals.entry(amount.currency.clone()).or_default(),
+                amount.quantity,
+            )?;
+        }
+    }
+
+    Ok(BalanceReport {
+        rows: balances
+            .into_iter()
+            .map(|((account, currency), quantity)| BalanceRow {
+                account,
+                quantity: quantity.to_string(),
+                currency,
+            })
+            .collect(),
+        totals: totals
+            .into_iter()
+            .map(|(currency, quantity)| ReportAmount {
+                quantity: quantity.to_string(),
+                currency,
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal::Decimal;
+
+    use super::{report, BalanceRow};
+
+    use crate::error::OverflowError;
+    use crate::journal::resolve;
+    use crate::parser::ast::*;
+    use crate::parser::parse_journal;
+    use crate::report::ReportAmount;
+
+    const JOURNAL: &str = "2021-10-07 Tesco\nexpenses:food 25.30 EUR\nassets:bank\n\n\
+                           2021-10-08 Ikea\nexpenses:home 10 EUR\nassets:bank\n";
+
+    fn transactions(content: &str) -> Vec<Transaction> {
+        resolve(parse_journal(content).unwrap())
+    }
+
+    fn row(account: &str, quantity: &str) -> BalanceRow {
+        BalanceRow {
+            account: account.to_owned(),
+            quantity: quantity.to_owned(),
+            currency: "EUR".to_owned(),
+        }
+    }
+
+    #[test]
+    fn computes_balance_of_accounts_and_their_parents() {
+        let transactions = transactions(JOURNAL);
+
+        let balance = report(&transactions, &Query(vec![])).unwrap();
+
+        assert_eq!(
+            balance.rows,
+            vec![
+                row("assets", "-35.30"),
+                row("assets:bank", "-35.30"),
+                row("expenses", "35.30"),
+                row("expenses:food", "25.30"),
+                row("expenses:home", "10"),
+            ]
+        );
+        assert_eq!(
+            balance.totals,
+            vec![ReportAmount {
+                quantity: "0".to_owned(),
+                currency: "EUR".to_owned()
+            }]
+        )
+    }
+
+    #[test]
+    fn only_includes_postings_that_match_query() {
+        let transactions = transactions(JOURNAL);
+
+        assert_eq!(
+            report(
+                &transactions,
+                &Query(vec![QueryTerm::Account("food".to_owned())])
+            )
+            .unwrap()
+            .rows,
+            vec![row("expenses", "25.30"), row("expenses:food", "25.30")]
+        )
+    }
+
+    #[test]
+    fn fails_when_a_balance_is_too_large_to_be_computed() {
+        let transactions = transactions(&format!(
+            "2021-10-07 Tesco\nexpenses:food {max} EUR\nassets:bank\n\n\
+             2021-10-08 Tesco\nexpenses:food {max} EUR\nassets:bank\n",
+            max = Decimal::MAX
+        ));
+
+        assert_eq!(report(&transactions, &Query(vec![])), Err(OverflowError))
+    }
+}
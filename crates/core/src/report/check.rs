@@ -0,0 +1,166 @@
+use serde::Serialize;
+
+use crate::journal::Diagnostic;
+
+use super::journal::TransactionReport;
+
+/// Defines how serious a message of the check is.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Represents an error or warning found while checking a journal, with the lines and columns of
+/// the journal where it was found and the transaction that caused it if there is one. The path is
+/// only given when it was found in an included journal.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct CheckMessage {
+    pub code: String,
+    pub severity: Severity,
+    pub kind: String,
+    pub message: String,
+    pub path: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub transaction: Option<TransactionReport>,
+}
+
+/// Represents a message of the check as a flat row, for formats that can't nest the transaction.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct CheckRow {
+    pub code: String,
+    pub severity: Severity,
+    pub kind: String,
+    pub message: String,
+    pub path: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub date: Option<String>,
+    pub payee: Option<String>,
+}
+
+/// Represents the result of checking a journal.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct CheckReport {
+    pub valid: bool,
+    pub messages: Vec<CheckMessage>,
+}
+
+/// Creates a report from the diagnostics found while checking a journal.
+pub fn report(diagnostics: &[Diagnostic]) -> CheckReport {
+    CheckReport {
+        valid: !diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.code.is_error()),
+        messages: diagnostics.iter().map(message).collect(),
+    }
+}
+
+/// Returns the messages of the report as flat rows.
+pub fn rows(report: &CheckReport) -> Vec<CheckRow> {
+    report
+        .messages
+        .iter()
+        .map(|message| CheckRow {
+            code: message.code.clone(),
+            severity: message.severity.clone(),
+            kind: message.kind.clone(),
+            message: message.message.clone(),
+            path: message.path.clone(),
+            line: message.line,
+            column: message.column,
+            date: message
+                .transaction
+                .as_ref()
+                .map(|transaction| transaction.date.clone()),
+            payee: message
+                .transaction
+                .as_ref()
+                .map(|transaction| transaction.payee.clone()),
+        })
+        .collect()
+}
+
+fn message(diagnostic: &Diagnostic) -> CheckMessage {
+    CheckMessage {
+        code: diagnostic.code.code().to_owned(),
+        severity: if diagnostic.code.is_error() {
+            Severity::Error
+        } else {
+            Severity::Warning
+        },
+        kind: diagnostic.code.kind().to_owned(),
+        message: diagnostic.message.clone(),
+        path: diagnostic.path.clone(),
+        line: diagnostic.span.start.line,
+        column: diagnostic.span.start.column,
+        end_line: diagnostic.span.end.line,
+        end_column: diagnostic.span.end.column,
+        transaction: diagnostic.transaction.as_ref().map(TransactionReport::from),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{report, rows, Severity};
+
+    use crate::journal::diagnose;
+
+    #[test]
+    fn reports_valid_journal() {
+        let report = report(&diagnose("2021-10-07 Tesco\nexpenses 10 EUR\nassets\n"));
+
+        assert!(report.valid);
+        assert!(report.messages.is_empty());
+    }
+
+    #[test]
+    fn reports_unbalanced_transactions() {
+        let report = report(&diagnose(
+            "y2021\n\n10-07 Tesco\nexpenses 10 EUR\nassets -5 EUR\n",
+        ));
+
+        assert!(!report.valid);
+        assert_eq!(report.messages.len(), 1);
+
+        let message = &report.messages[0];
+        assert_eq!(message.code, "V002");
+        assert_eq!(message.severity, Severity::Error);
+        assert_eq!(message.kind, "unbalanced-transaction");
+        assert_eq!(
+            message.message,
+            "The transaction is unbalanced. Difference between postings: 5"
+        );
+        assert_eq!(
+            (
+                message.line,
+                message.column,
+                message.end_line,
+                message.end_column
+            ),
+            (3, 1, 5, 13)
+        );
+        assert_eq!(
+            message
+                .transaction
+                .as_ref()
+                .map(|transaction| transaction.date.as_str()),
+            Some("2021-10-07")
+        );
+    }
+
+    #[test]
+    fn rows_contain_transaction_date_and_payee() {
+        let report = report(&diagnose(
+            "2021-10-07 Tesco\nexpenses 10 EUR\nassets 5 EUR\n",
+        ));
+        let rows = rows(&report);
+
+        assert_eq!(rows[0].date, Some("2021-10-07".to_owned()));
+        assert_eq!(rows[0].payee, Some("Tesco".to_owned()));
+    }
+}
@@ -0,0 +1,60 @@
+use serde::Serialize;
+
+use crate::error::OverflowError;
+use crate::lots::{self, Sale};
+
+/// Represents a sale with the cost of the lots it reduced and the gain it realized.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct SaleRow {
+    pub date: String,
+    pub account: String,
+    pub commodity: String,
+    pub quantity: String,
+    pub proceeds: String,
+    pub cost: String,
+    pub gain: String,
+    pub currency: String,
+}
+
+/// Represents the gains realized during a tax year in a currency.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct YearRow {
+    pub year: i32,
+    pub gain: String,
+    pub currency: String,
+}
+
+/// Represents the gains realized by every sale and by every tax year.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct GainsReport {
+    pub sales: Vec<SaleRow>,
+    pub years: Vec<YearRow>,
+}
+
+/// Lists the given sales ordered by date together with the gains of every year. Returns an error if
+/// the gains of a year are too large to be computed.
+pub fn report(sales: &[Sale]) -> Result<GainsReport, OverflowError> {
+    Ok(GainsReport {
+        sales: sales
+            .iter()
+            .map(|sale| SaleRow {
+                date: sale.date.format("%Y-%m-%d").to_string(),
+                account: sale.account.clone(),
+                commodity: sale.commodity.clone(),
+                quantity: sale.quantity.to_string(),
+                proceeds: sale.proceeds.quantity.to_string(),
+                cost: sale.cost.quantity.to_string(),
+                gain: sale.gain.quantity.to_string(),
+                currency: sale.gain.currency.clone(),
+            })
+            .collect(),
+        years: lots::gains_per_year(sales)?
+            .into_iter()
+            .map(|((year, currency), gain)| YearRow {
+                year,
+                gain: gain.to_string(),
+                currency,
+            })
+            .collect(),
+    })
+}
@@ -0,0 +1,142 @@
+use serde::Serialize;
+
+use crate::journal::{resolve, resolve_date};
+use crate::parser::ast::{JournalElement, Metadata, Transaction};
+
+use super::{postings, report_date, report_status, ReportAmount};
+
+/// Represents a posting of a resolved transaction.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct PostingReport {
+    pub account: String,
+    pub amount: Option<ReportAmount>,
+    /// Whether the posting was added by an automated transaction.
+    pub automated: bool,
+}
+
+/// Represents a resolved transaction with all of its postings.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct TransactionReport {
+    pub date: String,
+    pub status: String,
+    pub payee: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub metadata: Metadata,
+    pub postings: Vec<PostingReport>,
+}
+
+/// Represents a price declaration of the journal.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct PriceReport {
+    pub date: String,
+    pub currency: String,
+    pub amount: ReportAmount,
+}
+
+/// Represents the full journal after resolving it.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct JournalReport {
+    pub transactions: Vec<TransactionReport>,
+    pub prices: Vec<PriceReport>,
+}
+
+/// Creates a report of the journal with partial dates given their year, the automated transactions
+/// expanded and the amounts of postings without one resolved.
+pub fn report(journal: Vec<JournalElement>) -> JournalReport {
+    let mut year = None;
+    let prices = journal
+        .iter()
+        .filter_map(|element| match element {
+            JournalElement::Year(journal_year) => {
+                year = Some(*journal_year);
+                None
+            }
+            JournalElement::Price(price) => Some(PriceReport {
+                date: report_date(&resolve_date(price.date.clone(), year)),
+                currency: price.currency.clone(),
+                amount: ReportAmount::from(&price.amount),
+            }),
+            _ => None,
+        })
+        .collect();
+
+    JournalReport {
+        transactions: resolve(journal)
+            .iter()
+            .map(TransactionReport::from)
+            .collect(),
+        prices,
+    }
+}
+
+impl From<&Transaction> for TransactionReport {
+    fn from(transaction: &Transaction) -> Self {
+        Self {
+            date: report_date(&transaction.date),
+            status: report_status(&transaction.status),
+            payee: transaction.payee.clone(),
+            description: transaction.description.clone(),
+            tags: transaction.tags.clone(),
+            metadata: transaction.metadata.clone(),
+            postings: postings(transaction)
+                .iter()
+                .enumerate()
+                .map(|(index, posting)| PostingReport {
+                    account: posting.account.to_string(),
+                    amount: posting.amount.as_ref().map(ReportAmount::from),
+                    automated: index >= 2,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{report, PostingReport};
+
+    use crate::parser::parse_journal;
+    use crate::report::ReportAmount;
+
+    fn amount(quantity: &str) -> Option<ReportAmount> {
+        Some(ReportAmount {
+            quantity: quantity.to_owned(),
+            currency: "EUR".to_owned(),
+        })
+    }
+
+    #[test]
+    fn reports_resolved_journal() {
+        let journal = parse_journal(
+            "y2020\nP 10-01 USD 0.85 EUR\n= acct:expenses:food\nassets:savings 0.1\n10-07 ! Tesco\nexpenses:food 25.30 EUR\nassets:bank\n",
+        )
+        .unwrap();
+
+        let report = report(journal);
+
+        assert_eq!(report.prices[0].date, "2020-10-01");
+        assert_eq!(report.transactions[0].date, "2020-10-07");
+        assert_eq!(report.transactions[0].status, "pending");
+        assert_eq!(
+            report.transactions[0].postings,
+            vec![
+                PostingReport {
+                    account: "expenses:food".to_owned(),
+                    amount: amount("25.30"),
+                    automated: false,
+                },
+                PostingReport {
+                    account: "assets:bank".to_owned(),
+                    amount: amount("-25.30"),
+                    automated: false,
+                },
+                PostingReport {
+                    account: "assets:savings".to_owned(),
+                    amount: amount("2.53"),
+                    automated: true,
+                },
+            ]
+        )
+    }
+}
@@ -0,0 +1,85 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::error::OverflowError;
+use crate::journal::resolved_postings;
+use crate::parser::ast::{Account, Amount, ParsedDate, Posting, Transaction, TransactionStatus};
+
+pub mod balance;
+pub mod check;
+pub mod gains;
+pub mod journal;
+pub mod networth;
+pub mod register;
+pub mod unrealized;
+
+/// Names of the root accounts of assets, which are compared ignoring case.
+pub(crate) const ASSET_ROOTS: [&str; 2] = ["asset", "assets"];
+/// Names of the root accounts of liabilities, which are compared ignoring case.
+pub(crate) const LIABILITY_ROOTS: [&str; 2] = ["liability", "liabilities"];
+/// Names of the root accounts of the balance sheet, which are compared ignoring case.
+pub(crate) const BALANCE_SHEET_ROOTS: [&str; 4] = [
+    ASSET_ROOTS[0],
+    ASSET_ROOTS[1],
+    LIABILITY_ROOTS[0],
+    LIABILITY_ROOTS[1],
+];
+/// Names of the root accounts of the income statement, which are compared ignoring case.
+pub(crate) const PROFIT_AND_LOSS_ROOTS: [&str; 5] =
+    ["income", "revenue", "revenues", "expense", "expenses"];
+
+/// Represents an amount in a report. The quantity is kept as a string so that its precision is not
+/// lost when serialising it.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct ReportAmount {
+    pub quantity: String,
+    pub currency: String,
+}
+
+impl From<&Amount> for ReportAmount {
+    fn from(amount: &Amount) -> Self {
+        Self {
+            quantity: amount.quantity.to_string(),
+            currency: amount.currency.clone(),
+        }
+    }
+}
+
+fn report_date(date: &ParsedDate) -> String {
+    date.date().format("%Y-%m-%d").to_string()
+}
+
+/// Adds the quantity to the total, failing when the sum is too large to be computed.
+pub(crate) fn add_to(total: &mut Decimal, quantity: Decimal) -> Result<(), OverflowError> {
+    *total = total.checked_add(quantity).ok_or(OverflowError)?;
+    Ok(())
+}
+
+pub(crate) fn report_status(status: &TransactionStatus) -> String {
+    match status {
+        TransactionStatus::Cleared => "cleared".to_owned(),
+        TransactionStatus::Pending => "pending".to_owned(),
+    }
+}
+
+/// Returns the postings of the transaction with their amounts resolved followed by the postings
+/// added by automated transactions.
+pub(crate) fn postings(transaction: &Transaction) -> Vec<Posting> {
+    let (first_posting, second_posting) = resolved_postings(transaction);
+
+    vec![first_posting, second_posting]
+        .into_iter()
+        .chain(transaction.automated_postings.iter().cloned())
+        .collect()
+}
+
+/// Returns whether the posting is to the account or one of its children.
+pub(crate) fn belongs_to(posting: &Posting, account: &str) -> bool {
+    let name = posting.account.to_string();
+    name == account || name.starts_with(&format!("{}:", account))
+}
+
+/// Returns whether the root of the account is one of the given roots, ignoring case.
+pub(crate) fn has_root(account: &Account, roots: &[&str]) -> bool {
+    roots.contains(&account.name.to_lowercase().as_str())
+}
@@ -0,0 +1,210 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::conversion::{checked_convert_to, Prices};
+use crate::error::OverflowError;
+use crate::parser::ast::{Amount, CurrencyCode, Transaction};
+use crate::period::Period;
+
+use super::{add_to, has_root, postings, ASSET_ROOTS, LIABILITY_ROOTS};
+
+/// Represents the assets, liabilities and net worth at the end of a day. Liabilities keep the sign
+/// of the journal, so they are usually negative and the net worth is their sum with the assets.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct NetWorthRow {
+    pub date: String,
+    pub assets: String,
+    pub liabilities: String,
+    pub net_worth: String,
+    pub currency: String,
+}
+
+/// Represents the net worth at several dates, together with the currencies that were left out
+/// because they had no price in the currency of the report.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct NetWorthReport {
+    pub rows: Vec<NetWorthRow>,
+    pub unpriced: Vec<String>,
+}
+
+/// Computes the net worth at the end of each of the given dates in the currency, valuing other
+/// currencies and commodities with the latest price known at every date. Returns an error if the
+/// net worth is too large to be computed.
+pub fn report(
+    transactions: &[Transaction],
+    prices: &Prices,
+    currency: &CurrencyCode,
+    dates: &[NaiveDate],
+) -> Result<NetWorthReport, OverflowError> {
+    let mut unpriced = BTreeSet::new();
+    let rows = dates
+        .iter()
+        .map(|date| {
+            let mut totals: BTreeMap<(bool, String), Decimal> = BTreeMap::new();
+
+            for transaction in transactions
+                .iter()
+                .filter(|transaction| transaction.date.date() <= *date)
+            {
+                for posting in postings(transaction) {
+                    let is_asset = has_root(&posting.account, &ASSET_ROOTS);
+                    let is_liability = has_root(&posting.account, &LIABILITY_ROOTS);
+
+                    if let Some(amount) = posting.amount.filter(|_| is_asset || is_liability) {
+                        add_to(
+                            totals.entry((is_asset, amount.currency)).or_default(),
+                            amount.quantity,
+                        )?;
+                    }
+                }
+            }
+
+            let (mut assets, mut liabilities) = (Decimal::ZERO, Decimal::ZERO);
+            for ((is_asset, origin), quantity) in totals {
+                let amount = Amount {
+                    quantity,
+                    currency: origin,
+                };
+
+                match checked_convert_to(currency, &amount, prices, *date)? {
+                    Some(valued) if is_asset => add_to(&mut assets, valued.quantity)?,
+                    Some(valued) => add_to(&mut liabilities, valued.quantity)?,
+                    None => {
+                        unpriced.insert(amount.currency);
+                    }
+                }
+            }
+
+            Ok(NetWorthRow {
+                date: date.format("%Y-%m-%d").to_string(),
+                assets: assets.to_string(),
+                liabilities: liabilities.to_string(),
+                net_worth: assets
+                    .checked_add(liabilities)
+                    .ok_or(OverflowError)?
+                    .to_string(),
+                currency: currency.clone(),
+            })
+        })
+        .collect::<Result<Vec<NetWorthRow>, OverflowError>>()?;
+
+    Ok(NetWorthReport {
+        rows,
+        unpriced: unpriced.into_iter().collect(),
+    })
+}
+
+/// Returns the last day of every month from the month of `from` to the month of `to`, ending at
+/// `to` rather than after it.
+pub fn month_ends(from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+    let last = Period::month_of(to);
+    let mut month = Period::month_of(from);
+    let mut ends = vec![];
+
+    if from > to {
+        return ends;
+    }
+
+    while let Some(end) = month.last_day() {
+        ends.push(end.min(to));
+
+        if month == last {
+            break;
+        }
+        month = month.next();
+    }
+
+    ends
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+
+    use super::{month_ends, report};
+
+    use crate::conversion::Prices;
+    use crate::error::OverflowError;
+    use crate::journal::resolve;
+    use crate::parser::parse_journal;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2021, month, day).unwrap()
+    }
+
+    #[test]
+    fn lists_the_end_of_every_month() {
+        assert_eq!(
+            month_ends(date(11, 15), NaiveDate::from_ymd_opt(2022, 2, 3).unwrap()),
+            vec![
+                date(11, 30),
+                date(12, 31),
+                NaiveDate::from_ymd_opt(2022, 1, 31).unwrap(),
+                NaiveDate::from_ymd_opt(2022, 2, 3).unwrap(),
+            ]
+        );
+        assert_eq!(month_ends(date(3, 2), date(3, 20)), vec![date(3, 20)]);
+        assert_eq!(
+            month_ends(date(3, 2), date(4, 30)),
+            vec![date(3, 31), date(4, 30)]
+        );
+    }
+
+    #[test]
+    fn values_assets_and_liabilities_at_every_date() {
+        let journal = parse_journal(
+            "P 2021-10-01 VWCE 100 EUR\n\
+             P 2021-11-01 VWCE 105 EUR\n\n\
+             2021-10-05 ACME\nincome:salary -2000 EUR\nassets:bank\n\n\
+             2021-10-07 Broker\nassets:broker 10 VWCE {95 EUR}\nassets:bank\n\n\
+             2021-11-10 Card\nexpenses:food 30 EUR\nLiabilities:Card\n\n\
+             2021-11-12 Shop\nexpenses:books 10 GBP\nassets:cash\n",
+        )
+        .unwrap();
+        let report = report(
+            &resolve(journal.clone()),
+            &Prices::from_journal(&journal),
+            &"EUR".to_owned(),
+            &[date(10, 31), date(11, 30)],
+        )
+        .unwrap();
+
+        assert_eq!(
+            report
+                .rows
+                .iter()
+                .map(|row| (
+                    row.assets.as_str(),
+                    row.liabilities.as_str(),
+                    row.net_worth.as_str()
+                ))
+                .collect::<Vec<(&str, &str, &str)>>(),
+            vec![("2050", "0", "2050"), ("2100", "-30", "2070")]
+        );
+        assert_eq!(report.unpriced, vec!["GBP".to_owned()]);
+    }
+
+    #[test]
+    fn fails_when_the_net_worth_is_too_large_to_be_computed() {
+        let journal = parse_journal(&format!(
+            "2021-10-05 ACME\nassets:bank {max} EUR\nincome:salary\n\n\
+             2021-10-06 ACME\nassets:cash {max} EUR\nincome:salary\n",
+            max = Decimal::MAX
+        ))
+        .unwrap();
+
+        assert_eq!(
+            report(
+                &resolve(journal.clone()),
+                &Prices::from_journal(&journal),
+                &"EUR".to_owned(),
+                &[date(10, 31)],
+            ),
+            Err(OverflowError)
+        );
+    }
+}
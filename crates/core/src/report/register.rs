@@ -0,0 +1,266 @@
+use std::collections::{BTreeMap, HashMap};
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::error::OverflowError;
+use crate::parser::ast::{CurrencyCode, Posting, Query, Transaction};
+use crate::period::Period;
+use crate::query::matches_posting;
+
+use super::{add_to, belongs_to, postings, report_date};
+
+/// Represents a posting in the register together with the running total of its currency.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct RegisterEntry {
+    pub date: String,
+    pub payee: String,
+    pub description: String,
+    pub account: String,
+    pub quantity: String,
+    pub currency: String,
+    pub total: String,
+    /// Whether the posting was added by an automated transaction.
+    pub automated: bool,
+}
+
+/// Represents the list of postings that matched the query ordered by date.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct RegisterReport {
+    pub entries: Vec<RegisterEntry>,
+}
+
+/// Lists the postings that match the query ordered by the date of their transaction, keeping a
+/// running total for each currency. Fails when a running total is too large to be computed.
+pub fn report(
+    transactions: &[Transaction],
+    query: &Query,
+) -> Result<RegisterReport, OverflowError> {
+    let mut transactions: Vec<&Transaction> = transactions.iter().collect();
+    transactions.sort_by_key(|transaction| transaction.date.date());
+
+    let mut totals: HashMap<String, Decimal> = HashMap::new();
+    let mut entries = Vec::new();
+
+    for transaction in transactions {
+        for (index, posting) in postings(transaction).into_iter().enumerate() {
+            let amount = match &posting.amount {
+                Some(amount) if matches_posting(query, transaction, &posting) => amount,
+                _ => continue,
+            };
+
+            let total = totals.entry(amount.currency.clone()).or_default();
+            add_to(total, amount.quantity)?;
+
+            entries.push(RegisterEntry {
+                date: report_date(&transaction.date),
+                payee: transaction.payee.clone(),
+                description: transaction.description.clone(),
+                account: posting.account.to_string(),
+                quantity: amount.quantity.to_string(),
+                currency: amount.currency.clone(),
+                total: total.to_string(),
+                automated: index >= 2,
+            });
+        }
+    }
+
+    Ok(RegisterReport { entries })
+}
+
+/// Represents a posting to an account together with the running total of every currency.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AccountEntry {
+    /// Position of the transaction of the posting among the given transactions.
+    pub index: usize,
+    pub transaction: Transaction,
+    pub posting: Posting,
+    pub totals: BTreeMap<CurrencyCode, Decimal>,
+}
+
+/// Lists the postings to the account or its children in the period that match the query, in the
+/// order of the transactions. Fails when a running total is too large to be computed.
+pub fn account_report(
+    transactions: &[Transaction],
+    account: &str,
+    period: Period,
+    query: &Query,
+) -> Result<Vec<AccountEntry>, OverflowError> {
+    let mut totals: BTreeMap<CurrencyCode, Decimal> = BTreeMap::new();
+    let mut entries = Vec::new();
+
+    for (index, transaction) in transactions
+        .iter()
+        .enumerate()
+        .filter(|(_, transaction)| period.contains(transaction.date.date()))
+    {
+        for posting in postings(transaction) {
+            if !belongs_to(&posting, account) || !matches_posting(query, transaction, &posting) {
+                continue;
+            }
+
+            if let Some(amount) = &posting.amount {
+                add_to(
+                    totals.entry(amount.currency.clone()).or_default(),
+                    amount.quantity,
+                )?;
+            }
+
+            entries.push(AccountEntry {
+                index,
+                transaction: transaction.clone(),
+                posting,
+                totals: totals.clone(),
+            });
+        }
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal_macros::dec;
+
+    use super::{account_report, report};
+
+    use crate::journal::resolve;
+    use crate::parser::ast::*;
+    use crate::parser::parse_journal;
+    use crate::period::Period;
+
+    #[test]
+    fn lists_matching_postings_by_date_with_running_total() {
+        let transactions = resolve(
+            parse_journal(
+                "2021-10-08 Tesco\nexpenses:food 10 EUR\nassets:bank\n\n\
+                 2021-10-07 Albert\nexpenses:food 25.30 EUR\nassets:bank\n",
+            )
+            .unwrap(),
+        );
+
+        let entries = report(
+            &transactions,
+            &Query(vec![QueryTerm::Account("expenses".to_owned())]),
+        )
+        .unwrap()
+        .entries;
+
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (
+                    entry.date.as_str(),
+                    entry.payee.as_str(),
+                    entry.quantity.as_str(),
+                    entry.total.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("2021-10-07", "Albert", "25.30", "25.30"),
+                ("2021-10-08", "Tesco", "10", "35.30"),
+            ]
+        )
+    }
+
+    #[test]
+    fn lists_both_postings_without_query() {
+        let transactions = resolve(
+            parse_journal("2021-10-07 Albert\nexpenses:food 25.30 EUR\nassets:bank\n").unwrap(),
+        );
+
+        let entries = report(&transactions, &Query(vec![])).unwrap().entries;
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].account, "assets:bank");
+        assert_eq!(entries[1].quantity, "-25.30");
+    }
+
+    #[test]
+    fn marks_the_postings_of_automated_transactions() {
+        let transactions = resolve(
+            parse_journal(
+                "= expenses:food\nassets:savings 0.1\nassets:bank -0.1\n\n\
+                 2021-10-07 Tesco\nexpenses:food 30 EUR\nassets:bank\n",
+            )
+            .unwrap(),
+        );
+
+        let entries = report(&transactions, &Query(vec![])).unwrap().entries;
+
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.account.as_str(), entry.automated))
+                .collect::<Vec<_>>(),
+            vec![
+                ("expenses:food", false),
+                ("assets:bank", false),
+                ("assets:savings", true),
+                ("assets:bank", true),
+            ]
+        )
+    }
+
+    #[test]
+    fn lists_the_postings_of_an_account_and_its_children_in_the_period() {
+        let transactions = resolve(
+            parse_journal(
+                "2021-09-30 Albert\nexpenses:food 5 EUR\nassets:bank\n\n\
+                 2021-10-07 Tesco\nexpenses:food 30 EUR\nassets:bank\n\n\
+                 2021-10-08 Shell\nexpenses:fuel 20 USD\nassets:bank\n\n\
+                 2021-10-09 Rent\nexpenses-rent 700 EUR\nassets:bank\n",
+            )
+            .unwrap(),
+        );
+
+        let entries = account_report(
+            &transactions,
+            "expenses",
+            Period::Month(2021, 10),
+            &Query(vec![]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.index, entry.posting.account.to_string()))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, "expenses:food".to_owned()),
+                (2, "expenses:fuel".to_owned())
+            ]
+        );
+        assert_eq!(
+            entries[1].totals.iter().collect::<Vec<_>>(),
+            vec![
+                (&"EUR".to_owned(), &dec!(30)),
+                (&"USD".to_owned(), &dec!(20))
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_the_postings_of_an_account_that_match_the_query() {
+        let transactions = resolve(
+            parse_journal(
+                "2021-10-07 Tesco\nexpenses:food 30 EUR\nassets:bank\n\n\
+                 2021-10-08 Albert\nexpenses:food 10 EUR\nassets:bank\n",
+            )
+            .unwrap(),
+        );
+
+        let entries = account_report(
+            &transactions,
+            "assets",
+            Period::All,
+            &Query(vec![QueryTerm::Payee("Albert".to_owned())]),
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].transaction.payee, "Albert");
+        assert_eq!(entries[0].totals["EUR"], dec!(-10));
+    }
+}
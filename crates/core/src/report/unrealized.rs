@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::conversion::{checked_convert_to, Prices};
+use crate::error::OverflowError;
+use crate::lots::Holding;
+use crate::parser::ast::Amount;
+
+use super::add_to;
+
+/// Represents a lot that is still held with its cost and its market value in the currency of the
+/// cost.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct HoldingRow {
+    pub account: String,
+    pub commodity: String,
+    pub quantity: String,
+    pub date: String,
+    pub cost: String,
+    pub market: String,
+    pub gain: String,
+    pub currency: String,
+}
+
+/// Represents the cost and market value of every lot held in an account in a currency.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct AccountRow {
+    pub account: String,
+    pub cost: String,
+    pub market: String,
+    pub gain: String,
+    pub currency: String,
+}
+
+/// Represents the gains that would be realized by selling every lot at its market value.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct UnrealizedReport {
+    pub holdings: Vec<HoldingRow>,
+    pub accounts: Vec<AccountRow>,
+}
+
+/// Compares the cost of every holding with its market value at the given date, for every holding
+/// and for every account. Holdings without a known price are valued at their cost. Returns an
+/// error if the values are too large to be computed.
+pub fn report(
+    holdings: &[Holding],
+    prices: &Prices,
+    date: NaiveDate,
+) -> Result<UnrealizedReport, OverflowError> {
+    let mut accounts: BTreeMap<(String, String), (Decimal, Decimal)> = BTreeMap::new();
+    let mut rows = vec![];
+
+    for holding in holdings {
+        let cost = holding
+            .quantity
+            .checked_mul(holding.cost.quantity)
+            .ok_or(OverflowError)?;
+        let market = checked_convert_to(
+            &holding.cost.currency,
+            &Amount {
+                quantity: holding.quantity,
+                currency: holding.commodity.clone(),
+            },
+            prices,
+            date,
+        )?
+        .map_or(cost, |market| market.quantity);
+
+        let totals = accounts
+            .entry((holding.account.clone(), holding.cost.currency.clone()))
+            .or_default();
+        add_to(&mut totals.0, cost)?;
+        add_to(&mut totals.1, market)?;
+
+        rows.push(HoldingRow {
+            account: holding.account.clone(),
+            commodity: holding.commodity.clone(),
+            quantity: holding.quantity.to_string(),
+            date: holding.date.format("%Y-%m-%d").to_string(),
+            cost: cost.to_string(),
+            market: market.to_string(),
+            gain: market.checked_sub(cost).ok_or(OverflowError)?.to_string(),
+            currency: holding.cost.currency.clone(),
+        });
+    }
+
+    Ok(UnrealizedReport {
+        holdings: rows,
+        accounts: accounts
+            .into_iter()
+            .map(|((account, currency), (cost, market))| {
+                Ok(AccountRow {
+                    account,
+                    cost: cost.to_string(),
+                    market: market.to_string(),
+                    gain: market.checked_sub(cost).ok_or(OverflowError)?.to_string(),
+                    currency,
+                })
+            })
+            .collect::<Result<Vec<AccountRow>, OverflowError>>()?,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    use super::report;
+
+    use crate::conversion::Prices;
+    use crate::error::OverflowError;
+    use crate::journal::resolve;
+    use crate::lots::{book, Booking, Holding};
+    use crate::parser::ast::Amount;
+    use crate::parser::parse_journal;
+
+    #[test]
+    fn compares_cost_with_market_value() {
+        let journal = parse_journal(
+            "P 2021-12-01 VWCE 105 EUR\n\n\
+             2021-10-07 Broker\nassets:broker 10 VWCE {95 EUR}\nassets:bank\n\n\
+             2021-11-07 Broker\nassets:broker 5 VWCE {100 EUR}\nassets:bank\n\n\
+             2021-11-08 Broker\nassets:broker 2 AAPL {150 EUR}\nassets:bank\n",
+        )
+        .unwrap();
+        let prices = Prices::from_journal(&journal);
+        let lots = book(&resolve(journal), Booking::Fifo).unwrap();
+        let report = report(
+            &lots.holdings,
+            &prices,
+            NaiveDate::from_ymd_opt(2021, 12, 31).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            report
+                .holdings
+                .iter()
+                .map(|row| (row.market.as_str(), row.gain.as_str()))
+                .collect::<Vec<(&str, &str)>>(),
+            vec![("1050", "100"), ("525", "25"), ("300", "0")]
+        );
+        assert_eq!(report.accounts.len(), 1);
+        assert_eq!(report.accounts[0].cost, "1750");
+        assert_eq!(report.accounts[0].market, "1875");
+        assert_eq!(report.accounts[0].gain, "125");
+    }
+
+    #[test]
+    fn fails_when_a_value_is_too_large_to_be_computed() {
+        let date = NaiveDate::from_ymd_opt(2021, 12, 31).unwrap();
+        let holding = Holding {
+            account: "assets:broker".to_owned(),
+            commodity: "VWCE".to_owned(),
+            quantity: dec!(10),
+            cost: Amount {
+                quantity: Decimal::MAX,
+                currency: "EUR".to_owned(),
+            },
+            date,
+        };
+
+        assert_eq!(
+            report(&[holding], &Prices::default(), date),
+            Err(OverflowError)
+        );
+    }
+}
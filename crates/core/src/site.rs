@@ -0,0 +1,336 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+
+use crate::chart::{self, svg, svg::escape, Interval};
+use crate::conversion::Prices;
+use crate::error::OverflowError;
+use crate::journal::{self, resolve};
+use crate::parser::ast::{CurrencyCode, JournalElement, Query, QueryTerm, Transaction};
+use crate::report::balance::{self, BalanceRow};
+use crate::report::register::{self, RegisterEntry};
+use crate::report::{BALANCE_SHEET_ROOTS, PROFIT_AND_LOSS_ROOTS};
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em auto;max-width:60em;color:#222}\
+                     nav a{margin-right:1em}table{border-collapse:collapse;margin:1em 0}\
+                     td,th{padding:.2em .8em;text-align:left;border-bottom:1px solid #eee}\
+                     .amount{text-align:right;font-family:monospace;white-space:nowrap}\
+                     .root{font-weight:bold}svg{max-width:100%;height:auto}";
+
+/// Represents a page of the static site, with its path relative to the directory of the site.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Page {
+    pub path: String,
+    pub content: String,
+}
+
+/// Builds a self-contained static site of the journal: an overview with charts valued in the
+/// currency, the balance sheet, the income statement and a page for every account, payee and tag.
+/// Every page is built from the same reports as the commands and links to the others. Fails when a
+/// balance is too large to be computed.
+pub fn build(
+    journal: Vec<JournalElement>,
+    currency: &CurrencyCode,
+) -> Result<Vec<Page>, OverflowError> {
+    let prices = Prices::from_journal(&journal);
+    let (payees, tags) = (journal::payees(&journal), journal::tags(&journal));
+    let transactions = resolve(journal);
+    let balance = balance::report(&transactions, &Query(vec![]))?;
+    let mut accounts: Vec<String> = balance.rows.iter().map(|row| row.account.clone()).collect();
+    accounts.dedup();
+
+    let links = Links {
+        accounts: paths("account", &accounts),
+        payees: paths("payee", &payees),
+        tags: paths("tag", &tags),
+    };
+    let rows_with_roots = |roots: &[&str]| {
+        balance
+            .rows
+            .iter()
+            .filter(|row| {
+                let root = row.account.split(':').next().unwrap_or_default();
+                roots.contains(&root.to_lowercase().as_str())
+            })
+            .collect::<Vec<&BalanceRow>>()
+    };
+    let balance_sheet_roots: Vec<&str> = BALANCE_SHEET_ROOTS
+        .iter()
+        .copied()
+        .chain(std::iter::once("equity"))
+        .collect();
+
+    let mut pages = vec![
+        page(
+            "index.html",
+            "Overview",
+            &overview(&transactions, &prices, currency)?,
+        ),
+        page(
+            "balance-sheet.html",
+            "Balance sheet",
+            &balance_table(&rows_with_roots(&balance_sheet_roots), &links),
+        ),
+        page(
+            "income-statement.html",
+            "Income statement",
+            &balance_table(&rows_with_roots(&PROFIT_AND_LOSS_ROOTS), &links),
+        ),
+        page("accounts.html", "Accounts", &list(&links.accounts)),
+        page("payees.html", "Payees", &list(&links.payees)),
+        page("tags.html", "Tags", &list(&links.tags)),
+    ];
+
+    for (account, path) in &links.accounts {
+        let query = Query(vec![QueryTerm::Account(account.clone())]);
+        let balances = chart::balances(
+            &transactions,
+            &query,
+            &prices,
+            currency,
+            Interval::Month,
+            false,
+        )?;
+        let mut content = String::new();
+
+        if !balances.periods.is_empty() {
+            content.push_str(&svg::line_chart(&balances));
+        }
+        content.push_str(&register_table(
+            &register::report(&transactions, &query)?.entries,
+            &links,
+        ));
+        pages.push(page(path, account, &content));
+    }
+    for (name, path) in &links.payees {
+        let query = Query(vec![QueryTerm::Payee(name.clone())]);
+        pages.push(page(path, name, &summary(&transactions, &query, &links)?));
+    }
+    for (name, path) in &links.tags {
+        let query = Query(vec![QueryTerm::Tag(name.clone())]);
+        pages.push(page(path, name, &summary(&transactions, &query, &links)?));
+    }
+
+    Ok(pages)
+}
+
+/// Paths of the pages of every account, payee and tag.
+struct Links {
+    accounts: BTreeMap<String, String>,
+    payees: BTreeMap<String, String>,
+    tags: BTreeMap<String, String>,
+}
+
+/// Links the name to its page, or returns the name if it has none.
+fn link(paths: &BTreeMap<String, String>, name: &str) -> String {
+    match paths.get(name) {
+        Some(path) => format!("<a href=\"{}\">{}</a>", escape(path), escape(name)),
+        None => escape(name),
+    }
+}
+
+/// Gives every name the path of its page, made of the prefix and the name with every character
+/// that is not alphanumeric replaced, numbering the names that would share a path.
+fn paths(prefix: &str, names: &[String]) -> BTreeMap<String, String> {
+    let mut used = HashSet::new();
+
+    names
+        .iter()
+        .map(|name| {
+            let slug: String = name
+                .chars()
+                .flat_map(char::to_lowercase)
+                .map(|c| if c.is_alphanumeric() { c } else { '-' })
+                .collect();
+            let mut path = format!("{}-{}.html", prefix, slug);
+            let mut number = 1;
+
+            while !used.insert(path.clone()) {
+                number += 1;
+                path = format!("{}-{}-{}.html", prefix, slug, number);
+            }
+
+            (name.clone(), path)
+        })
+        .collect()
+}
+
+fn overview(
+    transactions: &[Transaction],
+    prices: &Prices,
+    currency: &CurrencyCode,
+) -> Result<String, OverflowError> {
+    let expenses = chart::flows(
+        transactions,
+        &Query(vec![QueryTerm::Account("expenses".to_owned())]),
+        prices,
+        currency,
+        Interval::Month,
+        false,
+    )?;
+    let assets = chart::balances(
+        transactions,
+        &Query(vec![QueryTerm::Account("assets".to_owned())]),
+        prices,
+        currency,
+        Interval::Month,
+        false,
+    )?;
+    let mut content = String::new();
+
+    if !expenses.periods.is_empty() {
+        let _ = write!(
+            content,
+            "<h2>Monthly expenses</h2>{}",
+            svg::bar_chart(&expenses)
+        );
+    }
+    if !assets.periods.is_empty() {
+        let _ = write!(
+            content,
+            "<h2>Assets over time</h2>{}",
+            svg::line_chart(&assets)
+        );
+    }
+
+    Ok(content)
+}
+
+/// Lists the balance of the accounts of the postings that match the query, followed by the
+/// postings.
+fn summary(
+    transactions: &[Transaction],
+    query: &Query,
+    links: &Links,
+) -> Result<String, OverflowError> {
+    let balance = balance::report(transactions, query)?;
+
+    Ok(format!(
+        "{}{}",
+        balance_table(&balance.rows.iter().collect::<Vec<&BalanceRow>>(), links),
+        register_table(&register::report(transactions, query)?.entries, links)
+    ))
+}
+
+fn balance_table(rows: &[&BalanceRow], links: &Links) -> String {
+    let mut content =
+        "<table><tr><th>Account</th><th class=\"amount\">Balance</th></tr>\n".to_owned();
+
+    for row in rows {
+        let depth = row.account.matches(':').count();
+        let _ = writeln!(
+            content,
+            "<tr{}><td style=\"padding-left:{}em\">{}</td><td class=\"amount\">{} {}</td></tr>",
+            if depth == 0 { " class=\"root\"" } else { "" },
+            depth as f64 * 1.5 + 0.8,
+            link(&links.accounts, &row.account),
+            escape(&row.quantity),
+            escape(&row.currency)
+        );
+    }
+
+    content + "</table>\n"
+}
+
+fn register_table(entries: &[RegisterEntry], links: &Links) -> String {
+    let mut content = "<table><tr><th>Date</th><th>Payee</th><th>Description</th>\
+                       <th>Account</th><th class=\"amount\">Amount</th>\
+                       <th class=\"amount\">Total</th></tr>\n"
+        .to_owned();
+
+    for entry in entries {
+        let _ = writeln!(
+            content,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td>\
+             <td class=\"amount\">{} {}</td><td class=\"amount\">{} {}</td></tr>",
+            escape(&entry.date),
+            link(&links.payees, &entry.payee),
+            escape(&entry.description),
+            link(&links.accounts, &entry.account),
+            escape(&entry.quantity),
+            escape(&entry.currency),
+            escape(&entry.total),
+            escape(&entry.currency)
+        );
+    }
+
+    content + "</table>\n"
+}
+
+fn list(paths: &BTreeMap<String, String>) -> String {
+    let items: String = paths
+        .keys()
+        .map(|name| format!("<li>{}</li>\n", link(paths, name)))
+        .collect();
+
+    format!("<ul>\n{}</ul>\n", items)
+}
+
+fn page(path: &str, title: &str, content: &str) -> Page {
+    Page {
+        path: path.to_owned(),
+        content: format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>{}</style>\n</head>\n<body>\n<nav><a href=\"index.html\">Overview</a>\
+             <a href=\"balance-sheet.html\">Balance sheet</a>\
+             <a href=\"income-statement.html\">Income statement</a>\
+             <a href=\"accounts.html\">Accounts</a><a href=\"payees.html\">Payees</a>\
+             <a href=\"tags.html\">Tags</a></nav>\n<h1>{title}</h1>\n{}</body>\n</html>\n",
+            STYLE,
+            content,
+            title = escape(title)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{build, paths};
+
+    use crate::parser::parse_journal;
+
+    #[test]
+    fn numbers_names_that_share_a_path() {
+        let paths = paths(
+            "payee",
+            &[
+                "Tesco".to_owned(),
+                "tesco".to_owned(),
+                "Café & Co".to_owned(),
+            ],
+        );
+
+        assert_eq!(paths["Tesco"], "payee-tesco.html");
+        assert_eq!(paths["tesco"], "payee-tesco-2.html");
+        assert_eq!(paths["Café & Co"], "payee-café---co.html");
+    }
+
+    #[test]
+    fn builds_a_page_for_every_account_payee_and_tag() {
+        let pages = build(
+            parse_journal(
+                "2021-01-05 ACME\nincome:salary -1000 EUR\nassets:bank\n\n\
+                 2021-01-10 Tesco ;food\nexpenses:food 30 EUR\nassets:bank\n",
+            )
+            .unwrap(),
+            &"EUR".to_owned(),
+        )
+        .unwrap();
+        let page = |path: &str| {
+            pages
+                .iter()
+                .find(|page| page.path == path)
+                .map(|page| page.content.as_str())
+                .unwrap_or_else(|| panic!("There is no page {}", path))
+        };
+
+        assert_eq!(pages.len(), 6 + 6 + 2 + 1);
+        assert!(page("index.html").contains("<h2>Monthly expenses</h2><svg"));
+        assert!(page("balance-sheet.html")
+            .contains("<a href=\"account-assets-bank.html\">assets:bank</a>"));
+        assert!(!page("balance-sheet.html").contains("expenses:food"));
+        assert!(page("income-statement.html").contains("expenses:food"));
+        assert!(page("account-assets-bank.html")
+            .contains("<td class=\"amount\">-30 EUR</td><td class=\"amount\">970 EUR</td>"));
+        assert!(page("tag-food.html").contains("<a href=\"payee-tesco.html\">Tesco</a>"));
+    }
+}
@@ -0,0 +1,122 @@
+use crate::parser::ast::{Amount, ParsedDate, Posting, Transaction};
+
+/// Returns the most recent of the given transactions with the given payee, ignoring case. If
+/// several transactions happened on the same date, the last one in the journal is returned.
+pub fn find<'a>(transactions: &'a [Transaction], payee: &str) -> Option<&'a Transaction> {
+    let payee = payee.trim().to_lowercase();
+
+    transactions
+        .iter()
+        .filter(|transaction| transaction.payee.to_lowercase() == payee)
+        .max_by_key(|transaction| transaction.date.date())
+}
+
+/// Creates a new transaction from the given one, keeping its payee, description, tags and
+/// accounts but with the given date. The first posting takes the given amount, or keeps its own if
+/// none is given, and the second posting is left without amount so that it balances the first
+/// one. Metadata is not kept since it usually identifies the original transaction.
+pub fn apply(template: &Transaction, date: ParsedDate, amount: Option<Amount>) -> Transaction {
+    let (first_posting, second_posting) = &template.postings;
+    let amount = amount.or_else(|| first_posting.amount.clone()).or_else(|| {
+        second_posting.amount.as_ref().map(|amount| Amount {
+            quantity: -amount.quantity,
+            currency: amount.currency.clone(),
+        })
+    });
+
+    Transaction {
+        date,
+        payee: template.payee.clone(),
+        description: template.description.clone(),
+        tags: template.tags.clone(),
+        postings: (
+            Posting {
+                account: first_posting.account.clone(),
+                amount,
+                lot: None,
+                price: None,
+            },
+            Posting {
+                account: second_posting.account.clone(),
+                amount: None,
+                lot: None,
+                price: None,
+            },
+        ),
+        ..Transaction::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    use super::{apply, find};
+
+    use crate::journal::resolve;
+    use crate::parser::ast::*;
+    use crate::parser::parse_journal;
+
+    fn transactions() -> Vec<Transaction> {
+        resolve(
+            parse_journal(
+                "2021-10-07 Albert | Groceries ;food\nexpenses:food 10 EUR\nassets:bank\n\n\
+                 2021-10-14 Albert | Weekly groceries ;food ;import-id:ABC\nexpenses:food 12 EUR\nassets:bank\n\n\
+                 2021-10-09 Albert\nexpenses:home 5 EUR\nassets:bank\n\n\
+                 2021-10-20 Tesco\nexpenses:food 30 EUR\nassets:bank\n",
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn finds_most_recent_transaction_of_payee_ignoring_case() {
+        let transactions = transactions();
+
+        assert_eq!(
+            find(&transactions, "albert").map(|transaction| transaction.description.as_str()),
+            Some("Weekly groceries")
+        );
+        assert_eq!(find(&transactions, "Aldi"), None);
+    }
+
+    #[test]
+    fn applies_template_with_new_date_and_amount() {
+        let transactions = transactions();
+        let template = find(&transactions, "Albert").unwrap();
+        let date = ParsedDate::Full(NaiveDate::from_ymd_opt(2021, 10, 21).unwrap());
+        let amount = Amount {
+            quantity: dec!(15.30),
+            currency: "EUR".to_owned(),
+        };
+
+        let transaction = apply(template, date.clone(), Some(amount.clone()));
+
+        assert_eq!(transaction.date, date);
+        assert_eq!(transaction.description, "Weekly groceries");
+        assert_eq!(transaction.tags, vec!["food".to_owned()]);
+        assert!(transaction.metadata.is_empty());
+        assert_eq!(transaction.postings.0.account.to_string(), "expenses:food");
+        assert_eq!(transaction.postings.0.amount, Some(amount));
+        assert_eq!(transaction.postings.1.account.to_string(), "assets:bank");
+        assert_eq!(transaction.postings.1.amount, None);
+    }
+
+    #[test]
+    fn applies_template_keeping_its_amount_when_none_given() {
+        let transactions = transactions();
+        let template = find(&transactions, "Tesco").unwrap();
+
+        assert_eq!(
+            apply(template, template.date.clone(), None)
+                .postings
+                .0
+                .amount,
+            Some(Amount {
+                quantity: dec!(30),
+                currency: "EUR".to_owned(),
+            })
+        );
+    }
+}
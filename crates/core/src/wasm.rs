@@ -0,0 +1,148 @@
+use std::io;
+
+use js_sys::Function;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::error::IoError;
+use crate::journal::diagnose_with;
+use crate::report::check::{self, CheckMessage};
+use crate::report::journal::TransactionReport;
+use crate::Journal;
+
+/// Represents the result of parsing a journal, which has the transactions if it could be parsed
+/// or the messages that describe why it could not otherwise.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+struct ParseReport {
+    transactions: Vec<TransactionReport>,
+    messages: Vec<CheckMessage>,
+}
+
+/// Parses the journal in the given content, returning as JSON its resolved transactions and the
+/// messages of the check if it can't be parsed. Included journals are read with the `resolve`
+/// function, which is given their path and returns their content, or null if they don't exist.
+#[wasm_bindgen]
+pub fn parse(content: &str, resolve: Option<Function>) -> Result<String, JsError> {
+    let mut read = reader(resolve);
+    let report = match Journal::parse_with(content, &mut read) {
+        Ok(journal) => ParseReport {
+            transactions: journal
+                .transactions()
+                .iter()
+                .map(TransactionReport::from)
+                .collect(),
+            messages: vec![],
+        },
+        Err(_) => ParseReport {
+            transactions: vec![],
+            messages: check::report(&diagnose_with(content, "", &mut read)).messages,
+        },
+    };
+
+    serde_json::to_string(&report).map_err(|err| JsError::new(&err.to_string()))
+}
+
+/// Checks the journal in the given content like the `check` command, returning as JSON whether it
+/// is valid and the messages of every problem with its location. Included journals are read with
+/// the `resolve` function like in `parse`.
+#[wasm_bindgen]
+pub fn check(content: &str, resolve: Option<Function>) -> Result<String, JsError> {
+    let report = check::report(&diagnose_with(content, "", &mut reader(resolve)));
+
+    serde_json::to_string(&report).map_err(|err| JsError::new(&err.to_string()))
+}
+
+/// Returns a function that reads the included journals by calling the JavaScript function, so
+/// that the file system is never accessed. Journals can't be read if there is no function.
+fn reader(resolve: Option<Function>) -> impl FnMut(&str) -> Result<String, IoError> {
+    move |path| {
+        let result = match &resolve {
+            Some(resolve) => resolve.call1(&JsValue::NULL, &JsValue::from_str(path)),
+            None => Ok(JsValue::NULL),
+        };
+
+        match result {
+            Ok(content) => content
+                .as_string()
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound)),
+            Err(exception) => Err(io::Error::other(
+                exception
+                    .dyn_ref::<js_sys::Error>()
+                    .map(|error| String::from(error.message()))
+                    .or_else(|| exception.as_string())
+                    .unwrap_or_else(|| "the resolve function failed".to_owned()),
+            )),
+        }
+        .map_err(|cause| IoError::new(path, cause))
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod test {
+    use js_sys::Function;
+    use serde_json::Value;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::{check, parse};
+
+    fn resolve() -> Option<Function> {
+        Some(Function::new_with_args(
+            "path",
+            "if (path === 'broken.journal') throw new Error('permission denied');\n\
+             const journals = {\n\
+               'prices.journal': 'P 2021-10-01 USD 0.9 EUR\\n',\n\
+               'shop.journal': '2021-10-06 Shop\\nexpenses 5 EUR\\nassets -4 EUR\\n',\n\
+             };\n\
+             return journals[path] ?? null;",
+        ))
+    }
+
+    fn json(result: Result<String, super::JsError>) -> Value {
+        serde_json::from_str(&result.ok().unwrap()).unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn parses_transactions_with_included_journals() {
+        let report = json(parse(
+            "include prices.journal\n\n2021-10-05 Tesco\nexpenses 30 EUR\nassets\n",
+            resolve(),
+        ));
+
+        assert_eq!(report["transactions"][0]["payee"], "Tesco");
+        assert_eq!(report["messages"], Value::Array(vec![]));
+    }
+
+    #[wasm_bindgen_test]
+    fn returns_messages_when_the_journal_cant_be_parsed() {
+        let report = json(parse("2021-10-05 Tesco\n", None));
+
+        assert_eq!(report["transactions"], Value::Array(vec![]));
+        assert_eq!(report["messages"][0]["code"], "P001");
+        assert_eq!(report["messages"][0]["line"], 2);
+    }
+
+    #[wasm_bindgen_test]
+    fn checks_journals_and_their_includes() {
+        let report = json(check(
+            "include missing.journal\n\ninclude broken.journal\n\ninclude shop.journal\n\n\
+             2021-10-05 Tesco\nexpenses 30 EUR\nassets 20 EUR\n",
+            resolve(),
+        ));
+        let codes: Vec<&Value> = report["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|message| &message["code"])
+            .collect();
+
+        assert_eq!(report["valid"], false);
+        assert_eq!(codes, vec!["P002", "P002", "V002", "V002"]);
+        assert_eq!(report["messages"][2]["path"], "shop.journal");
+        assert_eq!(report["messages"][3]["path"], Value::Null);
+        assert!(report["messages"][1]["message"]
+            .as_str()
+            .unwrap()
+            .contains("permission denied"));
+    }
+}
@@ -0,0 +1,20 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pledger::journal;
+use pledger::parser::{self, ast::Query};
+use pledger::report::balance;
+
+// Parsing, checking and reporting must return an error instead of panicking, whatever the content
+// is.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(content) = std::str::from_utf8(data) {
+        let _ = parser::parse_journal_with_spans(content);
+        let _ = journal::validate(content);
+        let _ = journal::diagnose(content);
+
+        if let Ok(elements) = parser::parse_journal(content) {
+            let _ = balance::report(&journal::resolve(elements), &Query(vec![]));
+        }
+    }
+});